@@ -580,8 +580,16 @@ impl BlockAcquisitionState {
                     header.block_hash()
                 );
                 let deploy_hashes = block.deploy_and_transfer_hashes().copied().collect();
+                // The block's approvals hashes are only acquired once we have the block, so its
+                // deploys are acquired by hash until they are registered.
                 let deploy_acquisition =
                     DeployAcquisition::new_by_hash(deploy_hashes, need_execution_state);
+                debug!(
+                    total_deploys = deploy_acquisition.total_deploys(),
+                    requires_execution_result = deploy_acquisition.requires_execution_result(),
+                    "BlockAcquisition: acquiring deploys for: {}",
+                    header.block_hash()
+                );
 
                 BlockAcquisitionState::HaveBlock(
                     Box::new(block.clone()),
@@ -1045,10 +1053,15 @@ impl BlockAcquisitionState {
                         }
                         ExecutionResultsAcquisition::Complete { ref results, .. } => {
                             debug!("apply_block_execution_results_or_chunk: Complete");
+                            let mut deploys = deploys.clone();
+                            for deploy_hash in results.keys() {
+                                let _ = deploys.apply_execution_result(*deploy_hash);
+                            }
+                            debug!(%deploys, "BlockAcquisition: execution results held");
                             let new_state = BlockAcquisitionState::HaveGlobalState(
                                 block.clone(),
                                 signatures.clone(),
-                                deploys.clone(),
+                                deploys,
                                 new_acquisition.clone(),
                             );
                             let maybe_exec_results = Some(results.clone());
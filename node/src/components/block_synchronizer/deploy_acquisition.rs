@@ -3,7 +3,8 @@ mod tests;
 
 use std::{
     cmp::Ord,
-    fmt::{Display, Formatter},
+    collections::BTreeSet,
+    fmt::{self, Display, Formatter},
 };
 
 use datasize::DataSize;
@@ -16,6 +17,7 @@ use crate::types::{ApprovalsHashes, DeployHash, DeployId};
 pub(crate) enum Error {
     AcquisitionByIdNotPossible,
     EncounteredNonVacantDeployState,
+    MismatchedApprovalsHashesCount { expected: usize, actual: usize },
 }
 
 impl Display for Error {
@@ -25,6 +27,11 @@ impl Display for Error {
             Error::EncounteredNonVacantDeployState => {
                 write!(f, "encountered non vacant deploy state")
             }
+            Error::MismatchedApprovalsHashesCount { expected, actual } => write!(
+                f,
+                "mismatched approvals hashes count: expected {}, got {}",
+                expected, actual
+            ),
         }
     }
 }
@@ -43,42 +50,91 @@ pub(super) enum DeployAcquisition {
 
 impl DeployAcquisition {
     pub(super) fn new_by_hash(deploy_hashes: Vec<DeployHash>, need_execution_result: bool) -> Self {
-        DeployAcquisition::ByHash(Acquisition::new(deploy_hashes, need_execution_result))
+        deploy_hashes
+            .into_iter()
+            .map(|deploy_hash| (deploy_hash, need_execution_result))
+            .collect()
     }
 
     pub(super) fn apply_deploy(&mut self, deploy_id: DeployId) -> Option<Acceptance> {
-        match self {
+        let acceptance = match self {
             DeployAcquisition::ByHash(acquisition) => {
                 acquisition.apply_deploy(*deploy_id.deploy_hash())
             }
             DeployAcquisition::ById(acquisition) => acquisition.apply_deploy(deploy_id),
+        };
+        self.check_consistency();
+        acceptance
+    }
+
+    /// Records that the execution result of the given deploy is held.
+    ///
+    /// Returns `false` if the deploy is not part of this acquisition or its execution result is not
+    /// needed.
+    pub(super) fn apply_execution_result(&mut self, deploy_hash: DeployHash) -> bool {
+        match self {
+            DeployAcquisition::ByHash(acquisition) => {
+                acquisition.apply_execution_result(|hash| *hash == deploy_hash)
+            }
+            DeployAcquisition::ById(acquisition) => acquisition
+                .apply_execution_result(|deploy_id| *deploy_id.deploy_hash() == deploy_hash),
         }
     }
 
+    /// Switches to acquiring deploys by ID, pairing each deploy hash with the approvals hash at the
+    /// same position.  The block's deploy order is preserved.
+    ///
+    /// Returns an error without processing them unless there is exactly one approvals hash per
+    /// deploy.
     pub(super) fn apply_approvals_hashes(
         &mut self,
         approvals_hashes: &ApprovalsHashes,
     ) -> Result<(), Error> {
+        let original_order = if cfg!(debug_assertions) {
+            self.deploy_hashes()
+        } else {
+            vec![]
+        };
         let new_acquisition = match self {
             DeployAcquisition::ByHash(acquisition) => {
-                let mut new_deploy_ids = vec![];
-                for ((deploy_hash, deploy_state), approvals_hash) in acquisition
+                // The approvals hashes come from a peer, so reject any not pairing up with the
+                // deploys before processing them.
+                let approvals_hashes_count = approvals_hashes.approvals_hashes().len();
+                if approvals_hashes_count != acquisition.inner.len() {
+                    debug!(
+                        expected = acquisition.inner.len(),
+                        actual = approvals_hashes_count,
+                        "DeployAcquisition: mismatched approvals hashes count"
+                    );
+                    return Err(Error::MismatchedApprovalsHashesCount {
+                        expected: acquisition.inner.len(),
+                        actual: approvals_hashes_count,
+                    });
+                }
+                // Check every deploy to be converted before changing any state, so that an error
+                // leaves the acquisition as it was.
+                if acquisition
                     .inner
-                    .drain(..)
-                    .zip(approvals_hashes.approvals_hashes())
+                    .iter()
+                    .any(|(_, deploy_state)| *deploy_state == DeployState::HaveDeployBody)
                 {
-                    if !matches!(deploy_state, DeployState::Vacant) {
-                        return Err(Error::EncounteredNonVacantDeployState);
-                    };
-                    new_deploy_ids.push((
-                        DeployId::new(deploy_hash, *approvals_hash),
-                        DeployState::Vacant,
-                    ));
+                    return Err(Error::EncounteredNonVacantDeployState);
                 }
+                let new_deploy_ids: Vec<_> = acquisition
+                    .inner
+                    .drain(..)
+                    .zip(approvals_hashes.approvals_hashes())
+                    .map(|((deploy_hash, deploy_state), approvals_hash)| {
+                        (DeployId::new(deploy_hash, *approvals_hash), deploy_state)
+                    })
+                    .collect();
 
                 DeployAcquisition::ById(Acquisition {
                     inner: new_deploy_ids,
-                    need_execution_result: acquisition.need_execution_result,
+                    original_deploy_count: acquisition.original_deploy_count,
+                    approvals_applied: true,
+                    execution_results: std::mem::take(&mut acquisition.execution_results),
+                    checked_states: std::mem::take(&mut acquisition.checked_states),
                 })
             }
             DeployAcquisition::ById(_) => {
@@ -88,15 +144,111 @@ impl DeployAcquisition {
         };
 
         *self = new_acquisition;
+        debug_assert_eq!(
+            original_order,
+            self.deploy_hashes(),
+            "applying approvals hashes changed the deploy order"
+        );
+        self.check_consistency();
         Ok(())
     }
 
+    /// Panics if any of the acquisition's invariants are violated:
+    ///   * no deploy is tracked more than once
+    ///   * acquisition is by ID only once approvals hashes have been applied
+    ///   * no deploy's state has moved backwards since the previous check
+    ///   * the number of tracked deploys matches the number the acquisition was created with
+    ///   * every tracked deploy has an execution result state
+    ///
+    /// Only checked when `debug_assertions` are enabled.
+    pub(super) fn debug_assert_consistent(&self) {
+        match self {
+            DeployAcquisition::ByHash(acq) => {
+                debug_assert!(
+                    !acq.approvals_applied,
+                    "acquisition by hash after approvals hashes were applied"
+                );
+                acq.debug_assert_consistent();
+            }
+            DeployAcquisition::ById(acq) => {
+                debug_assert!(
+                    acq.approvals_applied,
+                    "acquisition by id without approvals hashes having been applied"
+                );
+                acq.debug_assert_consistent();
+            }
+        }
+    }
+
+    /// Asserts the acquisition is consistent, then records the current deploy states against
+    /// which the next check is made.
+    fn check_consistency(&mut self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        self.debug_assert_consistent();
+        match self {
+            DeployAcquisition::ByHash(acq) => acq.record_checked_states(),
+            DeployAcquisition::ById(acq) => acq.record_checked_states(),
+        }
+    }
+
     pub(super) fn needs_deploy(&self) -> Option<DeployIdentifier> {
         match self {
             DeployAcquisition::ByHash(acq) => acq.needs_deploy().map(DeployIdentifier::ByHash),
             DeployAcquisition::ById(acq) => acq.needs_deploy().map(DeployIdentifier::ById),
         }
     }
+
+    /// Returns the hashes of the deploys covered by this acquisition.
+    ///
+    /// These are in the same order as the deploys in the block, both before and after approvals
+    /// hashes are applied, so can be matched positionally against the block's deploy list.
+    pub(super) fn deploy_hashes(&self) -> Vec<DeployHash> {
+        match self {
+            DeployAcquisition::ByHash(acq) => acq.inner.iter().map(|(hash, _)| *hash).collect(),
+            DeployAcquisition::ById(acq) => acq
+                .inner
+                .iter()
+                .map(|(deploy_id, _)| *deploy_id.deploy_hash())
+                .collect(),
+        }
+    }
+
+    /// Returns the number of deploys covered by this acquisition.
+    pub(super) fn total_deploys(&self) -> usize {
+        match self {
+            DeployAcquisition::ByHash(acq) => acq.inner.len(),
+            DeployAcquisition::ById(acq) => acq.inner.len(),
+        }
+    }
+
+    /// Returns whether execution results are required for any of the deploys.
+    pub(super) fn requires_execution_result(&self) -> bool {
+        match self {
+            DeployAcquisition::ByHash(acq) => acq.requires_execution_result(),
+            DeployAcquisition::ById(acq) => acq.requires_execution_result(),
+        }
+    }
+}
+
+/// Constructs an acquisition by hash from deploy hashes in block order, each paired with whether
+/// that deploy's execution result is needed.
+impl FromIterator<(DeployHash, bool)> for DeployAcquisition {
+    fn from_iter<I: IntoIterator<Item = (DeployHash, bool)>>(iter: I) -> Self {
+        DeployAcquisition::ByHash(Acquisition::new(iter))
+    }
+}
+
+/// A one-line summary of the acquisition's progress for log lines, e.g.
+/// `by-id 412/500 bodies, 300/500 results`.  The results are omitted if none are needed.
+impl Display for DeployAcquisition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DeployAcquisition::ByHash(acq) => write!(f, "by-hash {}", acq),
+            DeployAcquisition::ById(acq) => write!(f, "by-id {}", acq),
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, DataSize, Debug, Default)]
@@ -106,22 +258,100 @@ pub(super) enum DeployState {
     HaveDeployBody,
 }
 
+impl DeployState {
+    /// Returns whether a deploy in this state may transition to `next`.  A held deploy never
+    /// changes state.
+    fn can_advance_to(self, next: DeployState) -> bool {
+        match (self, next) {
+            (DeployState::Vacant, _)
+            | (DeployState::HaveDeployBody, DeployState::HaveDeployBody) => true,
+            (DeployState::HaveDeployBody, DeployState::Vacant) => false,
+        }
+    }
+}
+
+/// Whether the execution result of a deploy is needed, and if so, whether it is held yet.
+#[derive(Clone, Copy, PartialEq, Eq, DataSize, Debug)]
+pub(super) enum ExecutionResultState {
+    NotNeeded,
+    Needed,
+    Held,
+}
+
 #[derive(Clone, PartialEq, Eq, DataSize, Debug)]
 pub(super) struct Acquisition<T> {
     inner: Vec<(T, DeployState)>,
-    need_execution_result: bool,
+    /// The number of deploys the acquisition was created with.
+    original_deploy_count: usize,
+    /// Whether `inner` was derived from approvals hashes.
+    approvals_applied: bool,
+    /// The execution result state of each deploy, in the same order as `inner`.
+    execution_results: Vec<ExecutionResultState>,
+    /// The deploy states as of the last consistency check.  Only populated when
+    /// `debug_assertions` are enabled.
+    checked_states: Vec<DeployState>,
 }
 
 impl<T: Copy + Ord> Acquisition<T> {
-    fn new(deploy_identifiers: Vec<T>, need_execution_result: bool) -> Self {
-        let inner = deploy_identifiers
+    fn new<I: IntoIterator<Item = (T, bool)>>(deploy_identifiers: I) -> Self {
+        let (inner, execution_results): (Vec<_>, Vec<_>) = deploy_identifiers
             .into_iter()
-            .map(|deploy_identifier| (deploy_identifier, DeployState::Vacant))
-            .collect();
-        Acquisition {
+            .map(|(deploy_identifier, need_execution_result)| {
+                let execution_result_state = if need_execution_result {
+                    ExecutionResultState::Needed
+                } else {
+                    ExecutionResultState::NotNeeded
+                };
+                (
+                    (deploy_identifier, DeployState::Vacant),
+                    execution_result_state,
+                )
+            })
+            .unzip();
+        let mut acquisition = Acquisition {
+            original_deploy_count: inner.len(),
             inner,
-            need_execution_result,
+            approvals_applied: false,
+            execution_results,
+            checked_states: vec![],
+        };
+        if cfg!(debug_assertions) {
+            acquisition.record_checked_states();
         }
+        acquisition
+    }
+
+    fn debug_assert_consistent(&self) {
+        debug_assert_eq!(
+            self.inner.len(),
+            self.original_deploy_count,
+            "tracked deploy count changed"
+        );
+        debug_assert_eq!(
+            self.inner
+                .iter()
+                .map(|(deploy_identifier, _)| deploy_identifier)
+                .collect::<BTreeSet<_>>()
+                .len(),
+            self.inner.len(),
+            "duplicate deploy identifier"
+        );
+        debug_assert_eq!(
+            self.execution_results.len(),
+            self.inner.len(),
+            "execution result states don't match tracked deploys"
+        );
+        debug_assert!(
+            self.inner
+                .iter()
+                .zip(&self.checked_states)
+                .all(|((_, state), checked_state)| checked_state.can_advance_to(*state)),
+            "deploy state moved backwards"
+        );
+    }
+
+    fn record_checked_states(&mut self) {
+        self.checked_states = self.inner.iter().map(|(_, state)| *state).collect();
     }
 
     fn apply_deploy(&mut self, deploy_identifier: T) -> Option<Acceptance> {
@@ -139,6 +369,32 @@ impl<T: Copy + Ord> Acquisition<T> {
         None
     }
 
+    /// Marks the needed execution result of the first identifier matching `predicate` as held.
+    ///
+    /// Returns `true` if such an identifier was found.
+    fn apply_execution_result<F: Fn(&T) -> bool>(&mut self, predicate: F) -> bool {
+        match self
+            .inner
+            .iter()
+            .zip(self.execution_results.iter_mut())
+            .find(|((deploy_identifier, _), _)| predicate(deploy_identifier))
+        {
+            Some((_, execution_result_state))
+                if *execution_result_state == ExecutionResultState::Needed =>
+            {
+                *execution_result_state = ExecutionResultState::Held;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn requires_execution_result(&self) -> bool {
+        self.execution_results.iter().any(|execution_result_state| {
+            *execution_result_state != ExecutionResultState::NotNeeded
+        })
+    }
+
     fn needs_deploy(&self) -> Option<T> {
         self.inner
             .iter()
@@ -148,3 +404,35 @@ impl<T: Copy + Ord> Acquisition<T> {
             })
     }
 }
+
+impl<T> Display for Acquisition<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let count_states = |wanted: DeployState| {
+            self.inner
+                .iter()
+                .filter(|(_, state)| *state == wanted)
+                .count()
+        };
+        write!(
+            f,
+            "{}/{} bodies",
+            count_states(DeployState::HaveDeployBody),
+            self.inner.len()
+        )?;
+        let (mut held_results, mut needed_results) = (0, 0);
+        for execution_result_state in &self.execution_results {
+            match execution_result_state {
+                ExecutionResultState::NotNeeded => (),
+                ExecutionResultState::Needed => needed_results += 1,
+                ExecutionResultState::Held => {
+                    held_results += 1;
+                    needed_results += 1;
+                }
+            }
+        }
+        if needed_results > 0 {
+            write!(f, ", {}/{} results", held_results, needed_results)?;
+        }
+        Ok(())
+    }
+}
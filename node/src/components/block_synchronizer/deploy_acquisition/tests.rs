@@ -1,13 +1,31 @@
 use std::collections::{BTreeMap, VecDeque};
 
-use crate::types::{Block, Deploy};
+use crate::types::{ApprovalsHash, Block, Deploy};
 use assert_matches::assert_matches;
 use casper_execution_engine::storage::trie::merkle_proof::TrieMerkleProof;
 use casper_types::{testing::TestRng, AccessRights, CLValue, StoredValue, URef};
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
 
 use super::*;
 
+impl DeployAcquisition {
+    /// Overwrites the state of the given deploy without any of the usual checks.
+    fn force_deploy_state(&mut self, deploy_hash: &DeployHash, new_state: DeployState) {
+        match self {
+            DeployAcquisition::ByHash(acq) => acq
+                .inner
+                .iter_mut()
+                .filter(|(hash, _)| hash == deploy_hash)
+                .for_each(|(_, state)| *state = new_state),
+            DeployAcquisition::ById(acq) => acq
+                .inner
+                .iter_mut()
+                .filter(|(deploy_id, _)| deploy_id.deploy_hash() == deploy_hash)
+                .for_each(|(_, state)| *state = new_state),
+        }
+    }
+}
+
 fn gen_test_deploys(rng: &mut TestRng) -> BTreeMap<DeployHash, Deploy> {
     let num_deploys = rng.gen_range(2..15);
     (0..num_deploys)
@@ -43,13 +61,12 @@ fn dont_apply_approvals_hashes_when_acquiring_by_id() {
     let approvals_hashes = gen_approvals_hashes(&mut rng, test_deploys.values());
 
     let mut deploy_acquisition = DeployAcquisition::ById(Acquisition::new(
-        test_deploys
-            .iter()
-            .map(|(deploy_hash, deploy)| {
-                DeployId::new(*deploy_hash, deploy.approvals_hash().unwrap())
-            })
-            .collect(),
-        false,
+        test_deploys.iter().map(|(deploy_hash, deploy)| {
+            (
+                DeployId::new(*deploy_hash, deploy.approvals_hash().unwrap()),
+                false,
+            )
+        }),
     ));
 
     assert_matches!(
@@ -69,9 +86,7 @@ fn apply_approvals_on_acquisition_by_hash_creates_correct_ids() {
     let mut deploy_acquisition =
         DeployAcquisition::new_by_hash(test_deploys.keys().copied().collect(), false);
 
-    // Generate the ApprovalsHashes for all test deploys except the last one
-    let approvals_hashes =
-        gen_approvals_hashes(&mut rng, test_deploys.values().take(test_deploys.len() - 1));
+    let approvals_hashes = gen_approvals_hashes(&mut rng, test_deploys.values());
 
     assert_matches!(
         deploy_acquisition.needs_deploy().unwrap(),
@@ -88,25 +103,78 @@ fn apply_approvals_on_acquisition_by_hash_creates_correct_ids() {
     );
 
     // Apply the deploys
-    for (deploy_hash, deploy) in test_deploys.iter().take(test_deploys.len() - 1) {
+    for (deploy_hash, deploy) in test_deploys.iter() {
         let acceptance = deploy_acquisition.apply_deploy(DeployId::new(
             *deploy_hash,
             deploy.approvals_hash().unwrap(),
         ));
         assert_matches!(acceptance, Some(Acceptance::NeededIt));
     }
-
-    // The last deploy was excluded from acquisition when we applied the approvals hashes so it
-    // should not be needed
     assert!(deploy_acquisition.needs_deploy().is_none());
+}
 
-    // Try to apply the last deploy; it should not be accepted
-    let last_deploy = test_deploys.values().last().unwrap();
-    let last_deploy_acceptance = deploy_acquisition.apply_deploy(DeployId::new(
-        *last_deploy.hash(),
-        last_deploy.approvals_hash().unwrap(),
-    ));
-    assert_matches!(last_deploy_acceptance, None);
+#[test]
+fn reject_undersized_approvals_hashes() {
+    let mut rng = TestRng::new();
+    let test_deploys = gen_test_deploys(&mut rng);
+    let mut deploy_acquisition =
+        DeployAcquisition::new_by_hash(test_deploys.keys().copied().collect(), false);
+
+    // Generate the approvals hashes for all test deploys except the last one.
+    let approvals_hashes =
+        gen_approvals_hashes(&mut rng, test_deploys.values().take(test_deploys.len() - 1));
+
+    assert_matches!(
+        deploy_acquisition.apply_approvals_hashes(&approvals_hashes),
+        Err(Error::MismatchedApprovalsHashesCount { expected, actual })
+            if expected == test_deploys.len() && actual == test_deploys.len() - 1
+    );
+
+    // The acquisition should be left untouched, still acquiring every deploy by hash.
+    assert_eq!(
+        deploy_acquisition.deploy_hashes(),
+        test_deploys.keys().copied().collect::<Vec<_>>()
+    );
+    assert_matches!(
+        deploy_acquisition.needs_deploy().unwrap(),
+        DeployIdentifier::ByHash(hash) if test_deploys.contains_key(&hash)
+    );
+}
+
+#[test]
+fn reject_oversized_approvals_hashes() {
+    let mut rng = TestRng::new();
+    let test_deploys = gen_test_deploys(&mut rng);
+    let mut deploy_acquisition =
+        DeployAcquisition::new_by_hash(test_deploys.keys().copied().collect(), false);
+
+    let block = Block::random_with_deploys(&mut rng, test_deploys.values());
+    let oversized_count = 100_000;
+    let approvals_hashes = ApprovalsHashes::new(
+        block.hash(),
+        vec![ApprovalsHash::random(&mut rng); oversized_count],
+        TrieMerkleProof::new(
+            URef::new([255; 32], AccessRights::NONE).into(),
+            StoredValue::CLValue(CLValue::from_t(()).unwrap()),
+            VecDeque::new(),
+        ),
+    );
+
+    assert_matches!(
+        deploy_acquisition.apply_approvals_hashes(&approvals_hashes),
+        Err(Error::MismatchedApprovalsHashesCount { expected, actual })
+            if expected == test_deploys.len() && actual == oversized_count
+    );
+
+    // The acquisition should be left untouched, still acquiring every deploy by hash.
+    assert_eq!(
+        deploy_acquisition.deploy_hashes(),
+        test_deploys.keys().copied().collect::<Vec<_>>()
+    );
+    assert_matches!(
+        deploy_acquisition.needs_deploy().unwrap(),
+        DeployIdentifier::ByHash(hash) if test_deploys.contains_key(&hash)
+    );
 }
 
 #[test]
@@ -133,6 +201,43 @@ fn apply_approvals_hashes_after_having_already_applied_deploys() {
     );
 }
 
+#[test]
+fn rejected_approvals_hashes_leave_acquisition_unchanged() {
+    let mut rng = TestRng::new();
+    let test_deploys = gen_test_deploys(&mut rng);
+    let deploy_hashes: Vec<_> = test_deploys.keys().copied().collect();
+    let mut deploy_acquisition = DeployAcquisition::new_by_hash(deploy_hashes.clone(), false);
+    let (last_deploy_hash, last_deploy) = test_deploys.last_key_value().unwrap();
+    assert_matches!(
+        deploy_acquisition.apply_deploy(DeployId::new(
+            *last_deploy_hash,
+            last_deploy.approvals_hash().unwrap(),
+        )),
+        Some(Acceptance::NeededIt)
+    );
+
+    // The held deploy is only reached after every other deploy, so the rejection must be made
+    // before any deploy is converted.
+    let approvals_hashes = gen_approvals_hashes(&mut rng, test_deploys.values());
+    assert_matches!(
+        deploy_acquisition.apply_approvals_hashes(&approvals_hashes),
+        Err(Error::EncounteredNonVacantDeployState)
+    );
+    assert_matches!(deploy_acquisition, DeployAcquisition::ByHash(_));
+    assert_eq!(deploy_acquisition.deploy_hashes(), deploy_hashes);
+    deploy_acquisition.debug_assert_consistent();
+
+    // Further mutations still pass the consistency checks.
+    let (first_deploy_hash, first_deploy) = test_deploys.first_key_value().unwrap();
+    assert_matches!(
+        deploy_acquisition.apply_deploy(DeployId::new(
+            *first_deploy_hash,
+            first_deploy.approvals_hash().unwrap(),
+        )),
+        Some(Acceptance::NeededIt)
+    );
+}
+
 #[test]
 fn partially_applied_deploys_on_acquisition_by_hash_should_need_missing_deploys() {
     let mut rng = TestRng::new();
@@ -197,3 +302,181 @@ fn apply_unregistered_deploy_returns_no_acceptance() {
         DeployIdentifier::ByHash(hash) if *first_deploy.hash() == hash
     );
 }
+
+#[test]
+fn total_deploys_and_execution_result_flag_survive_approvals_hashes_application() {
+    let mut rng = TestRng::new();
+    let test_deploys = gen_test_deploys(&mut rng);
+    let need_execution_result = rng.gen();
+    let mut deploy_acquisition = DeployAcquisition::new_by_hash(
+        test_deploys.keys().copied().collect(),
+        need_execution_result,
+    );
+    assert_eq!(deploy_acquisition.total_deploys(), test_deploys.len());
+    assert_eq!(
+        deploy_acquisition.requires_execution_result(),
+        need_execution_result
+    );
+
+    let approvals_hashes = gen_approvals_hashes(&mut rng, test_deploys.values());
+    assert!(deploy_acquisition
+        .apply_approvals_hashes(&approvals_hashes)
+        .is_ok());
+    assert_matches!(deploy_acquisition, DeployAcquisition::ById(_));
+    assert_eq!(deploy_acquisition.total_deploys(), test_deploys.len());
+    assert_eq!(
+        deploy_acquisition.requires_execution_result(),
+        need_execution_result
+    );
+}
+
+#[test]
+fn new_by_hash_acquires_by_hash() {
+    let mut rng = TestRng::new();
+    let test_deploys = gen_test_deploys(&mut rng);
+
+    let deploy_acquisition =
+        DeployAcquisition::new_by_hash(test_deploys.keys().copied().collect(), true);
+    assert_matches!(deploy_acquisition, DeployAcquisition::ByHash(_));
+    assert_eq!(deploy_acquisition.total_deploys(), test_deploys.len());
+    assert!(deploy_acquisition.requires_execution_result());
+    assert_matches!(
+        deploy_acquisition.needs_deploy().unwrap(),
+        DeployIdentifier::ByHash(_)
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "deploy state moved backwards")]
+fn regressed_deploy_state_trips_consistency_check() {
+    let mut rng = TestRng::new();
+    let test_deploys = gen_test_deploys(&mut rng);
+    let mut deploy_acquisition =
+        DeployAcquisition::new_by_hash(test_deploys.keys().copied().collect(), false);
+
+    let mut deploys_iter = test_deploys.iter();
+    let (first_hash, first_deploy) = deploys_iter.next().unwrap();
+    assert_matches!(
+        deploy_acquisition.apply_deploy(DeployId::new(
+            *first_hash,
+            first_deploy.approvals_hash().unwrap()
+        )),
+        Some(Acceptance::NeededIt)
+    );
+    deploy_acquisition.debug_assert_consistent();
+
+    // Move the held deploy back to vacant; the next mutation should detect it.
+    deploy_acquisition.force_deploy_state(first_hash, DeployState::Vacant);
+    let (second_hash, second_deploy) = deploys_iter.next().unwrap();
+    let _ = deploy_acquisition.apply_deploy(DeployId::new(
+        *second_hash,
+        second_deploy.approvals_hash().unwrap(),
+    ));
+}
+
+#[test]
+fn deploy_order_is_preserved_across_approvals_hashes_application() {
+    let mut rng = TestRng::new();
+    // Use an order other than that of the hashes themselves.
+    let mut deploys: Vec<_> = gen_test_deploys(&mut rng).into_values().collect();
+    deploys.shuffle(&mut rng);
+    let block_order: Vec<_> = deploys.iter().map(|deploy| *deploy.hash()).collect();
+
+    let mut deploy_acquisition = DeployAcquisition::new_by_hash(block_order.clone(), false);
+    assert_eq!(deploy_acquisition.deploy_hashes(), block_order);
+
+    let approvals_hashes = gen_approvals_hashes(&mut rng, deploys.iter());
+    assert!(deploy_acquisition
+        .apply_approvals_hashes(&approvals_hashes)
+        .is_ok());
+    assert_matches!(deploy_acquisition, DeployAcquisition::ById(_));
+    assert_eq!(deploy_acquisition.deploy_hashes(), block_order);
+}
+
+#[test]
+fn per_deploy_execution_result_needs_are_honored_when_applying_results() {
+    let mut rng = TestRng::new();
+    let test_deploys = gen_test_deploys(&mut rng);
+    // Only the first deploy, e.g. a contract call rather than a transfer, needs its result.
+    let (needs_result_hash, _) = test_deploys.iter().next().unwrap();
+    let mut deploy_acquisition: DeployAcquisition = test_deploys
+        .keys()
+        .map(|deploy_hash| (*deploy_hash, deploy_hash == needs_result_hash))
+        .collect();
+    assert!(deploy_acquisition.requires_execution_result());
+    assert_eq!(
+        deploy_acquisition.deploy_hashes(),
+        test_deploys.keys().copied().collect::<Vec<_>>()
+    );
+
+    // Results for deploys which don't need them are not applied.
+    let (no_result_hash, _) = test_deploys.iter().nth(1).unwrap();
+    assert!(!deploy_acquisition.apply_execution_result(*no_result_hash));
+
+    let approvals_hashes = gen_approvals_hashes(&mut rng, test_deploys.values());
+    assert!(deploy_acquisition
+        .apply_approvals_hashes(&approvals_hashes)
+        .is_ok());
+    assert!(deploy_acquisition.requires_execution_result());
+
+    // The needed execution result is only applied once.
+    assert!(deploy_acquisition.apply_execution_result(*needs_result_hash));
+    assert!(!deploy_acquisition.apply_execution_result(*needs_result_hash));
+    assert_eq!(
+        deploy_acquisition.to_string(),
+        format!("by-id 0/{} bodies, 1/1 results", test_deploys.len())
+    );
+}
+
+#[test]
+fn summary_reflects_bodies_and_execution_results() {
+    let mut rng = TestRng::new();
+    let test_deploys: BTreeMap<DeployHash, Deploy> = (0..5)
+        .map(|_| {
+            let deploy = Deploy::random(&mut rng);
+            (*deploy.hash(), deploy)
+        })
+        .collect();
+    let deploy_hashes: Vec<_> = test_deploys.keys().copied().collect();
+    let mut by_hash_acquisition = DeployAcquisition::new_by_hash(deploy_hashes.clone(), true);
+    let mut by_id_acquisition = DeployAcquisition::new_by_hash(deploy_hashes.clone(), true);
+    let approvals_hashes = gen_approvals_hashes(&mut rng, test_deploys.values());
+    assert!(by_id_acquisition
+        .apply_approvals_hashes(&approvals_hashes)
+        .is_ok());
+    assert_eq!(
+        by_hash_acquisition.to_string(),
+        "by-hash 0/5 bodies, 0/5 results"
+    );
+    assert_eq!(
+        by_id_acquisition.to_string(),
+        "by-id 0/5 bodies, 0/5 results"
+    );
+
+    // Acquire three deploys and two execution results.
+    for acquisition in [&mut by_hash_acquisition, &mut by_id_acquisition] {
+        for deploy in test_deploys.values().take(3) {
+            let deploy_id = DeployId::new(*deploy.hash(), deploy.approvals_hash().unwrap());
+            assert_matches!(
+                acquisition.apply_deploy(deploy_id),
+                Some(Acceptance::NeededIt)
+            );
+        }
+        for deploy_hash in &deploy_hashes[..2] {
+            assert!(acquisition.apply_execution_result(*deploy_hash));
+        }
+    }
+    assert_eq!(
+        by_hash_acquisition.to_string(),
+        "by-hash 3/5 bodies, 2/5 results"
+    );
+    assert_eq!(
+        by_id_acquisition.to_string(),
+        "by-id 3/5 bodies, 2/5 results"
+    );
+
+    // Results are omitted when none are needed.
+    let acquisition = DeployAcquisition::new_by_hash(deploy_hashes, false);
+    assert_eq!(acquisition.to_string(), "by-hash 0/5 bodies");
+}
@@ -5,7 +5,7 @@ mod event;
 mod metrics;
 mod tests;
 
-use std::{collections::BTreeSet, fmt::Debug, sync::Arc};
+use std::{collections::BTreeSet, fmt::Debug, sync::Arc, time::Duration};
 
 use datasize::DataSize;
 use prometheus::Registry;
@@ -28,7 +28,7 @@ use casper_types::{
 };
 
 use crate::{
-    components::Component,
+    components::{fetcher::FetchItem, Component},
     effect::{
         announcements::{DeployAcceptorAnnouncement, FatalAnnouncement},
         requests::{ContractRuntimeRequest, StorageRequest},
@@ -50,6 +50,10 @@ const COMPONENT_NAME: &str = "deploy_acceptor";
 
 const ARG_TARGET: &str = "target";
 
+/// The delay before first putting a deploy received from a client to storage again, having not
+/// found it there after putting it.  Each subsequent retry doubles the delay.
+const CLIENT_PUT_RETRY_BASE_DELAY: Duration = Duration::from_millis(100);
+
 #[derive(Debug, Error, Serialize)]
 pub(crate) enum Error {
     /// The block chain has no blocks.
@@ -84,6 +88,13 @@ pub(crate) enum Error {
         /// The timestamp when the node validated the expiry timestamp.
         current_node_timestamp: Timestamp,
     },
+
+    /// The deploy received by the node from the client wasn't found in storage after being put.
+    #[error("deploy not found in storage after {put_attempts} attempts to store it")]
+    FailedToStore {
+        /// The number of times the deploy was put to storage.
+        put_attempts: u32,
+    },
 }
 
 impl Error {
@@ -919,11 +930,30 @@ impl DeployAcceptor {
             return effects;
         }
 
+        Self::put_to_storage(
+            effect_builder,
+            event_metadata,
+            false,
+            0,
+            verification_start_timestamp,
+        )
+    }
+
+    /// Puts the deploy to storage.  `was_new` is whether an earlier put of the same deploy, now
+    /// being retried, reported it as new.
+    fn put_to_storage<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: Box<EventMetadata>,
+        was_new: bool,
+        put_retries: u32,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
         effect_builder
             .put_deploy_to_storage(event_metadata.deploy.clone())
             .event(move |is_new| Event::PutToStorageResult {
                 event_metadata,
-                is_new,
+                is_new: is_new || was_new,
+                put_retries,
                 verification_start_timestamp,
             })
     }
@@ -961,7 +991,91 @@ impl DeployAcceptor {
         effects
     }
 
+    /// Handles the result of putting a deploy to storage.  Deploys received from clients are
+    /// checked to be held in storage before being accepted, as unlike those received from peers,
+    /// they can't be re-acquired.
     fn handle_put_to_storage<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: Box<EventMetadata>,
+        is_new: bool,
+        put_retries: u32,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
+        if matches!(event_metadata.source, Source::Client)
+            && self.acceptor_config.max_client_put_retries > 0
+        {
+            return effect_builder
+                .is_deploy_stored(event_metadata.deploy.fetch_id())
+                .event(move |is_stored| Event::PutVerificationResult {
+                    event_metadata,
+                    is_new,
+                    is_stored,
+                    put_retries,
+                    verification_start_timestamp,
+                });
+        }
+        self.handle_stored_deploy(
+            effect_builder,
+            event_metadata,
+            is_new,
+            verification_start_timestamp,
+        )
+    }
+
+    /// Handles the result of checking that a deploy received from a client is held in storage
+    /// after putting it.  If it isn't, it is put again up to `max_client_put_retries` times before
+    /// the client is told it couldn't be stored.
+    fn handle_put_verification_result<REv: ReactorEventT>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        event_metadata: Box<EventMetadata>,
+        is_new: bool,
+        is_stored: bool,
+        put_retries: u32,
+        verification_start_timestamp: Timestamp,
+    ) -> Effects<Event> {
+        if is_stored {
+            return self.handle_stored_deploy(
+                effect_builder,
+                event_metadata,
+                is_new,
+                verification_start_timestamp,
+            );
+        }
+        let deploy_hash = *event_metadata.deploy.hash();
+        if put_retries < self.acceptor_config.max_client_put_retries {
+            let delay =
+                CLIENT_PUT_RETRY_BASE_DELAY.saturating_mul(2_u32.saturating_pow(put_retries));
+            debug!(
+                %deploy_hash, retry = put_retries + 1, ?delay,
+                "deploy from client not found in storage after put, will retry"
+            );
+            return effect_builder
+                .set_timeout(delay)
+                .event(move |_| Event::RetryPut {
+                    event_metadata,
+                    was_new: is_new,
+                    put_retries: put_retries + 1,
+                    verification_start_timestamp,
+                });
+        }
+        let put_attempts = put_retries + 1;
+        error!(
+            %deploy_hash, put_attempts,
+            "deploy from client not found in storage after put, giving up"
+        );
+        match event_metadata.maybe_responder {
+            Some(responder) => responder
+                .respond(Err(Error::FailedToStore { put_attempts }))
+                .ignore(),
+            None => Effects::new(),
+        }
+    }
+
+    /// Handles a deploy now held in storage, announcing it if it is new and notifying the client
+    /// it was received from, if any.
+    fn handle_stored_deploy<REv: ReactorEventT>(
         &self,
         effect_builder: EffectBuilder<REv>,
         event_metadata: Box<EventMetadata>,
@@ -1130,11 +1244,39 @@ impl<REv: ReactorEventT> Component<REv> for DeployAcceptor {
             Event::PutToStorageResult {
                 event_metadata,
                 is_new,
+                put_retries,
                 verification_start_timestamp,
             } => self.handle_put_to_storage(
                 effect_builder,
                 event_metadata,
                 is_new,
+                put_retries,
+                verification_start_timestamp,
+            ),
+            Event::PutVerificationResult {
+                event_metadata,
+                is_new,
+                is_stored,
+                put_retries,
+                verification_start_timestamp,
+            } => self.handle_put_verification_result(
+                effect_builder,
+                event_metadata,
+                is_new,
+                is_stored,
+                put_retries,
+                verification_start_timestamp,
+            ),
+            Event::RetryPut {
+                event_metadata,
+                was_new,
+                put_retries,
+                verification_start_timestamp,
+            } => Self::put_to_storage(
+                effect_builder,
+                event_metadata,
+                was_new,
+                put_retries,
                 verification_start_timestamp,
             ),
             Event::StoredFinalizedApprovals {
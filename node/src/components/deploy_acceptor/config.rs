@@ -6,6 +6,7 @@ use serde::{Deserialize, Serialize};
 use casper_types::TimeDiff;
 
 const DEFAULT_TIMESTAMP_LEEWAY: &str = "2sec";
+const DEFAULT_MAX_CLIENT_PUT_RETRIES: u32 = 3;
 
 /// Configuration options for accepting deploys.
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, DataSize)]
@@ -18,12 +19,19 @@ pub struct Config {
     /// The maximum value to which `timestamp_leeway` can be set is defined by the chainspec
     /// setting `deploys.max_timestamp_leeway`.
     pub timestamp_leeway: TimeDiff,
+    /// The maximum number of times a deploy received from a client is put to storage again, after
+    /// exponentially increasing delays, if it isn't found there having been put.  If it still
+    /// isn't found, the client is told it couldn't be stored.  Deploys received from peers are
+    /// only put once.  A value of 0 disables checking that deploys received from clients were
+    /// stored.
+    pub max_client_put_retries: u32,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Config {
             timestamp_leeway: TimeDiff::from_str(DEFAULT_TIMESTAMP_LEEWAY).unwrap(),
+            max_client_put_retries: DEFAULT_MAX_CLIENT_PUT_RETRIES,
         }
     }
 }
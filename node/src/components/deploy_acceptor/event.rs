@@ -52,6 +52,24 @@ pub(crate) enum Event {
     PutToStorageResult {
         event_metadata: Box<EventMetadata>,
         is_new: bool,
+        put_retries: u32,
+        verification_start_timestamp: Timestamp,
+    },
+    /// The result of the `DeployAcceptor` checking that a `Deploy` received from a client is held
+    /// in storage after putting it.
+    PutVerificationResult {
+        event_metadata: Box<EventMetadata>,
+        is_new: bool,
+        is_stored: bool,
+        put_retries: u32,
+        verification_start_timestamp: Timestamp,
+    },
+    /// The delay before putting a `Deploy` received from a client to storage again, having not
+    /// found it there after putting it, has elapsed.
+    RetryPut {
+        event_metadata: Box<EventMetadata>,
+        was_new: bool,
+        put_retries: u32,
         verification_start_timestamp: Timestamp,
     },
     /// The result of the `DeployAcceptor` storing the approvals from a `Deploy` provided by a
@@ -128,6 +146,37 @@ impl Display for Event {
                     )
                 }
             }
+            Event::PutVerificationResult {
+                event_metadata,
+                is_stored,
+                ..
+            } => {
+                if *is_stored {
+                    write!(
+                        formatter,
+                        "verified {} is stored",
+                        event_metadata.deploy.hash()
+                    )
+                } else {
+                    write!(
+                        formatter,
+                        "{} not stored after put",
+                        event_metadata.deploy.hash()
+                    )
+                }
+            }
+            Event::RetryPut {
+                event_metadata,
+                put_retries,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "retry {} of putting {} to storage",
+                    put_retries,
+                    event_metadata.deploy.hash()
+                )
+            }
             Event::StoredFinalizedApprovals {
                 event_metadata,
                 is_new,
@@ -1003,11 +1003,15 @@ async fn run_deploy_acceptor_without_timeout(
                     )
                 )
             }
-            // Check that repeated valid deploys from a client raises `PutToStorageResult` with the
-            // `is_new` flag as false.
+            // Check that repeated valid deploys from a client raises `PutVerificationResult` with
+            // the `is_new` flag as false.
             TestScenario::FromClientRepeatedValidDeploy => matches!(
                 event,
-                Event::DeployAcceptor(super::Event::PutToStorageResult { is_new: false, .. })
+                Event::DeployAcceptor(super::Event::PutVerificationResult {
+                    is_new: false,
+                    is_stored: true,
+                    ..
+                })
             ),
             // Check that repeated valid deploys from a peer raises `StoredFinalizedApprovals` with
             // the `is_new` flag as false.
@@ -1217,6 +1221,87 @@ async fn should_accept_repeated_valid_deploy_from_client() {
     assert!(result.is_ok())
 }
 
+#[tokio::test(start_paused = true)]
+async fn should_retry_putting_deploy_from_client_not_found_in_storage() {
+    let mut rng = crate::new_rng();
+    let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+    let config = Config {
+        max_client_put_retries: 1,
+        ..Config::default()
+    };
+    let mut deploy_acceptor = DeployAcceptor::new(config, &chainspec, &Registry::new()).unwrap();
+    let scheduler =
+        crate::utils::leak(reactor::Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let deploy = Arc::new(Deploy::random_valid_native_transfer(&mut rng));
+    let (deploy_sender, deploy_receiver) = oneshot::channel();
+    let event_metadata = Box::new(EventMetadata::new(
+        Arc::clone(&deploy),
+        Source::Client,
+        Some(Responder::without_shutdown(deploy_sender)),
+    ));
+
+    // The deploy wasn't found in storage after the first put, so it should be put again once the
+    // retry delay has elapsed.
+    let event = super::Event::PutVerificationResult {
+        event_metadata,
+        is_new: true,
+        is_stored: false,
+        put_retries: 0,
+        verification_start_timestamp: Timestamp::now(),
+    };
+    let mut effects = deploy_acceptor.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 1);
+    let mut events = effects.pop().unwrap().await;
+    let event_metadata = match events.pop() {
+        Some(super::Event::RetryPut {
+            event_metadata,
+            was_new: true,
+            put_retries: 1,
+            ..
+        }) => event_metadata,
+        other => panic!("unexpected events: {:?}", other),
+    };
+
+    // The retried put finds the deploy, which should then be accepted.
+    let event = super::Event::PutVerificationResult {
+        event_metadata,
+        is_new: true,
+        is_stored: true,
+        put_retries: 1,
+        verification_start_timestamp: Timestamp::now(),
+    };
+    let effects = deploy_acceptor.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 2);
+    for effect in effects {
+        tokio::spawn(effect);
+    }
+    assert!(deploy_receiver.await.unwrap().is_ok());
+
+    // Once retries are exhausted, the client should be told the deploy couldn't be stored.
+    let (deploy_sender, deploy_receiver) = oneshot::channel();
+    let event = super::Event::PutVerificationResult {
+        event_metadata: Box::new(EventMetadata::new(
+            deploy,
+            Source::Client,
+            Some(Responder::without_shutdown(deploy_sender)),
+        )),
+        is_new: true,
+        is_stored: false,
+        put_retries: 1,
+        verification_start_timestamp: Timestamp::now(),
+    };
+    let effects = deploy_acceptor.handle_event(effect_builder, &mut rng, event);
+    assert_eq!(effects.len(), 1);
+    for effect in effects {
+        tokio::spawn(effect);
+    }
+    assert!(matches!(
+        deploy_receiver.await.unwrap(),
+        Err(super::Error::FailedToStore { put_attempts: 2 })
+    ));
+}
+
 #[tokio::test]
 async fn should_accept_deploy_with_valid_custom_payment_from_client() {
     let test_scenario = TestScenario::FromClientCustomPaymentContract(ContractScenario::Valid);
@@ -21,7 +21,10 @@ use tokio::{net::UnixListener, sync::watch};
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    components::{Component, ComponentState, InitializedComponent, PortBoundComponent},
+    components::{
+        gossiper::GossiperRegistry, Component, ComponentState, InitializedComponent,
+        PortBoundComponent,
+    },
     effect::{
         announcements::ControlAnnouncement,
         diagnostics_port::DumpConsensusStateRequest,
@@ -68,14 +71,18 @@ pub(crate) struct DiagnosticsPort {
     #[data_size(skip)]
     _shutdown_sender: Option<watch::Sender<()>>, // only used for its `Drop` impl
     config: WithDir<Config>,
+    /// The node's gossipers, whose health can be queried by clients.
+    #[data_size(skip)]
+    gossiper_registry: GossiperRegistry,
 }
 
 impl DiagnosticsPort {
     /// Creates a new diagnostics port component.
-    pub(crate) fn new(config: WithDir<Config>) -> Self {
+    pub(crate) fn new(config: WithDir<Config>, gossiper_registry: GossiperRegistry) -> Self {
         DiagnosticsPort {
             state: ComponentState::Uninitialized,
             config,
+            gossiper_registry,
             _shutdown_sender: None,
         }
     }
@@ -208,7 +215,13 @@ where
             #[allow(clippy::useless_conversion)]
             cfg.socket_umask.into(),
         )?;
-        let server = tasks::server(effect_builder, socket_path, listener, shutdown_receiver);
+        let server = tasks::server(
+            effect_builder,
+            socket_path,
+            listener,
+            self.gossiper_registry.clone(),
+            shutdown_receiver,
+        );
         Ok(server.ignore())
     }
 }
@@ -91,6 +91,8 @@ pub(super) enum Action {
     DumpQueues,
     /// Get detailed networking insights.
     NetInfo,
+    /// Show the health of every gossiper.
+    GossipHealth,
     /// Stop the node at a certain condition.
     Stop {
         /// When to stop the node.
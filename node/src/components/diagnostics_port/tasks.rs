@@ -30,7 +30,7 @@ use super::{
     util::ShowUnixAddr,
 };
 use crate::{
-    components::consensus::EraDump,
+    components::{consensus::EraDump, gossiper::GossiperRegistry},
     effect::{
         announcements::{ControlAnnouncement, QueueDumpFormat},
         diagnostics_port::DumpConsensusStateRequest,
@@ -143,6 +143,76 @@ enum ObtainDumpError {
     ReopenTempFile(#[source] io::Error),
 }
 
+/// The health of a single gossiper, as reported to diagnostics port clients.
+#[derive(Debug, Serialize)]
+struct GossiperHealthReport {
+    /// Name of the gossiper.
+    name: &'static str,
+    /// Whether the gossiper is considered healthy.
+    is_healthy: bool,
+    /// Whether newly-received items are handed off for storing immediately rather than queued.
+    is_accepting_items: bool,
+    /// The number of items currently being gossiped.
+    active_item_count: usize,
+    /// The number of items currently being gossiped which have made no recent progress.
+    stalled_item_count: usize,
+    /// Seconds elapsed since at least one peer was last sent a gossip message, if ever.
+    secs_since_last_successful_gossip: Option<u64>,
+}
+
+/// The health of all gossipers registered with the node.
+#[derive(Debug, Serialize)]
+struct GossipHealthReport(Vec<GossiperHealthReport>);
+
+impl GossipHealthReport {
+    /// Collects the health of every gossiper in the registry.
+    fn collect(gossiper_registry: &GossiperRegistry) -> Self {
+        let reports = gossiper_registry
+            .active_types()
+            .into_iter()
+            .filter_map(|name| {
+                let health = gossiper_registry.health_of(name)?;
+                Some(GossiperHealthReport {
+                    name,
+                    is_healthy: health.is_healthy(),
+                    is_accepting_items: health.is_accepting_items,
+                    active_item_count: health.active_item_count,
+                    stalled_item_count: health.stalled_item_count,
+                    secs_since_last_successful_gossip: health
+                        .last_successful_gossip
+                        .map(|instant| instant.elapsed().as_secs()),
+                })
+            })
+            .collect();
+        GossipHealthReport(reports)
+    }
+}
+
+impl Display for GossipHealthReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for report in &self.0 {
+            write!(
+                f,
+                "{}: {}, accepting items: {}, active: {}, stalled: {}, last gossiped: ",
+                report.name,
+                if report.is_healthy {
+                    "healthy"
+                } else {
+                    "unhealthy"
+                },
+                report.is_accepting_items,
+                report.active_item_count,
+                report.stalled_item_count,
+            )?;
+            match report.secs_since_last_successful_gossip {
+                Some(secs) => writeln!(f, "{}s ago", secs)?,
+                None => writeln!(f, "never")?,
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Session {
     /// Creates a serializer for an `EraDump`.
     fn create_era_dump_serializer(&self) -> fn(&EraDump<'_>) -> Result<Vec<u8>, Cow<'static, str>> {
@@ -196,6 +266,7 @@ impl Session {
     async fn process_line<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
+        gossiper_registry: &GossiperRegistry,
         writer: &mut OwnedWriteHalf,
         line: &str,
     ) -> io::Result<bool>
@@ -324,6 +395,12 @@ impl Session {
                         let insights = effect_builder.get_network_insights().await;
                         self.send_to_client(writer, &insights).await?;
                     }
+                    Action::GossipHealth => {
+                        self.send_outcome(writer, &Outcome::success("collecting gossip health"))
+                            .await?;
+                        let report = GossipHealthReport::collect(gossiper_registry);
+                        self.send_to_client(writer, &report).await?;
+                    }
                     Action::Stop { at, clear } => {
                         let (msg, stop_at) = if clear {
                             ("clearing stopping point", None)
@@ -496,6 +573,7 @@ fn set_log_filter(filter_str: &str) -> Result<(), SetLogFilterError> {
 async fn handler<REv>(
     effect_builder: EffectBuilder<REv>,
     stream: UnixStream,
+    gossiper_registry: GossiperRegistry,
     mut shutdown_receiver: watch::Receiver<()>,
 ) -> io::Result<()>
 where
@@ -523,7 +601,12 @@ where
             Either::Right((line_result, _)) => {
                 if let Some(line) = line_result? {
                     keep_going = session
-                        .process_line(effect_builder, &mut writer, line.as_str())
+                        .process_line(
+                            effect_builder,
+                            &gossiper_registry,
+                            &mut writer,
+                            line.as_str(),
+                        )
                         .await?;
                 } else {
                     info!("client closed diagnostics port connection");
@@ -541,6 +624,7 @@ pub(super) async fn server<REv>(
     effect_builder: EffectBuilder<REv>,
     socket_path: PathBuf,
     listener: UnixListener,
+    gossiper_registry: GossiperRegistry,
     mut shutdown_receiver: watch::Receiver<()>,
 ) where
     REv: From<DumpConsensusStateRequest>
@@ -566,8 +650,13 @@ pub(super) async fn server<REv>(
                     next_client_id += 1;
 
                     tokio::spawn(
-                        handler(effect_builder, stream, handling_shutdown_receiver.clone())
-                            .instrument(span),
+                        handler(
+                            effect_builder,
+                            stream,
+                            gossiper_registry.clone(),
+                            handling_shutdown_receiver.clone(),
+                        )
+                        .instrument(span),
                     );
                 }
                 Err(err) => {
@@ -620,6 +709,7 @@ mod tests {
     use crate::{
         components::{
             diagnostics_port::{self, Config as DiagnosticsPortConfig, DiagnosticsPort},
+            gossiper::GossiperRegistry,
             network::{self, Identity as NetworkIdentity},
             Component, InitializedComponent,
         },
@@ -747,8 +837,10 @@ mod tests {
             _event_queue: EventQueueHandle<Event>,
             _rng: &mut NodeRng,
         ) -> Result<(Self, Effects<Event>), Error> {
-            let mut diagnostics_console =
-                DiagnosticsPort::new(WithDir::new(cfg.base_dir.clone(), cfg.diagnostics_port));
+            let mut diagnostics_console = DiagnosticsPort::new(
+                WithDir::new(cfg.base_dir.clone(), cfg.diagnostics_port),
+                GossiperRegistry::default(),
+            );
             <DiagnosticsPort as InitializedComponent<Event>>::start_initialization(
                 &mut diagnostics_console,
             );
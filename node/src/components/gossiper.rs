@@ -1,44 +1,108 @@
 mod config;
 #[cfg(test)]
 mod error;
+mod error_sampler;
 mod event;
+mod get_cache;
 mod gossip_item;
 mod gossip_table;
+mod health;
+mod holder_set;
 mod item_provider;
 mod message;
 mod metrics;
+mod preferred_holders;
 mod provider_impls;
+mod quarantine;
+mod registry;
+mod rejected_peers;
 mod tests;
+mod transport;
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet, VecDeque},
     fmt::{self, Debug, Formatter},
-    time::Duration,
+    iter, mem,
+    sync::{atomic::AtomicUsize, Arc},
+    time::{Duration, Instant},
 };
 
 use datasize::DataSize;
+use futures::Future;
 use prometheus::Registry;
-use tracing::{debug, error, trace, warn};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use tokio::sync::Semaphore;
+use tracing::{debug, error, error_span, trace, warn};
+
+use casper_hashing::Digest;
+use casper_types::{PublicKey, SecretKey, Timestamp};
 
 use crate::{
     components::Component,
     effect::{
-        announcements::GossiperAnnouncement,
+        announcements::{FatalAnnouncement, GossiperAnnouncement},
         incoming::GossiperIncoming,
         requests::{BeginGossipRequest, NetworkRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects, GossipTarget,
     },
+    fatal,
     types::NodeId,
     utils::Source,
     NodeRng,
 };
-pub(crate) use config::Config;
+use config::{AcquiringItemGetPolicy, IdCollisionPolicy, ResubmissionPolicy};
+pub(crate) use config::{Config, TrustLevel};
+use error_sampler::{ErrorSampler, StorageErrorKind};
 pub(crate) use event::Event;
-pub(crate) use gossip_item::{GossipItem, LargeGossipItem, SmallGossipItem};
-use gossip_table::{GossipAction, GossipTable};
+use event::{ItemCorruption, RangeError, RejectionReason};
+use get_cache::GetCache;
+pub(crate) use gossip_item::{GossipItem, LargeGossipItem, Priority, SmallGossipItem};
+use gossip_table::{GossipAction, GossipOpId, GossipTable};
+use health::GossipHealth;
 use item_provider::ItemProvider;
 pub(crate) use message::Message;
-use metrics::Metrics;
+use message::{
+    GossipSignature, MAX_GET_RANGE_LENGTH, MAX_GOSSIP_BATCH_ITEM_COUNT, MAX_GOSSIP_ID_PREFIX_LEN,
+};
+use metrics::{Metrics, PeerMetric};
+use preferred_holders::PreferredHolders;
+use quarantine::Quarantine;
+use registry::SharedGossipHealth;
+pub(crate) use registry::{GossipHealthSource, GossiperRegistry};
+use rejected_peers::RejectedPeers;
+pub(crate) use transport::GossipTransport;
+
+/// A function choosing up to the given number of peers to gossip to, excluding the given peers.
+#[cfg(test)]
+pub(crate) type PeerSelector = Box<dyn Fn(usize, &HashSet<NodeId>) -> HashSet<NodeId> + Send>;
+
+/// A function returning the level to which the given peer is trusted.
+pub(crate) type PeerTrust = Box<dyn Fn(&NodeId) -> TrustLevel + Send>;
+
+/// A function returning the public key with which the given peer signs its gossip, i.e. the key it
+/// authenticated with when connecting, or `None` if unknown.
+pub(crate) type PeerSigningKey = Box<dyn Fn(&NodeId) -> Option<PublicKey> + Send>;
+
+/// A function returning the time remaining until the current quiet window ends, or `None` if
+/// outside of a quiet window.  During a quiet window, gossip of items with less than
+/// `Priority::High` is deferred until the window ends.
+pub(crate) type QuietHours = Box<dyn Fn() -> Option<Duration> + Send>;
+
+/// The maximum number of times an unacknowledged response to a peer's `GetItem` request is re-sent.
+const MAX_GET_RESPONSE_RESENDS: u32 = 2;
+
+/// The maximum number of items whose gossip can be deferred until the current quiet window ends.
+/// Once reached, further gossip is sent immediately.
+const MAX_QUIET_QUEUE_LEN: usize = 10_000;
+
+/// The upper bound on `Config::get_request_batch_window`, limiting the latency it adds to
+/// responses.
+const MAX_GET_REQUEST_BATCH_WINDOW: Duration = Duration::from_millis(100);
+
+/// The interval between successive rounds of housekeeping, once started by the reactor sending a
+/// first `Event::Housekeeping`.
+const HOUSEKEEPING_INTERVAL: Duration = Duration::from_secs(5);
 
 /// The component which gossips to peers and handles incoming gossip messages from peers.
 #[allow(clippy::type_complexity)]
@@ -49,7 +113,124 @@ where
     table: GossipTable<T::Id>,
     gossip_timeout: Duration,
     get_from_peer_timeout: Duration,
+    /// See `Config::get_from_peer_jitter_percent`.
+    get_from_peer_jitter_percent: u8,
+    /// See `Config::max_inflight_get_response_bytes`.
+    max_inflight_get_response_bytes: usize,
+    /// The total serialized size of the items sent to peers in response to their requests which
+    /// haven't yet been handed to the network.
+    inflight_get_response_bytes: usize,
+    /// Responses to peers' requests waiting for earlier responses to be sent, along with the
+    /// serialized size of each item.
+    queued_get_responses: VecDeque<(Box<T>, NodeId, usize)>,
+    /// See `Config::ack_get_responses`.
+    ack_get_responses: bool,
+    /// The number of times each response to a peer's request which hasn't been acknowledged yet
+    /// has been re-sent.
+    unacked_get_responses: HashMap<(T::Id, NodeId), u32>,
     validate_and_store_timeout: Duration,
+    /// See `Config::max_concurrent_puts`.
+    max_concurrent_puts: usize,
+    /// IDs of items handed off to be validated and stored, for which `ItemReceived` or the
+    /// validate-and-store timeout is still pending, along with the time they were handed off.
+    in_flight_puts: HashMap<T::Id, Instant>,
+    /// IDs of items being retrieved from storage, along with the time the retrieval started.
+    get_from_storage_started: HashMap<T::Id, Instant>,
+    /// Items awaiting a free put slot, in the order they were received.
+    queued_puts: VecDeque<(Box<T>, NodeId)>,
+    /// The last time at least one peer was sent a gossip message.
+    last_successful_gossip: Option<Instant>,
+    /// IDs of items which failed validation, and for which incoming gossip is ignored.
+    quarantine: Quarantine<T::Id>,
+    /// Records of peers whose messages have been rejected.
+    rejected_peers: RejectedPeers,
+    /// See `Config::verify_after_put`.
+    verify_after_put: bool,
+    /// See `Config::sync_after_put`.
+    sync_after_put: bool,
+    /// See `Config::require_signed_gossip`.
+    require_signed_gossip: bool,
+    /// See `Config::cascade_threshold`.
+    cascade_threshold: usize,
+    /// See `Config::acquiring_item_get_policy`.
+    acquiring_item_get_policy: AcquiringItemGetPolicy,
+    /// See `Config::id_collision_policy`.
+    id_collision_policy: IdCollisionPolicy,
+    /// See `Config::resubmission_policy`.
+    resubmission_policy: ResubmissionPolicy,
+    /// See `Config::max_amplification`.
+    max_amplification: usize,
+    /// See `Config::reject_expired_items`.
+    reject_expired_items: bool,
+    /// See `Config::max_outstanding_gets_per_peer`.
+    max_outstanding_gets_per_peer: usize,
+    /// The items we have requested from each peer and not yet received or timed out.
+    outstanding_gets: HashMap<NodeId, HashSet<T::Id>>,
+    /// See `Config::awaiting_remainder_stall_threshold`.
+    awaiting_remainder_stall_threshold: Duration,
+    /// See `Config::max_untrusted_item_size`.
+    max_untrusted_item_size: usize,
+    /// Determines how far each peer is trusted.  If `None`, all peers are trusted.
+    peer_trust: Option<PeerTrust>,
+    /// Determines when gossip of non-urgent items is deferred.  If `None`, it never is.
+    quiet_hours: Option<QuietHours>,
+    /// The gossip deferred until the current quiet window ends, in the order it was deferred, with
+    /// at most one entry per item.
+    quiet_queue: VecDeque<(T::Id, GossipTarget, usize, HashSet<NodeId>)>,
+    /// Our own node ID, if set, used to recognize our own gossip echoed back to us.
+    our_id: Option<NodeId>,
+    /// The items we received from a source other than a peer, i.e. which we originated, while
+    /// they remain in the gossip table.
+    originated: HashSet<T::Id>,
+    /// See `Config::gossip_id_prefix_len`.
+    gossip_id_prefix_len: usize,
+    /// See `Config::get_request_batch_window`.
+    get_request_batch_window: Duration,
+    /// The peers requesting each item whose read from storage is deferred until the current
+    /// `get_request_batch_window` elapses.
+    pending_get_requests: HashMap<T::Id, Vec<NodeId>>,
+    /// The key used to sign outgoing gossip messages, if any.
+    signing_key: Option<(Arc<SecretKey>, PublicKey)>,
+    /// Determines the key each peer's signed gossip must be signed with.  If `None`, no signed
+    /// gossip is accepted.
+    peer_signing_key: Option<PeerSigningKey>,
+    /// Limits the rate at which storage errors are logged.
+    storage_error_sampler: ErrorSampler<StorageErrorKind>,
+    /// Transports over which gossip is announced in addition to the primary gossip network.
+    secondary_transports: Vec<Arc<dyn GossipTransport<T>>>,
+    /// The health published to a `GossiperRegistry`, if registered with one.
+    published_health: Option<Arc<SharedGossipHealth>>,
+    /// See `Config::min_regossip_interval`.
+    min_regossip_interval: Duration,
+    /// The time at which each recently-gossiped item was last gossiped, or is scheduled to be.
+    last_gossiped: HashMap<T::Id, Instant>,
+    /// Permits for accessing storage, shared with other gossipers so that they fairly share a
+    /// storage concurrency budget.  If `None`, storage accesses are not limited.
+    storage_permits: Option<Arc<Semaphore>>,
+    /// Complete items recently read from storage to serve get-item requests.
+    get_cache: GetCache<T::Id, Box<T>>,
+    /// Peers which have recently delivered items we were getting from them.
+    preferred_holders: PreferredHolders,
+    /// See `Config::max_read_through_hops`.
+    max_read_through_hops: u8,
+    /// The number of times each get-item request relayed to us and not yet served had been
+    /// relayed, by item and requester.
+    relayed_get_requests: HashMap<(T::Id, NodeId), u8>,
+    /// The items missing from storage which we're getting from a holder in order to relay them to
+    /// the peers which requested them from us, along with the holder and when we asked it.
+    read_throughs: HashMap<T::Id, (NodeId, Instant, Vec<NodeId>)>,
+    /// The serialized sizes of items being gossiped which don't provide a size hint.
+    serialized_sizes: HashMap<T::Id, usize>,
+    /// The content hashes of held items, as read from storage when checking a received item for
+    /// an ID collision, so that later duplicates can be checked without reading storage again.
+    held_content_hashes: HashMap<T::Id, Digest>,
+    /// The source of randomness for the gossiper's own decisions, e.g. timeout jitter.  Not a
+    /// `NodeRng`, since in tests only one of those may be created per thread, and a test may
+    /// replace it via `set_rng` to control the decisions.
+    rng: ChaCha20Rng,
+    /// Chooses the peers to gossip to in place of the network component, if set.
+    #[cfg(test)]
+    peer_selector: Option<PeerSelector>,
     name: &'static str,
     metrics: Metrics,
 }
@@ -63,14 +244,85 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         name: &'static str,
         config: Config,
         registry: &Registry,
+    ) -> Result<Self, prometheus::Error> {
+        Self::new_with_transports(name, config, registry, vec![])
+    }
+
+    /// Constructs a new gossiper component which, in addition to the primary gossip network,
+    /// announces gossip over each of the given secondary transports.
+    pub(crate) fn new_with_transports(
+        name: &'static str,
+        config: Config,
+        registry: &Registry,
+        secondary_transports: Vec<Arc<dyn GossipTransport<T>>>,
     ) -> Result<Self, prometheus::Error> {
         Ok(Gossiper {
             table: GossipTable::new(config),
             gossip_timeout: config.gossip_request_timeout().into(),
             get_from_peer_timeout: config.get_remainder_timeout().into(),
+            get_from_peer_jitter_percent: config.get_from_peer_jitter_percent().min(100),
+            max_inflight_get_response_bytes: config.max_inflight_get_response_bytes() as usize,
+            inflight_get_response_bytes: 0,
+            queued_get_responses: VecDeque::new(),
+            ack_get_responses: config.ack_get_responses(),
+            unacked_get_responses: HashMap::new(),
             validate_and_store_timeout: config.validate_and_store_timeout().into(),
+            max_concurrent_puts: config.max_concurrent_puts() as usize,
+            in_flight_puts: HashMap::new(),
+            get_from_storage_started: HashMap::new(),
+            queued_puts: VecDeque::new(),
+            last_successful_gossip: None,
+            quarantine: Quarantine::new(config.quarantine_duration().into()),
+            rejected_peers: RejectedPeers::new(),
+            verify_after_put: config.verify_after_put(),
+            sync_after_put: config.sync_after_put(),
+            require_signed_gossip: config.require_signed_gossip(),
+            cascade_threshold: config.cascade_threshold() as usize,
+            acquiring_item_get_policy: config.acquiring_item_get_policy(),
+            id_collision_policy: config.id_collision_policy(),
+            resubmission_policy: config.resubmission_policy(),
+            max_amplification: config.max_amplification() as usize,
+            reject_expired_items: config.reject_expired_items(),
+            max_outstanding_gets_per_peer: config.max_outstanding_gets_per_peer() as usize,
+            outstanding_gets: HashMap::new(),
+            awaiting_remainder_stall_threshold: config.awaiting_remainder_stall_threshold().into(),
+            max_untrusted_item_size: config.max_untrusted_item_size() as usize,
+            peer_trust: None,
+            quiet_hours: None,
+            quiet_queue: VecDeque::new(),
+            our_id: None,
+            originated: HashSet::new(),
+            gossip_id_prefix_len: usize::from(config.gossip_id_prefix_len())
+                .min(MAX_GOSSIP_ID_PREFIX_LEN),
+            get_request_batch_window: Duration::from(config.get_request_batch_window())
+                .min(MAX_GET_REQUEST_BATCH_WINDOW),
+            pending_get_requests: HashMap::new(),
+            signing_key: None,
+            peer_signing_key: None,
+            storage_error_sampler: ErrorSampler::new(config.storage_error_log_interval().into()),
+            secondary_transports,
+            published_health: None,
+            min_regossip_interval: config.min_regossip_interval().into(),
+            last_gossiped: HashMap::new(),
+            storage_permits: None,
+            get_cache: GetCache::new(
+                config.get_cache_capacity() as usize,
+                config.get_cache_ttl().into(),
+            ),
+            preferred_holders: PreferredHolders::new(
+                config.max_preferred_holders().into(),
+                config.preferred_holder_half_life().into(),
+            ),
+            max_read_through_hops: config.max_read_through_hops(),
+            relayed_get_requests: HashMap::new(),
+            read_throughs: HashMap::new(),
+            serialized_sizes: HashMap::new(),
+            held_content_hashes: HashMap::new(),
+            rng: ChaCha20Rng::from_entropy(),
+            #[cfg(test)]
+            peer_selector: None,
             name,
-            metrics: Metrics::new(name, registry)?,
+            metrics: Metrics::new(name, registry, config.max_peers_in_metrics().into())?,
         })
     }
 
@@ -91,203 +343,1256 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
     {
         debug!(item=%item_id, %source, "received new gossip item");
+        if let Some(put_started) = self.in_flight_puts.get(&item_id) {
+            self.metrics.observe_put_to_storage(put_started.elapsed());
+        }
+        let mut effects = self.finish_put(effect_builder, &item_id);
+        if self.is_ignored_resubmission(&item_id, &source) {
+            debug!(
+                item=%item_id,
+                "{}: ignoring re-submission of item already being gossiped", self.name
+            );
+            return effects;
+        }
+        if !self.table.make_room_for(&item_id) {
+            let reason = RejectionReason::TableFull;
+            effects.extend(
+                effect_builder
+                    .immediately()
+                    .event(move |_| Event::ItemRejected { item_id, reason }),
+            );
+            return effects;
+        }
+        if self.ack_get_responses && self.table.is_acquiring(&item_id) {
+            if let Some(holder) = source.node_id() {
+                let message = Message::GetResponseAck {
+                    item_id: item_id.clone(),
+                };
+                effects.extend(effect_builder.send_message(holder, message).ignore());
+            }
+        }
         match self
             .table
             .new_complete_data(&item_id, source.node_id(), target)
         {
             GossipAction::ShouldGossip(should_gossip) => {
                 self.metrics.items_received.inc();
-                Self::gossip(
+                if source.node_id().is_none() {
+                    self.record_originated(item_id.clone());
+                }
+                effects.extend(self.gossip(
                     effect_builder,
                     item_id,
                     should_gossip.target,
                     should_gossip.count,
                     should_gossip.exclude_peers,
-                )
+                ));
             }
-            GossipAction::Noop => Effects::new(),
+            GossipAction::Noop => (),
             GossipAction::AnnounceFinished => {
-                effect_builder.announce_finished_gossiping(item_id).ignore()
+                effects.extend(self.finished_gossiping(effect_builder, item_id))
             }
             GossipAction::GetRemainder { .. } | GossipAction::AwaitingRemainder => {
                 error!("can't be waiting for remainder since we hold the complete data");
-                Effects::new()
             }
         }
+        effects
     }
 
-    /// Gossips the given item ID to `count` random peers excluding the indicated ones.
-    fn gossip<REv>(
+    /// Returns whether the given item, received from `source`, is a local re-submission of an item
+    /// we are already gossiping which should be ignored as per `Config::resubmission_policy`.
+    ///
+    /// Otherwise a re-submission only tops up the gossip, since the gossip table only requests
+    /// gossip to as many peers as aren't already infected or being gossiped to.
+    fn is_ignored_resubmission(&self, item_id: &T::Id, source: &Source) -> bool {
+        self.resubmission_policy == ResubmissionPolicy::Ignore
+            && source.node_id().is_none()
+            && self.table.is_current(item_id)
+            && !self.table.is_acquiring(item_id)
+    }
+
+    /// Handles a new item which vetoed being gossiped via `GossipItem::should_gossip`.
+    ///
+    /// The item remains held locally, but no gossip table entry is created for it, and any existing
+    /// entry, e.g. from it having been gossiped to us, is finished.
+    fn handle_gossip_vetoed<REv>(
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         item_id: T::Id,
-        gossip_target: GossipTarget,
-        count: usize,
-        exclude_peers: HashSet<NodeId>,
     ) -> Effects<Event<T>>
     where
-        REv: From<NetworkRequest<Message<T>>> + Send,
+        REv: From<GossiperAnnouncement<T>> + Send,
     {
-        let message = Message::Gossip(item_id.clone());
-        effect_builder
-            .gossip_message(message, gossip_target, count, exclude_peers)
-            .event(move |peers| Event::GossipedTo {
-                item_id,
-                requested_count: count,
-                peers,
-            })
+        debug!(item=%item_id, "{}: not gossiping item which vetoed being gossiped", self.name);
+        let mut effects = self.finish_put(effect_builder, &item_id);
+        if self.table.force_finish(&item_id) {
+            effects.extend(self.announce_finished(effect_builder, item_id));
+        }
+        effects
     }
 
-    /// Handles the response from the network component detailing which peers it gossiped to.
-    fn gossiped_to<REv>(
+    /// Handles a new item having been rejected rather than gossiped.
+    fn handle_item_rejected(&self, item_id: T::Id, reason: RejectionReason) -> Effects<Event<T>> {
+        warn!(item=%item_id, %reason, "{}: rejected new item", self.name);
+        self.metrics.items_rejected.inc();
+        Effects::new()
+    }
+
+    /// Announces that gossiping of the given item has finished.  If it finished by becoming
+    /// saturated, its convergence time is reported via an `ItemConverged` event.
+    fn finished_gossiping<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         item_id: T::Id,
-        requested_count: usize,
-        peers: HashSet<NodeId>,
     ) -> Effects<Event<T>>
     where
         REv: From<GossiperAnnouncement<T>> + Send,
     {
-        self.metrics.times_gossiped.inc_by(peers.len() as u64);
-        // We don't have any peers to gossip to, so pause the process, which will eventually result
-        // in the entry being removed.
-        if peers.is_empty() {
-            self.metrics.times_ran_out_of_peers.inc();
-        }
-
-        // We didn't gossip to as many peers as was requested.  Reduce the table entry's in-flight
-        // count.
         let mut effects = Effects::new();
-        if peers.len() < requested_count
-            && self
-                .table
-                .reduce_in_flight_count(&item_id, requested_count - peers.len())
-        {
+        if let Some(elapsed) = self.table.take_convergence_time(&item_id) {
+            let converged_item_id = item_id.clone();
             effects.extend(
                 effect_builder
-                    .announce_finished_gossiping(item_id.clone())
-                    .ignore(),
+                    .immediately()
+                    .event(move |_| Event::ItemConverged {
+                        item_id: converged_item_id,
+                        elapsed,
+                    }),
             );
         }
+        effects.extend(self.announce_finished(effect_builder, item_id));
+        effects
+    }
 
-        // Remember which peers we *tried* to infect.
-        self.table
-            .register_infection_attempt(&item_id, peers.iter());
+    /// Handles an item having finished gossiping by becoming saturated.
+    fn handle_item_converged(&self, item_id: T::Id, elapsed: Duration) -> Effects<Event<T>> {
+        debug!(item=%item_id, ?elapsed, "{}: item converged", self.name);
+        self.metrics.observe_convergence(elapsed);
+        Effects::new()
+    }
 
-        // Set timeouts to check later that the specified peers all responded.
-        for peer in peers {
-            let item_id = item_id.clone();
-            effects.extend(
-                effect_builder
-                    .set_timeout(self.gossip_timeout)
-                    .event(move |_| Event::CheckGossipTimeout { item_id, peer }),
-            )
-        }
+    fn handle_amplification_limit_hit(
+        &self,
+        item_id: T::Id,
+        messages_sent: usize,
+    ) -> Effects<Event<T>> {
+        warn!(
+            item=%item_id, messages_sent,
+            "{}: paused gossiping item which exceeded amplification limit", self.name
+        );
+        self.metrics.amplification_limit_hits.inc();
+        Effects::new()
+    }
 
-        effects
+    /// Handles an item having been awaiting its remainder for longer than the
+    /// `awaiting_remainder_stall_threshold`.
+    fn handle_awaiting_remainder_stalled(
+        &self,
+        item_id: T::Id,
+        elapsed: Duration,
+    ) -> Effects<Event<T>> {
+        warn!(
+            item=%item_id, ?elapsed,
+            "{}: item stalled awaiting remainder", self.name
+        );
+        Effects::new()
     }
 
-    /// Checks that the given peer has responded to a previous gossip request we sent it.
-    fn check_gossip_timeout<REv>(
+    /// Handles an item having been found to have expired.  Any gossip table entry for it is
+    /// finished so that it isn't gossiped onwards.
+    fn handle_item_expired<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         item_id: T::Id,
-        peer: NodeId,
     ) -> Effects<Event<T>>
     where
-        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+        REv: From<GossiperAnnouncement<T>> + Send,
     {
-        match self.table.check_timeout(&item_id, peer) {
-            GossipAction::ShouldGossip(should_gossip) => Self::gossip(
-                effect_builder,
-                item_id,
-                should_gossip.target,
-                should_gossip.count,
-                should_gossip.exclude_peers,
-            ),
-            GossipAction::Noop => Effects::new(),
-            GossipAction::AnnounceFinished => {
-                effect_builder.announce_finished_gossiping(item_id).ignore()
-            }
-            GossipAction::GetRemainder { .. } | GossipAction::AwaitingRemainder => {
-                warn!(
-                    "can't have gossiped if we don't hold the complete data - likely the timeout \
-                    check was very delayed due to busy reactor"
-                );
-                Effects::new()
+        debug!(item=%item_id, "{}: not gossiping expired item", self.name);
+        if self.table.force_finish(&item_id) {
+            return self.announce_finished(effect_builder, item_id);
+        }
+        Effects::new()
+    }
+
+    /// Pauses gossiping of the given item if it has had more gossip requests sent for it than
+    /// `max_amplification` allows, emitting an `AmplificationLimitHit` event.
+    fn check_amplification<REv: Send>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: &T::Id,
+    ) -> Effects<Event<T>> {
+        if self.max_amplification == 0 {
+            return Effects::new();
+        }
+        match self.table.messages_sent(item_id) {
+            Some(messages_sent)
+                if messages_sent > self.max_amplification && self.table.pause(item_id) =>
+            {
+                let item_id = item_id.clone();
+                effect_builder
+                    .immediately()
+                    .event(move |_| Event::AmplificationLimitHit {
+                        item_id,
+                        messages_sent,
+                    })
             }
+            _ => Effects::new(),
         }
     }
 
-    /// Checks that the given peer has responded to a previous gossip response or `GetRequest` we
-    /// sent it indicating we wanted to get the full item from it.
-    fn check_get_from_peer_timeout<REv>(
+    /// Handles a request to gossip an item which has been paused, by reporting that it was gossiped
+    /// to none of the requested peers so that its in-flight count is reduced accordingly.
+    fn paused_gossip<REv: Send>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        count: usize,
+    ) -> Effects<Event<T>> {
+        debug!(item=%item_id, "{}: not gossiping paused item", self.name);
+        effect_builder
+            .immediately()
+            .event(move |_| Event::GossipedTo {
+                item_id,
+                requested_count: count,
+                peers: HashSet::new(),
+            })
+    }
+
+    /// Handles a newly-stored item, once storage has been synced to disk if required.  If
+    /// `verify_after_put` is set, the item is first retrieved from storage to verify it, otherwise
+    /// it is handled as per `handle_item_received`.
+    fn handle_item_stored<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         item_id: T::Id,
-        peer: NodeId,
+        source: Source,
+        target: GossipTarget,
     ) -> Effects<Event<T>>
     where
-        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+        REv: From<NetworkRequest<Message<T>>>
+            + From<StorageRequest>
+            + From<GossiperAnnouncement<T>>
+            + Send,
+        Self: ItemProvider<T>,
     {
-        match self.table.remove_holder_if_unresponsive(&item_id, peer) {
-            GossipAction::ShouldGossip(should_gossip) => Self::gossip(
-                effect_builder,
+        if !self.verify_after_put {
+            return self.handle_item_received(effect_builder, item_id, source, target);
+        }
+        let storage_key = T::storage_key(&item_id);
+        self.with_storage_permit(Self::get_from_storage(effect_builder, storage_key))
+            .event(move |maybe_item| Event::PutVerificationResult {
                 item_id,
-                should_gossip.target,
-                should_gossip.count,
-                should_gossip.exclude_peers,
-            ),
+                source,
+                target,
+                maybe_item,
+            })
+    }
 
-            GossipAction::GetRemainder { holder } => {
-                // The previous peer failed to provide the item, so we still need to get it.  Send
-                // a `GetItem` to a different holder and set a timeout to check we got the response.
-                let request = Message::GetItem(item_id.clone());
-                let mut effects = effect_builder.send_message(holder, request).ignore();
-                effects.extend(
-                    effect_builder
-                        .set_timeout(self.get_from_peer_timeout)
-                        .event(move |_| Event::CheckGetFromPeerTimeout {
-                            item_id,
-                            peer: holder,
-                        }),
-                );
-                effects
+    /// Handles the result of retrieving a newly-stored item from storage to verify that it is held
+    /// under its gossiped ID.  If so, the item is handled as per `handle_item_received`, unless it
+    /// vetoes being gossiped.  If the item isn't found, storage is checked for being read-only.
+    /// Otherwise gossiping of the item is abandoned.
+    fn handle_put_verification_result<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        source: Source,
+        target: GossipTarget,
+        maybe_item: Option<Box<T>>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>>
+            + From<StorageRequest>
+            + From<GossiperAnnouncement<T>>
+            + Send,
+    {
+        if let Some(item) = maybe_item {
+            if item.gossip_id() == item_id {
+                if !item.should_gossip() {
+                    return self.handle_gossip_vetoed(effect_builder, item_id);
+                }
+                if item.is_expired(Timestamp::now()) {
+                    let mut effects = self.finish_put(effect_builder, &item_id);
+                    effects.extend(
+                        effect_builder
+                            .immediately()
+                            .event(move |_| Event::ItemExpired { item_id }),
+                    );
+                    return effects;
+                }
+                return self.handle_item_received(effect_builder, item_id, source, target);
             }
+            error!(
+                item=%item_id, stored_item=%item.gossip_id(),
+                "storage holds a different item under the gossiped id"
+            );
+        } else {
+            return effect_builder
+                .is_storage_writable()
+                .event(move |is_writable| Event::StorageWritabilityChecked {
+                    item_id,
+                    is_writable,
+                });
+        }
+        self.abandon_put(effect_builder, item_id)
+    }
 
-            GossipAction::AnnounceFinished => {
-                effect_builder.announce_finished_gossiping(item_id).ignore()
-            }
+    /// Handles the result of checking whether storage is writable after a newly-stored item wasn't
+    /// found there.  Read-only storage can never hold any item, so is reported as fatal.
+    fn handle_storage_writability_checked<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        is_writable: bool,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<GossiperAnnouncement<T>> + Send,
+    {
+        if !is_writable {
+            let mut effects = self.finish_put(effect_builder, &item_id);
+            effects.extend(Self::fatal_inconsistency(
+                effect_builder,
+                format!("storage is read-only: cannot store {}", item_id),
+            ));
+            return effects;
+        }
+        if self.should_log_storage_error(StorageErrorKind::MissingAfterPut) {
+            error!(item=%item_id, "storage doesn't hold newly-stored item");
+        }
+        self.abandon_put(effect_builder, item_id)
+    }
 
-            GossipAction::Noop | GossipAction::AwaitingRemainder => Effects::new(),
+    /// Releases the put slot held by the given item and finishes gossiping it.
+    fn abandon_put<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<GossiperAnnouncement<T>> + Send,
+    {
+        let mut effects = self.finish_put(effect_builder, &item_id);
+        if self.table.force_finish(&item_id) {
+            effects.extend(self.announce_finished(effect_builder, item_id));
         }
+        effects
     }
 
-    /// Handles an incoming gossip request from a peer on the network, after having registered the
-    /// item in the gossip table.
-    fn handle_gossip<REv>(
+    /// Gossips the given item ID to `count` random peers excluding the indicated ones.
+    ///
+    /// If the item was gossiped less than `min_regossip_interval` ago, the gossip is deferred until
+    /// the interval has elapsed.  During a quiet window, gossip of items with less than
+    /// `Priority::High` is queued until the window ends.
+    fn gossip<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         item_id: T::Id,
-        sender: NodeId,
-        action: GossipAction,
+        gossip_target: GossipTarget,
+        count: usize,
+        exclude_peers: HashSet<NodeId>,
     ) -> Effects<Event<T>>
     where
-        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+        REv: From<NetworkRequest<Message<T>>> + Send,
     {
-        let mut effects = match action {
-            GossipAction::ShouldGossip(should_gossip) => {
-                debug!(item=%item_id, %sender, %should_gossip, "received gossip request");
-                self.metrics.items_received.inc();
-                // Gossip the item ID.
-                let mut effects = Self::gossip(
-                    effect_builder,
-                    item_id.clone(),
-                    should_gossip.target,
-                    should_gossip.count,
-                    should_gossip.exclude_peers,
+        if self.table.is_paused(&item_id) {
+            return self.paused_gossip(effect_builder, item_id, count);
+        }
+        if T::PRIORITY != Priority::High {
+            if let Some(remaining) = self
+                .quiet_hours
+                .as_ref()
+                .and_then(|quiet_hours| quiet_hours())
+            {
+                let queued_index = self
+                    .quiet_queue
+                    .iter()
+                    .position(|(queued_id, ..)| *queued_id == item_id);
+                if let Some(index) = queued_index {
+                    // Merge with the gossip of this item already deferred.
+                    debug!(
+                        item=%item_id,
+                        "{}: merging deferred gossip of item until quiet hours end", self.name
+                    );
+                    let (_, queued_target, queued_count, queued_exclude_peers) =
+                        &mut self.quiet_queue[index];
+                    *queued_target = gossip_target;
+                    *queued_count += count;
+                    queued_exclude_peers.extend(exclude_peers);
+                    return Effects::new();
+                }
+                if self.quiet_queue.len() >= MAX_QUIET_QUEUE_LEN {
+                    let table = &self.table;
+                    self.quiet_queue
+                        .retain(|(queued_id, ..)| table.is_current(queued_id));
+                }
+                if self.quiet_queue.len() < MAX_QUIET_QUEUE_LEN {
+                    debug!(
+                        item=%item_id,
+                        "{}: deferring gossip of item until quiet hours end", self.name
+                    );
+                    let mut effects = Effects::new();
+                    if self.quiet_queue.is_empty() {
+                        effects.extend(
+                            effect_builder
+                                .set_timeout(remaining)
+                                .event(|_| Event::QuietHoursEnded),
+                        );
+                    }
+                    self.quiet_queue
+                        .push_back((item_id, gossip_target, count, exclude_peers));
+                    return effects;
+                }
+                debug!(
+                    item=%item_id,
+                    "{}: quiet queue full, gossiping item during quiet hours", self.name
                 );
-
+            }
+        }
+        let delay = self.regossip_delay(&item_id);
+        let mut effects = self.announce_via_secondary_transports(
+            &item_id,
+            gossip_target,
+            count,
+            exclude_peers.clone(),
+        );
+        effects.extend(self.gossip_after(
+            effect_builder,
+            delay,
+            item_id,
+            gossip_target,
+            count,
+            exclude_peers,
+        ));
+        effects
+    }
+
+    /// Gossips the items queued during the quiet window which has now ended, skipping any no longer
+    /// being gossiped.
+    ///
+    /// If another quiet window is already under way, the queue is instead held until that ends.
+    fn flush_quiet_queue<REv>(&mut self, effect_builder: EffectBuilder<REv>) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        if self.quiet_queue.is_empty() {
+            return Effects::new();
+        }
+        if let Some(remaining) = self
+            .quiet_hours
+            .as_ref()
+            .and_then(|quiet_hours| quiet_hours())
+        {
+            return effect_builder
+                .set_timeout(remaining)
+                .event(|_| Event::QuietHoursEnded);
+        }
+        debug!(
+            count = self.quiet_queue.len(),
+            "{}: quiet hours ended, gossiping deferred items", self.name
+        );
+        let mut effects = Effects::new();
+        for (item_id, gossip_target, count, exclude_peers) in mem::take(&mut self.quiet_queue) {
+            if self.table.is_current(&item_id) {
+                effects.extend(self.gossip(
+                    effect_builder,
+                    item_id,
+                    gossip_target,
+                    count,
+                    exclude_peers,
+                ));
+            }
+        }
+        effects
+    }
+
+    /// Records that the given item is to be gossiped, returning how long the gossip must be
+    /// deferred to respect `min_regossip_interval`, if at all.
+    fn regossip_delay(&mut self, item_id: &T::Id) -> Option<Duration> {
+        if self.min_regossip_interval.is_zero() {
+            return None;
+        }
+        let now = Instant::now();
+        let interval = self.min_regossip_interval;
+        // Entries for items last gossiped at least an interval ago no longer constrain gossip.
+        self.last_gossiped
+            .retain(|_, gossiped_at| now.saturating_duration_since(*gossiped_at) < interval);
+        let gossip_at = match self.last_gossiped.get(item_id) {
+            Some(last_gossiped) if *last_gossiped + interval > now => *last_gossiped + interval,
+            _ => now,
+        };
+        let _ = self.last_gossiped.insert(item_id.clone(), gossip_at);
+        if gossip_at > now {
+            debug!(item=%item_id, "{}: deferring regossip of item", self.name);
+            Some(gossip_at - now)
+        } else {
+            None
+        }
+    }
+
+    /// Announces the given item ID over each secondary transport, independently of one another and
+    /// of the primary gossip network.
+    ///
+    /// Each announcement is bounded by the gossip request timeout, and its outcome is only logged.
+    fn announce_via_secondary_transports(
+        &self,
+        item_id: &T::Id,
+        gossip_target: GossipTarget,
+        count: usize,
+        exclude_peers: HashSet<NodeId>,
+    ) -> Effects<Event<T>> {
+        let mut effects = Effects::new();
+        for transport in &self.secondary_transports {
+            let announcement = transport.announce(
+                self.new_gossip_message(item_id),
+                gossip_target,
+                count,
+                exclude_peers.clone(),
+            );
+            let transport = Arc::clone(transport);
+            let item_id = item_id.clone();
+            let timeout = self.gossip_timeout;
+            effects.extend(
+                async move {
+                    match tokio::time::timeout(timeout, announcement).await {
+                        Ok(Some(peers)) => debug!(
+                            item=%item_id, transport=transport.name(), peer_count=peers.len(),
+                            "announced gossip via secondary transport"
+                        ),
+                        Ok(None) => warn!(
+                            item=%item_id, transport=transport.name(),
+                            "secondary transport unavailable to announce gossip"
+                        ),
+                        Err(_) => warn!(
+                            item=%item_id, transport=transport.name(),
+                            "timed out announcing gossip via secondary transport"
+                        ),
+                    }
+                }
+                .ignore(),
+            );
+        }
+        effects
+    }
+
+    /// Sets the shared count of connected peers, to be updated by the reactor as peers connect and
+    /// disconnect.
+    pub(crate) fn set_peer_count_hint(&mut self, peer_count: Arc<AtomicUsize>) {
+        self.table.set_peer_count_hint(peer_count);
+    }
+
+    /// Sets the permits for accessing storage, normally shared by all gossipers in the reactor so
+    /// that none can starve the others of storage access.
+    pub(crate) fn set_storage_permits(&mut self, storage_permits: Arc<Semaphore>) {
+        self.storage_permits = Some(storage_permits);
+    }
+
+    /// Returns a future which performs the given storage access once a storage permit has been
+    /// acquired, if permits are set.  Permits are granted in the order requested.
+    fn with_storage_permit<F>(&self, storage_access: F) -> impl Future<Output = F::Output> + Send
+    where
+        F: Future + Send,
+    {
+        let storage_permits = self.storage_permits.clone();
+        async move {
+            let _permit = match storage_permits {
+                Some(storage_permits) => storage_permits.acquire_owned().await.ok(),
+                None => None,
+            };
+            storage_access.await
+        }
+    }
+
+    /// Sets the key used to sign outgoing gossip messages, normally the node's own secret key.
+    pub(crate) fn set_signing_key(&mut self, secret_key: Arc<SecretKey>) {
+        let public_key = PublicKey::from(&*secret_key);
+        self.signing_key = Some((secret_key, public_key));
+    }
+
+    /// Sets the hook determining the key each peer's signed gossip must be signed with.  Until
+    /// set, signed gossip is dropped as its signer can't be tied to the sending peer.
+    pub(crate) fn set_peer_signing_key(&mut self, peer_signing_key: PeerSigningKey) {
+        self.peer_signing_key = Some(peer_signing_key);
+    }
+
+    /// Returns the serialized size of the given item if known without serializing it, i.e. as
+    /// hinted by the item or as cached while the item is being gossiped.
+    fn known_serialized_size(&self, item: &T) -> Option<usize> {
+        item.serialized_size_hint()
+            .or_else(|| self.serialized_sizes.get(&item.gossip_id()).copied())
+    }
+
+    /// Returns the serialized size of the given item, as hinted by the item if possible.
+    /// Otherwise the item is serialized, with the size cached while the item is being gossiped.
+    fn item_serialized_size(&mut self, item: &T) -> usize {
+        if let Some(size) = self.known_serialized_size(item) {
+            return size;
+        }
+        let item_id = item.gossip_id();
+        let size = serialized_size(item);
+        if self.table.is_current(&item_id) {
+            // Drop the sizes of items no longer being gossiped before caching another.
+            if self.serialized_sizes.len() >= self.table.items_current() {
+                let table = &self.table;
+                self.serialized_sizes
+                    .retain(|cached_item_id, _| table.is_current(cached_item_id));
+            }
+            let _ = self.serialized_sizes.insert(item_id, size);
+        }
+        size
+    }
+
+    /// Makes all subsequent gossip go to the peers chosen by `peer_selector`, given the requested
+    /// count and the peers to exclude, rather than to those chosen by the network component.
+    #[cfg(test)]
+    pub(crate) fn set_peer_selector(&mut self, peer_selector: PeerSelector) {
+        self.peer_selector = Some(peer_selector);
+    }
+
+    /// Replaces the source of randomness for the gossiper's own decisions, e.g. with a seeded one
+    /// or one previously returned by `rng_snapshot`, so that the decisions can be reproduced.
+    #[cfg(test)]
+    pub(crate) fn set_rng(&mut self, rng: ChaCha20Rng) {
+        self.rng = rng;
+    }
+
+    /// Returns a copy of the current state of the source of randomness for the gossiper's own
+    /// decisions.  Passing it to `set_rng` later replays the same sequence of random decisions.
+    #[cfg(test)]
+    pub(crate) fn rng_snapshot(&self) -> ChaCha20Rng {
+        self.rng.clone()
+    }
+
+    /// Sets the hook determining how far each peer is trusted.  Until set, all peers are trusted.
+    pub(crate) fn set_peer_trust(&mut self, peer_trust: PeerTrust) {
+        self.peer_trust = Some(peer_trust);
+    }
+
+    /// Sets the hook determining when gossip of items with less than `Priority::High` is deferred.
+    /// Until set, gossip is never deferred for quiet hours.
+    pub(crate) fn set_quiet_hours(&mut self, quiet_hours: QuietHours) {
+        self.quiet_hours = Some(quiet_hours);
+    }
+
+    /// Sets our own node ID, so that our own gossip echoed back to us, e.g. via a relay, is
+    /// recognized.
+    pub(crate) fn set_our_id(&mut self, our_id: NodeId) {
+        self.our_id = Some(our_id);
+    }
+
+    /// Records that we originated the given item.
+    fn record_originated(&mut self, item_id: T::Id) {
+        // Drop the items no longer in the gossip table before recording another.
+        if self.originated.len() >= self.table.items_tracked() {
+            let table = &self.table;
+            self.originated
+                .retain(|originated_id| table.has_entry(originated_id));
+        }
+        let _ = self.originated.insert(item_id);
+    }
+
+    /// Returns whether gossip about the given item from `sender` is an echo of our own gossip,
+    /// i.e. sent by ourselves, or about an item we originated.
+    fn is_echoed_gossip(&self, item_id: &T::Id, sender: NodeId) -> bool {
+        self.our_id == Some(sender)
+            || (self.originated.contains(item_id) && self.table.has_entry(item_id))
+    }
+
+    /// Handles an echo of our own gossip by recording the sender as a holder and replying that we
+    /// already hold the item, without checking storage.  Gossip appearing to come from ourselves
+    /// is dropped without touching the gossip table.
+    fn handle_echoed_gossip<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        sender: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        debug!(item=%item_id, %sender, "{}: received echo of our own gossip", self.name);
+        if self.our_id == Some(sender) {
+            return Effects::new();
+        }
+        // The sender has the item, so it must be known as a holder, e.g. so it isn't gossiped to.
+        let _ = self
+            .table
+            .merge_holders(&item_id, iter::once(sender).collect());
+        let reply = Message::GossipResponse {
+            item_id,
+            is_already_held: true,
+        };
+        effect_builder.send_message(sender, reply).ignore()
+    }
+
+    /// Returns the level to which the given peer is trusted.
+    fn trust_level(&self, peer: &NodeId) -> TrustLevel {
+        self.peer_trust
+            .as_ref()
+            .map_or(TrustLevel::Trusted, |peer_trust| peer_trust(peer))
+    }
+
+    /// Returns a new gossip message for the given item ID, signed if we have a signing key, or
+    /// otherwise carrying only the ID's hash prefix if configured to.
+    fn new_gossip_message(&self, item_id: &T::Id) -> Message<T> {
+        match &self.signing_key {
+            Some((secret_key, public_key)) => Message::SignedGossip {
+                item_id: item_id.clone(),
+                signature: GossipSignature::create(item_id, secret_key, public_key),
+            },
+            None => match self.unique_id_prefix(item_id) {
+                Some(prefix) => Message::GossipIdPrefix(prefix),
+                None => Message::Gossip(item_id.clone()),
+            },
+        }
+    }
+
+    /// Returns the hash prefix to gossip in place of the given item ID, or `None` if the full ID
+    /// should be gossiped, i.e. if gossiping prefixes is disabled, if the prefix would be no
+    /// shorter than the ID, or if the prefix also matches another item we know of.
+    fn unique_id_prefix(&self, item_id: &T::Id) -> Option<Vec<u8>> {
+        if self.gossip_id_prefix_len == 0 || serialized_size(item_id) <= self.gossip_id_prefix_len {
+            return None;
+        }
+        let prefix = id_hash_prefix(item_id, self.gossip_id_prefix_len);
+        let is_unique = !self
+            .table
+            .data_ids()
+            .any(|data_id| data_id != item_id && id_has_hash_prefix(data_id, &prefix));
+        is_unique.then_some(prefix)
+    }
+
+    /// Returns the IDs of all items we know of whose ID hashes start with `prefix`.
+    ///
+    /// This hashes the ID of every item in the gossip table, so is linear in the table's size.
+    fn ids_with_prefix(&self, prefix: &[u8]) -> Vec<T::Id> {
+        self.table
+            .data_ids()
+            .filter(|data_id| id_has_hash_prefix(*data_id, prefix))
+            .cloned()
+            .collect()
+    }
+
+    /// Handles gossip from `sender` carrying only the hash prefix of an item ID.  If the prefix
+    /// matches exactly one item we know of, it is handled as gossip of that item.  Otherwise,
+    /// including where the prefix matches several items, the full IDs are requested from `sender`.
+    fn handle_gossip_id_prefix<REv>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        prefix: Vec<u8>,
+        sender: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        let mut matching_ids = self.ids_with_prefix(&prefix);
+        if matching_ids.len() == 1 {
+            let message = Box::new(Message::Gossip(matching_ids.remove(0)));
+            return effect_builder
+                .immediately()
+                .event(move |_| Event::Incoming(GossiperIncoming { sender, message }));
+        }
+        debug!(
+            matching_count = matching_ids.len(), %sender,
+            "{}: requesting full ids for gossiped id prefix", self.name
+        );
+        effect_builder
+            .send_message(sender, Message::GetFullId(prefix))
+            .ignore()
+    }
+
+    /// Handles a request from `requester` for the full IDs of items whose ID hashes start with
+    /// `prefix`, by gossiping all such IDs to it.
+    fn handle_get_full_id<REv>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        prefix: Vec<u8>,
+        requester: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        let matching_ids = self.ids_with_prefix(&prefix);
+        if matching_ids.is_empty() {
+            debug!(%requester, "{}: no full ids for requested id prefix", self.name);
+        }
+        self.send_gossip_batches(effect_builder, requester, &matching_ids)
+    }
+
+    /// Returns `false` if the given incoming message is a gossip message which should be dropped,
+    /// i.e. if it is from a rejected peer, if it carries an invalid signature or one not made with
+    /// the sender's own key, or if it is unsigned while signatures are required.
+    fn is_acceptable_gossip(&mut self, message: &Message<T>, sender: NodeId) -> bool {
+        match message {
+            Message::Gossip(_)
+            | Message::SignedGossip { .. }
+            | Message::GossipBatch(_)
+            | Message::GossipIdPrefix(_)
+                if self.trust_level(&sender) == TrustLevel::Rejected =>
+            {
+                debug!(%sender, "{}: dropping {} from rejected peer", self.name, message);
+                false
+            }
+            Message::Gossip(item_id) if self.require_signed_gossip => {
+                debug!(item=%item_id, %sender, "{}: dropping unsigned gossip", self.name);
+                false
+            }
+            Message::GossipBatch(item_ids) if self.require_signed_gossip => {
+                debug!(
+                    item_count = item_ids.len(), %sender,
+                    "{}: dropping unsigned gossip batch", self.name
+                );
+                false
+            }
+            Message::GossipIdPrefix(_) if self.require_signed_gossip => {
+                debug!(%sender, "{}: dropping unsigned gossip id prefix", self.name);
+                false
+            }
+            Message::SignedGossip { item_id, signature } if !signature.is_valid_for(item_id) => {
+                warn!(
+                    item=%item_id, %sender,
+                    "{}: dropping gossip with invalid signature", self.name
+                );
+                self.rejected_peers.record(sender);
+                false
+            }
+            Message::SignedGossip { item_id, signature }
+                if !self.is_signed_by(signature, &sender) =>
+            {
+                warn!(
+                    item=%item_id, %sender, signer=%signature.public_key(),
+                    "{}: dropping gossip signed by a key other than the sender's", self.name
+                );
+                self.rejected_peers.record(sender);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    /// Returns `true` if the given signature was made with the key the given peer authenticated
+    /// with.
+    fn is_signed_by(&self, signature: &GossipSignature, peer: &NodeId) -> bool {
+        self.peer_signing_key
+            .as_ref()
+            .and_then(|peer_signing_key| peer_signing_key(peer))
+            .map_or(false, |public_key| public_key == *signature.public_key())
+    }
+
+    /// Announces that gossiping of the given item has finished, recording its achieved degree in
+    /// the metrics.
+    fn announce_finished<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<GossiperAnnouncement<T>> + Send,
+    {
+        if let Some(achieved_degree) = self.table.achieved_degree(&item_id) {
+            self.metrics.observe_achieved_degree(achieved_degree);
+        }
+        effect_builder.announce_finished_gossiping(item_id).ignore()
+    }
+
+    /// Returns the ID of the gossip operation for the item the given event relates to, if it is
+    /// currently being gossiped.
+    fn op_id_for(&self, event: &Event<T>) -> Option<GossipOpId> {
+        event
+            .item_id()
+            .and_then(|item_id| self.table.op_id(&item_id))
+    }
+
+    /// Gossips the given item ID as per `gossip`, optionally after waiting for `delay`.
+    fn gossip_after<REv>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        delay: Option<Duration>,
+        item_id: T::Id,
+        gossip_target: GossipTarget,
+        count: usize,
+        exclude_peers: HashSet<NodeId>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        if self.table.is_paused(&item_id) {
+            return self.paused_gossip(effect_builder, item_id, count);
+        }
+
+        #[cfg(test)]
+        if let Some(peer_selector) = &self.peer_selector {
+            // As when gossiping via the network component, nothing is sent until any delay has
+            // elapsed.
+            let peers = peer_selector(count, &exclude_peers);
+            let mut effects = Effects::new();
+            for peer in peers.iter().copied() {
+                let message = self.new_gossip_message(&item_id);
+                effects.extend(
+                    async move {
+                        if let Some(delay) = delay {
+                            let _ = effect_builder.set_timeout(delay).await;
+                        }
+                        effect_builder.send_message(peer, message).await
+                    }
+                    .ignore(),
+                );
+            }
+            let gossiped_to = Event::GossipedTo {
+                item_id,
+                requested_count: count,
+                peers,
+            };
+            effects.extend(match delay {
+                Some(delay) => effect_builder
+                    .set_timeout(delay)
+                    .event(move |_| gossiped_to),
+                None => effect_builder.immediately().event(move |_| gossiped_to),
+            });
+            return effects;
+        }
+
+        let message = self.new_gossip_message(&item_id);
+        let exclude = exclude_peers.clone();
+        async move {
+            if let Some(delay) = delay {
+                let _ = effect_builder.set_timeout(delay).await;
+            }
+            effect_builder
+                .try_gossip_message(message, gossip_target, count, exclude)
+                .await
+        }
+        .event(move |maybe_peers| match maybe_peers {
+            Some(peers) => Event::GossipedTo {
+                item_id,
+                requested_count: count,
+                peers,
+            },
+            None => Event::GossipNetworkUnavailable {
+                item_id,
+                gossip_target,
+                requested_count: count,
+                exclude_peers,
+            },
+        })
+    }
+
+    /// Handles the network component being unavailable to gossip an item, e.g. while it is
+    /// shutting down, by retrying after the gossip request timeout.
+    ///
+    /// Unlike an empty set of peers, this doesn't indicate we've run out of peers, so the item is
+    /// not finished.
+    fn handle_gossip_network_unavailable<REv>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        gossip_target: GossipTarget,
+        requested_count: usize,
+        exclude_peers: HashSet<NodeId>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        if !self.table.has_entry(&item_id) {
+            return Effects::new();
+        }
+        debug!(item=%item_id, "network unavailable to gossip item; will retry");
+        self.gossip_after(
+            effect_builder,
+            Some(self.gossip_timeout),
+            item_id,
+            gossip_target,
+            requested_count,
+            exclude_peers,
+        )
+    }
+
+    /// Gossips the given item IDs to `peer`, as a `Gossip` message if there is only one or as
+    /// `GossipBatch` messages otherwise.
+    fn send_gossip_batches<REv>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        peer: NodeId,
+        item_ids: &[T::Id],
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        item_ids
+            .chunks(MAX_GOSSIP_BATCH_ITEM_COUNT)
+            .flat_map(|batch| {
+                let message = match batch {
+                    [item_id] => Message::Gossip(item_id.clone()),
+                    _ => Message::GossipBatch(batch.to_vec()),
+                };
+                effect_builder.send_message(peer, message).ignore()
+            })
+            .collect()
+    }
+
+    /// Handles the response from the network component detailing which peers it gossiped to.
+    fn gossiped_to<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        requested_count: usize,
+        peers: HashSet<NodeId>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<GossiperAnnouncement<T>> + Send,
+    {
+        self.metrics.times_gossiped.inc_by(peers.len() as u64);
+        // We don't have any peers to gossip to, so pause the process, which will eventually result
+        // in the entry being removed.
+        if peers.is_empty() {
+            if !self.table.is_paused(&item_id) {
+                self.metrics.times_ran_out_of_peers.inc();
+            }
+        } else {
+            self.last_successful_gossip = Some(Instant::now());
+        }
+
+        // We didn't gossip to as many peers as was requested.  Reduce the table entry's in-flight
+        // count.
+        let mut effects = Effects::new();
+        if peers.len() < requested_count
+            && self
+                .table
+                .reduce_in_flight_count(&item_id, requested_count - peers.len())
+        {
+            effects.extend(self.announce_finished(effect_builder, item_id.clone()));
+        }
+
+        // Remember which peers we *tried* to infect.
+        self.table
+            .register_infection_attempt(&item_id, peers.iter());
+        effects.extend(self.check_amplification(effect_builder, &item_id));
+
+        // Set timeouts to check later that the specified peers all responded.
+        for peer in peers {
+            let item_id = item_id.clone();
+            effects.extend(
+                effect_builder
+                    .set_timeout(self.gossip_timeout)
+                    .event(move |_| Event::CheckGossipTimeout { item_id, peer }),
+            )
+        }
+
+        effects
+    }
+
+    /// Checks that the given peer has responded to a previous gossip request we sent it.
+    fn check_gossip_timeout<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        peer: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+    {
+        if self.table.is_current(&item_id)
+            && self
+                .table
+                .find_holder(&item_id, |holder| *holder == peer)
+                .is_none()
+        {
+            self.metrics.record_peer(peer, PeerMetric::GossipTimeout);
+        }
+        match self.table.check_timeout(&item_id, peer) {
+            GossipAction::ShouldGossip(should_gossip) => self.gossip(
+                effect_builder,
+                item_id,
+                should_gossip.target,
+                should_gossip.count,
+                should_gossip.exclude_peers,
+            ),
+            GossipAction::Noop => Effects::new(),
+            GossipAction::AnnounceFinished => self.finished_gossiping(effect_builder, item_id),
+            GossipAction::GetRemainder { .. } => Self::fatal_inconsistency(
+                effect_builder,
+                format!(
+                    "told to get remainder of {} on checking gossip timeout for {}",
+                    item_id, peer
+                ),
+            ),
+            GossipAction::AwaitingRemainder => {
+                warn!(
+                    "can't have gossiped if we don't hold the complete data - likely the timeout \
+                    check was very delayed due to busy reactor"
+                );
+                Effects::new()
+            }
+        }
+    }
+
+    /// Emits a `FatalInconsistency` event describing an internal state which should be impossible.
+    fn fatal_inconsistency<REv: Send>(
+        effect_builder: EffectBuilder<REv>,
+        detail: String,
+    ) -> Effects<Event<T>> {
+        effect_builder
+            .immediately()
+            .event(move |_| Event::FatalInconsistency { detail })
+    }
+
+    /// Reports an internal inconsistency to the reactor as fatal, leaving it to decide whether to
+    /// halt the node.
+    fn handle_fatal_inconsistency<REv>(
+        &self,
+        effect_builder: EffectBuilder<REv>,
+        detail: String,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<FatalAnnouncement> + Send,
+    {
+        error!(%detail, "{}: fatal inconsistency", self.name);
+        fatal!(effect_builder, "{}: {}", self.name, detail).ignore()
+    }
+
+    /// Returns the timeout for getting an item from a peer, extended by a random jitter of up to
+    /// `get_from_peer_jitter_percent` of it, so that retries for many items don't synchronize.
+    fn jittered_get_from_peer_timeout(&mut self) -> Duration {
+        let max_jitter =
+            self.get_from_peer_timeout * u32::from(self.get_from_peer_jitter_percent) / 100;
+        if max_jitter.is_zero() {
+            return self.get_from_peer_timeout;
+        }
+        self.get_from_peer_timeout + self.rng.gen_range(Duration::ZERO..=max_jitter)
+    }
+
+    /// Sends a `GetItem` for the given item to `holder` and sets a timeout to check we got the
+    /// response.
+    fn get_from_peer<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        holder: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        self.record_outstanding_get(holder, item_id.clone());
+        let request = Message::GetItem(item_id.clone());
+        let mut effects = effect_builder.send_message(holder, request).ignore();
+        effects.extend(
+            effect_builder
+                .set_timeout(self.jittered_get_from_peer_timeout())
+                .event(move |_| Event::CheckGetFromPeerTimeout {
+                    item_id,
+                    peer: holder,
+                }),
+        );
+        effects
+    }
+
+    /// Records that we have requested the given item from the given peer.
+    fn record_outstanding_get(&mut self, peer: NodeId, item_id: T::Id) {
+        let _ = self
+            .outstanding_gets
+            .entry(peer)
+            .or_default()
+            .insert(item_id);
+    }
+
+    /// Records that a request for the given item from the given peer is no longer outstanding,
+    /// having either been answered or timed out.
+    fn clear_outstanding_get(&mut self, peer: &NodeId, item_id: &T::Id) {
+        if let Some(item_ids) = self.outstanding_gets.get_mut(peer) {
+            let _ = item_ids.remove(item_id);
+            if item_ids.is_empty() {
+                let _ = self.outstanding_gets.remove(peer);
+            }
+        }
+    }
+
+    /// Returns `true` if the given peer has `max_outstanding_gets_per_peer` or more of our requests
+    /// outstanding.
+    fn exceeds_get_budget(&self, peer: &NodeId) -> bool {
+        self.max_outstanding_gets_per_peer != 0
+            && self
+                .outstanding_gets
+                .get(peer)
+                .map_or(0, |item_ids| item_ids.len())
+                >= self.max_outstanding_gets_per_peer
+    }
+
+    /// Checks that the given peer has responded to a previous gossip response or `GetRequest` we
+    /// sent it indicating we wanted to get the full item from it.
+    fn check_get_from_peer_timeout<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        peer: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+    {
+        self.clear_outstanding_get(&peer, &item_id);
+        if self.table.is_acquiring(&item_id) {
+            self.metrics.record_peer(peer, PeerMetric::FailedGet);
+            self.preferred_holders.remove(&peer);
+        }
+        match self.table.remove_holder_if_unresponsive(&item_id, peer) {
+            GossipAction::ShouldGossip(should_gossip) => self.gossip(
+                effect_builder,
+                item_id,
+                should_gossip.target,
+                should_gossip.count,
+                should_gossip.exclude_peers,
+            ),
+
+            GossipAction::GetRemainder { holder } => {
+                // The previous peer failed to provide the item, so we still need to get it from a
+                // different holder.  Prefer one which has recently delivered items to us, then one
+                // which isn't already handling too many of our requests, but fall back to the
+                // chosen one if there's no such holder.
+                let holder = if let Some(preferred) = self.preferred_holder(&item_id) {
+                    preferred
+                } else if self.exceeds_get_budget(&holder) {
+                    self.table
+                        .find_holder(&item_id, |holder| !self.exceeds_get_budget(holder))
+                        .unwrap_or(holder)
+                } else {
+                    holder
+                };
+                self.get_from_peer(effect_builder, item_id, holder)
+            }
+
+            GossipAction::AnnounceFinished => self.finished_gossiping(effect_builder, item_id),
+
+            GossipAction::Noop | GossipAction::AwaitingRemainder => Effects::new(),
+        }
+    }
+
+    /// Handles an incoming gossip request from a peer on the network, after having registered the
+    /// item in the gossip table.
+    fn handle_gossip<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        sender: NodeId,
+        action: GossipAction,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+    {
+        let mut effects = match action {
+            GossipAction::ShouldGossip(should_gossip) => {
+                debug!(item=%item_id, %sender, %should_gossip, "received gossip request");
+                self.metrics.items_received.inc();
+                if !should_gossip.is_already_held {
+                    self.metrics.record_peer(sender, PeerMetric::ItemLearned);
+                }
+                // Gossip the item ID.
+                let mut effects = self.gossip(
+                    effect_builder,
+                    item_id.clone(),
+                    should_gossip.target,
+                    should_gossip.count,
+                    should_gossip.exclude_peers,
+                );
+
                 // If this is a new complete item to us, announce it.
                 if ID_IS_COMPLETE_ITEM && !should_gossip.is_already_held {
                     debug!(item=%item_id, "announcing new complete gossip item received");
@@ -309,6 +1614,7 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
             GossipAction::GetRemainder { .. } => {
                 debug!(item=%item_id, %sender, %action, "received gossip request");
                 self.metrics.items_received.inc();
+                self.metrics.record_peer(sender, PeerMetric::ItemLearned);
                 // Send a response to the sender indicating we want the full item from them, and set
                 // a timeout for this response.
                 let reply = Message::GossipResponse {
@@ -316,185 +1622,915 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
                     is_already_held: false,
                 };
                 let mut effects = effect_builder.send_message(sender, reply).ignore();
+                self.record_outstanding_get(sender, item_id.clone());
                 let item_id_clone = item_id.clone();
                 effects.extend(
                     effect_builder
-                        .set_timeout(self.get_from_peer_timeout)
+                        .set_timeout(self.jittered_get_from_peer_timeout())
                         .event(move |_| Event::CheckGetFromPeerTimeout {
                             item_id: item_id_clone,
                             peer: sender,
                         }),
                 );
-                effects
+                effects
+            }
+            GossipAction::Noop
+            | GossipAction::AwaitingRemainder
+            | GossipAction::AnnounceFinished => {
+                trace!(item=%item_id, %sender, %action, "received gossip request");
+                // Send a response to the sender indicating we already hold the item.
+                let reply = Message::GossipResponse {
+                    item_id: item_id.clone(),
+                    is_already_held: true,
+                };
+                let mut effects = effect_builder.send_message(sender, reply).ignore();
+
+                if action == GossipAction::AnnounceFinished {
+                    effects.extend(self.finished_gossiping(effect_builder, item_id.clone()));
+                }
+
+                effects
+            }
+        };
+        if T::REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT {
+            effects.extend(
+                effect_builder
+                    .announce_gossip_received(item_id, sender)
+                    .ignore(),
+            );
+        }
+        effects
+    }
+
+    /// Handles an incoming gossip response from a peer on the network.
+    fn handle_gossip_response<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        is_already_held: bool,
+        sender: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>>
+            + From<StorageRequest>
+            + From<GossiperAnnouncement<T>>
+            + Send,
+        Self: ItemProvider<T>,
+    {
+        let mut effects: Effects<_> = Effects::new();
+        if !self.table.has_entry(&item_id) {
+            debug!(
+                item = %item_id,
+                %sender,
+                "got a gossip response for an item we're not gossiping"
+            );
+            if is_already_held {
+                let _ = self.table.unknown_response(&item_id, sender);
+            }
+            return effects;
+        }
+
+        let action = if is_already_held {
+            self.table.already_infected(&item_id, sender)
+        } else {
+            if !ID_IS_COMPLETE_ITEM {
+                // `sender` doesn't hold the full item; get the item from the component responsible
+                // for holding it, then send it to `sender`.
+                effects.extend(self.get_from_storage_for(effect_builder, item_id.clone(), sender));
+            }
+            self.table.we_infected(&item_id, sender)
+        };
+
+        match action {
+            GossipAction::ShouldGossip(_) if self.is_above_cascade_threshold(&item_id) => {
+                // The sender has been recorded as a holder; leave the entry current so that it can
+                // still serve requests and finish as normal.
+                debug!(item=%item_id, "well-propagated item; not gossiping further");
+            }
+            GossipAction::ShouldGossip(should_gossip) => effects.extend(self.gossip(
+                effect_builder,
+                item_id,
+                should_gossip.target,
+                should_gossip.count,
+                should_gossip.exclude_peers,
+            )),
+            GossipAction::Noop => (),
+            GossipAction::AnnounceFinished => {
+                effects.extend(self.finished_gossiping(effect_builder, item_id))
+            }
+            GossipAction::GetRemainder { .. } => effects.extend(Self::fatal_inconsistency(
+                effect_builder,
+                format!(
+                    "told to get remainder of {} on receiving gossip response from {}",
+                    item_id, sender
+                ),
+            )),
+            GossipAction::AwaitingRemainder => {
+                warn!(
+                    "shouldn't have gossiped if we don't hold the complete item - possible \
+                    significant latency, or malicious peer"
+                );
+            }
+        }
+
+        effects
+    }
+
+    /// Returns `true` if the cascade threshold is enabled and the given item has at least that many
+    /// known holders.
+    fn is_above_cascade_threshold(&self, item_id: &T::Id) -> bool {
+        self.cascade_threshold > 0
+            && self
+                .table
+                .holder_count(item_id)
+                .map_or(false, |holder_count| holder_count >= self.cascade_threshold)
+    }
+
+    /// Handles the `Some` case when attempting to get the item from storage in order to send it to
+    /// the requester.
+    ///
+    /// The item is checked for self-consistency first; a corrupt item is never sent, and is instead
+    /// handled as though it couldn't be retrieved from storage.
+    fn got_from_storage<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        item: Box<T>,
+        requester: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+    {
+        if let Err(corruption) = check_item_consistency(&item_id, &item) {
+            return self.failed_to_get_from_storage(effect_builder, item_id, Some(corruption));
+        }
+        self.get_cache.insert(&item_id, &item);
+        self.respond_with_item(effect_builder, item_id, item, requester)
+    }
+
+    /// Sends the item to the requesting peer, or queues it to be sent if too many bytes of
+    /// responses are already in flight.
+    fn respond_with_item<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        item: Box<T>,
+        requester: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        let size = self.item_serialized_size(&item);
+        if !self.queued_get_responses.is_empty() || self.exceeds_inflight_limit(size) {
+            debug!(
+                %item_id, %requester, size, inflight = self.inflight_get_response_bytes,
+                "queueing response since too many bytes in flight"
+            );
+            self.queued_get_responses.push_back((item, requester, size));
+            return Effects::new();
+        }
+        self.send_get_response(effect_builder, item_id, item, requester, size)
+    }
+
+    /// Sends the item to the requesting peer, tracking its size as in flight until it has been
+    /// handed to the network.
+    fn send_get_response<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        item: Box<T>,
+        requester: NodeId,
+        size: usize,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        self.inflight_get_response_bytes += size;
+        let mut effects = Effects::new();
+        if self.ack_get_responses {
+            let _ = self
+                .unacked_get_responses
+                .entry((item_id.clone(), requester))
+                .or_insert(0);
+            let item_id = item_id.clone();
+            effects.extend(
+                effect_builder
+                    .set_timeout(self.gossip_timeout)
+                    .event(move |_| Event::CheckGetResponseAckTimeout { item_id, requester }),
+            );
+        }
+        let message = Message::Item(item);
+        effects.extend(
+            effect_builder
+                .send_message(requester, message)
+                .event(move |()| Event::GetResponseSent {
+                    item_id,
+                    requester,
+                    size,
+                }),
+        );
+        effects
+    }
+
+    /// Handles the given peer acknowledging that it has stored the item we sent it.
+    fn handle_get_response_ack(&mut self, item_id: T::Id, sender: NodeId) -> Effects<Event<T>> {
+        if self
+            .unacked_get_responses
+            .remove(&(item_id.clone(), sender))
+            .is_some()
+        {
+            debug!(%item_id, %sender, "peer acknowledged get-response");
+        } else {
+            debug!(%item_id, %sender, "ignoring unexpected get-response ack");
+        }
+        Effects::new()
+    }
+
+    /// Checks whether the given peer has acknowledged the item we sent it, re-sending the item if
+    /// not, up to `MAX_GET_RESPONSE_RESENDS` times.
+    fn check_get_response_ack_timeout<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        requester: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<StorageRequest> + Send,
+        Self: ItemProvider<T>,
+    {
+        let key = (item_id.clone(), requester);
+        let resends = match self.unacked_get_responses.get_mut(&key) {
+            Some(resends) => resends,
+            None => return Effects::new(),
+        };
+        if *resends >= MAX_GET_RESPONSE_RESENDS {
+            debug!(
+                %item_id, %requester,
+                "giving up re-sending unacknowledged get-response"
+            );
+            let _ = self.unacked_get_responses.remove(&key);
+            return Effects::new();
+        }
+        *resends += 1;
+        debug!(%item_id, %requester, "re-sending unacknowledged get-response");
+        self.get_from_storage_for(effect_builder, item_id, requester)
+    }
+
+    /// Handles a response having been handed to the network, sending as many queued responses as
+    /// now fit within the limit on in-flight bytes.
+    fn handle_get_response_sent<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        size: usize,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        self.inflight_get_response_bytes = self.inflight_get_response_bytes.saturating_sub(size);
+        let mut effects = Effects::new();
+        while let Some((item, requester, size)) = self.queued_get_responses.pop_front() {
+            if self.exceeds_inflight_limit(size) {
+                self.queued_get_responses
+                    .push_front((item, requester, size));
+                break;
+            }
+            let item_id = item.gossip_id();
+            effects.extend(self.send_get_response(effect_builder, item_id, item, requester, size));
+        }
+        effects
+    }
+
+    /// Returns `true` if sending a response of the given size now would take the bytes in flight
+    /// over the configured limit.  A response is never held back if none are in flight.
+    fn exceeds_inflight_limit(&self, size: usize) -> bool {
+        self.max_inflight_get_response_bytes != 0
+            && self.inflight_get_response_bytes != 0
+            && self.inflight_get_response_bytes + size > self.max_inflight_get_response_bytes
+    }
+
+    /// Handles the `None` case when attempting to get the item from storage, or the case where the
+    /// retrieved item was found to be corrupt.
+    fn failed_to_get_from_storage<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        corruption: Option<ItemCorruption>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<GossiperAnnouncement<T>> + Send,
+    {
+        let kind = corruption.map_or(StorageErrorKind::NotFound, StorageErrorKind::Corrupt);
+        if self.should_log_storage_error(kind) {
+            match corruption {
+                Some(corruption) => error!(
+                    %corruption,
+                    "finished gossiping {} since item retrieved from storage is corrupt",
+                    item_id
+                ),
+                None => error!(
+                    "finished gossiping {} since failed to get from storage",
+                    item_id
+                ),
+            }
+        }
+
+        if self.table.force_finish(&item_id) {
+            return self.announce_finished(effect_builder, item_id);
+        }
+
+        Effects::new()
+    }
+
+    /// Records a storage error of the given kind, returning whether it should be logged.
+    ///
+    /// If it should, any errors of the same kind not logged since the last one was are summarized.
+    fn should_log_storage_error(&mut self, kind: StorageErrorKind) -> bool {
+        match self.storage_error_sampler.sample(kind) {
+            None => false,
+            Some((0, _)) => true,
+            Some((suppressed, elapsed)) => {
+                error!(
+                    "{}: {} more \"{}\" errors in the last {} seconds",
+                    self.name,
+                    suppressed,
+                    kind,
+                    elapsed.as_secs()
+                );
+                true
             }
-            GossipAction::Noop
-            | GossipAction::AwaitingRemainder
-            | GossipAction::AnnounceFinished => {
-                trace!(item=%item_id, %sender, %action, "received gossip request");
-                // Send a response to the sender indicating we already hold the item.
-                let reply = Message::GossipResponse {
-                    item_id: item_id.clone(),
-                    is_already_held: true,
-                };
-                let mut effects = effect_builder.send_message(sender, reply).ignore();
+        }
+    }
 
-                if action == GossipAction::AnnounceFinished {
-                    effects.extend(
-                        effect_builder
-                            .announce_finished_gossiping(item_id.clone())
-                            .ignore(),
+    /// Performs the housekeeping common to gossipers of large and small items: logging suppressed
+    /// storage errors, decaying peer activity and releasing items whose quarantine has elapsed.
+    fn do_housekeeping(&mut self) {
+        trace!("{}: housekeeping", self.name);
+        self.log_suppressed_storage_errors();
+        if let Some(peers) = self.metrics.peers.as_mut() {
+            peers.decay_activity();
+        }
+        self.quarantine.purge();
+    }
+
+    /// Summarizes the storage errors not logged during sampling intervals which have since
+    /// elapsed without a further error of the same kind.
+    fn log_suppressed_storage_errors(&mut self) {
+        for (kind, suppressed, elapsed) in self.storage_error_sampler.take_elapsed() {
+            error!(
+                "{}: {} more \"{}\" errors in the last {} seconds",
+                self.name,
+                suppressed,
+                kind,
+                elapsed.as_secs()
+            );
+        }
+    }
+
+    /// Handles a peer's request for an item.  `hops` is the number of times the request has been
+    /// relayed by peers which missed the item in storage, or 0 if it came from the requester
+    /// directly.
+    fn handle_get_item_request<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        requester: NodeId,
+        hops: u8,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + From<StorageRequest> + Send,
+        Self: ItemProvider<T>,
+    {
+        if !self.table.has_entry(&item_id) {
+            debug!(
+                item = %item_id,
+                %requester,
+                "got a gossip get-item request for an item we're not gossiping"
+            );
+            return Effects::new();
+        }
+
+        // We don't hold the full item yet, so trying to get it from storage would fail.
+        if self.table.is_acquiring(&item_id) {
+            return match self.acquiring_item_get_policy {
+                AcquiringItemGetPolicy::Defer => {
+                    debug!(
+                        item = %item_id,
+                        %requester,
+                        "got a gossip get-item request for an item we're still acquiring"
+                    );
+                    let message = Message::NotAvailableYet { item_id };
+                    effect_builder.send_message(requester, message).ignore()
+                }
+                AcquiringItemGetPolicy::Ignore => {
+                    debug!(
+                        item = %item_id,
+                        %requester,
+                        "ignoring gossip get-item request for an item we're still acquiring"
                     );
+                    Effects::new()
                 }
+            };
+        }
 
-                effects
+        if let Some(item) = self.get_cache.get(&item_id) {
+            trace!(item = %item_id, %requester, "serving gossip get-item request from cache");
+            return self.respond_with_item(effect_builder, item_id, item, requester);
+        }
+
+        if hops > 0 {
+            let _ = self
+                .relayed_get_requests
+                .insert((item_id.clone(), requester), hops);
+        }
+        self.get_from_storage_for(effect_builder, item_id, requester)
+    }
+
+    /// Gets an item missing from storage from a known holder other than `requester`, to be relayed
+    /// to `requester` once it arrives.  Does nothing unless read-through is enabled and the request
+    /// hasn't already been relayed `max_read_through_hops` times, bounding the length of any chain
+    /// of relayed requests.
+    fn read_through<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        requester: NodeId,
+        hops: u8,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        if hops >= self.max_read_through_hops {
+            return Effects::new();
+        }
+        if let Some((_, _, requesters)) = self.read_throughs.get_mut(&item_id) {
+            // We're already getting the item, so just relay it to this requester too.
+            if !requesters.contains(&requester) {
+                requesters.push(requester);
             }
+            return Effects::new();
+        }
+        let our_id = self.our_id;
+        let holder = match self
+            .table
+            .find_holder(&item_id, |peer| *peer != requester && Some(*peer) != our_id)
+        {
+            Some(holder) => holder,
+            None => return Effects::new(),
         };
-        if T::REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT {
-            effects.extend(
-                effect_builder
-                    .announce_gossip_received(item_id, sender)
-                    .ignore(),
+        debug!(
+            item = %item_id, %requester, %holder, hops,
+            "{}: getting item missing from storage from holder to relay", self.name
+        );
+        let _ = self
+            .read_throughs
+            .insert(item_id.clone(), (holder, Instant::now(), vec![requester]));
+        let message = Message::RelayedGetItem {
+            item_id,
+            hops: hops + 1,
+        };
+        effect_builder.send_message(holder, message).ignore()
+    }
+
+    /// Relays the given item to the peers which requested it from us while it was missing from
+    /// storage, provided it was sent by the holder we got it from.
+    fn relay_read_through<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item: &T,
+        sender: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + Send,
+    {
+        let item_id = item.gossip_id();
+        match self.read_throughs.get(&item_id) {
+            Some((holder, _, _)) if *holder == sender => (),
+            _ => return Effects::new(),
+        }
+        let requesters = self
+            .read_throughs
+            .remove(&item_id)
+            .map(|(_, _, requesters)| requesters)
+            .unwrap_or_default();
+        if let Err(corruption) = check_item_consistency(&item_id, item) {
+            warn!(
+                item = %item_id, %sender, %corruption,
+                "{}: not relaying corrupt item", self.name
             );
+            return Effects::new();
+        }
+        let mut effects = Effects::new();
+        for requester in requesters {
+            debug!(item = %item_id, %requester, "{}: relaying item", self.name);
+            effects.extend(self.respond_with_item(
+                effect_builder,
+                item_id.clone(),
+                Box::new(item.clone()),
+                requester,
+            ));
         }
         effects
     }
 
-    /// Handles an incoming gossip response from a peer on the network.
-    fn handle_gossip_response<REv>(
+    /// Gets the given item from storage in order to send it to `requester`.  If the
+    /// `get_request_batch_window` is non-zero, the get is deferred to be batched with any others
+    /// arriving during the window.
+    fn get_from_storage_for<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         item_id: T::Id,
-        is_already_held: bool,
+        requester: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<StorageRequest> + Send,
+        Self: ItemProvider<T>,
+    {
+        if !self.get_request_batch_window.is_zero() {
+            let start_window = self.pending_get_requests.is_empty();
+            self.pending_get_requests
+                .entry(item_id)
+                .or_default()
+                .push(requester);
+            if !start_window {
+                return Effects::new();
+            }
+            return effect_builder
+                .set_timeout(self.get_request_batch_window)
+                .event(|_| Event::ServeGetRequestBatch);
+        }
+        self.record_get_from_storage_start(&item_id);
+        let storage_key = T::storage_key(&item_id);
+        self.with_storage_permit(Self::get_from_storage(effect_builder, storage_key))
+            .event(move |maybe_item| Event::GetFromStorageResult {
+                item_id,
+                requester,
+                maybe_item,
+            })
+    }
+
+    /// Reads the items requested during the last `get_request_batch_window` from storage in a
+    /// single batch, yielding a `GetFromStorageResult` per requester of each item.
+    fn serve_get_request_batch<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<StorageRequest> + Send,
+        Self: ItemProvider<T>,
+    {
+        let pending_get_requests = mem::take(&mut self.pending_get_requests);
+        if pending_get_requests.is_empty() {
+            return Effects::new();
+        }
+        let (item_ids, requesters): (Vec<_>, Vec<_>) = pending_get_requests.into_iter().unzip();
+        for item_id in &item_ids {
+            self.record_get_from_storage_start(item_id);
+        }
+        let storage_keys = item_ids.iter().map(T::storage_key).collect();
+        self.with_storage_permit(Self::get_many_from_storage(effect_builder, storage_keys))
+            .events(move |maybe_items| {
+                item_ids
+                    .into_iter()
+                    .zip(requesters)
+                    .zip(maybe_items)
+                    .flat_map(move |((item_id, requesters), maybe_item)| {
+                        requesters
+                            .into_iter()
+                            .map(move |requester| Event::GetFromStorageResult {
+                                item_id: item_id.clone(),
+                                requester,
+                                maybe_item: maybe_item.clone(),
+                            })
+                    })
+            })
+    }
+
+    fn handle_get_range_request<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        offset: u64,
+        len: u32,
+        requester: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + From<StorageRequest> + Send,
+        Self: ItemProvider<T>,
+    {
+        if !self.table.has_entry(&item_id) {
+            debug!(
+                item = %item_id,
+                %requester,
+                "got a gossip get-range request for an item we're not gossiping"
+            );
+            return Effects::new();
+        }
+
+        if len > MAX_GET_RANGE_LENGTH {
+            let error = RangeError::TooLong { len };
+            debug!(item = %item_id, %requester, %error, "rejecting gossip get-range request");
+            let message = Message::GetRangeResponse {
+                item_id,
+                offset,
+                maybe_bytes: None,
+            };
+            return effect_builder.send_message(requester, message).ignore();
+        }
+
+        self.record_get_from_storage_start(&item_id);
+        let storage_key = T::storage_key(&item_id);
+        self.with_storage_permit(Self::get_from_storage(effect_builder, storage_key))
+            .event(move |maybe_item| Event::GetRangeFromStorageResult {
+                item_id,
+                requester,
+                offset,
+                len,
+                maybe_item,
+            })
+    }
+
+    /// Handles the result of getting the item from storage in order to send the requested range of
+    /// its serialized form to the requester.
+    ///
+    /// The requester is always sent a response, with no bytes if the item couldn't be retrieved or
+    /// the range isn't within its bounds.  As for full items, a corrupt item is never sent.
+    fn get_range_from_storage<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: T::Id,
+        offset: u64,
+        len: u32,
+        maybe_item: Option<Box<T>>,
+        requester: NodeId,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<NetworkRequest<Message<T>>> + From<GossiperAnnouncement<T>> + Send,
+    {
+        let mut effects = Effects::new();
+        let maybe_serialized_item = match maybe_item {
+            Some(item) => match check_item_consistency(&item_id, &item) {
+                Ok(()) => bincode::serialize(&item).ok(),
+                Err(corruption) => {
+                    effects.extend(self.failed_to_get_from_storage(
+                        effect_builder,
+                        item_id.clone(),
+                        Some(corruption),
+                    ));
+                    None
+                }
+            },
+            None => {
+                warn!(item = %item_id, "failed to get item from storage for gossip get-range");
+                None
+            }
+        };
+        let maybe_bytes = maybe_serialized_item.and_then(|serialized_item| {
+            match item_range(&serialized_item, offset, len) {
+                Ok(bytes) => Some(bytes.to_vec()),
+                Err(error) => {
+                    debug!(item = %item_id, %requester, %error, "can't serve gossip get-range");
+                    None
+                }
+            }
+        });
+        let message = Message::GetRangeResponse {
+            item_id,
+            offset,
+            maybe_bytes,
+        };
+        effects.extend(effect_builder.send_message(requester, message).ignore());
+        effects
+    }
+
+    fn handle_item_received_from_peer<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item: Box<T>,
         sender: NodeId,
     ) -> Effects<Event<T>>
     where
-        REv: From<NetworkRequest<Message<T>>>
+        REv: From<GossiperAnnouncement<T>>
+            + From<NetworkRequest<Message<T>>>
             + From<StorageRequest>
-            + From<GossiperAnnouncement<T>>
             + Send,
         Self: ItemProvider<T>,
     {
-        let mut effects: Effects<_> = Effects::new();
+        let item_id = item.gossip_id();
+        self.clear_outstanding_get(&sender, &item_id);
         if !self.table.has_entry(&item_id) {
             debug!(
                 item = %item_id,
                 %sender,
-                "got a gossip response for an item we're not gossiping"
+                "got a full gossip item for an item we're not gossiping"
             );
-            return effects;
+            return Effects::new();
         }
 
-        let action = if is_already_held {
-            self.table.already_infected(&item_id, sender)
-        } else {
-            if !ID_IS_COMPLETE_ITEM {
-                // `sender` doesn't hold the full item; get the item from the component responsible
-                // for holding it, then send it to `sender`.
-                let cloned_id = item_id.clone();
-                effects.extend(
-                    Self::get_from_storage(effect_builder, item_id.clone()).event(
-                        move |maybe_item| Event::GetFromStorageResult {
-                            item_id: cloned_id,
-                            requester: sender,
-                            maybe_item,
-                        },
-                    ),
-                );
+        match self.trust_level(&sender) {
+            TrustLevel::Trusted => (),
+            TrustLevel::Untrusted => {
+                // Don't use the cached size, as the cache is keyed by ID, not contents.
+                let size = item
+                    .serialized_size_hint()
+                    .unwrap_or_else(|| serialized_size(&*item));
+                if self.max_untrusted_item_size != 0 && size > self.max_untrusted_item_size {
+                    warn!(
+                        item = %item_id,
+                        %sender,
+                        size,
+                        "got an oversized gossip item from an untrusted peer"
+                    );
+                    self.rejected_peers.record(sender);
+                    return Effects::new();
+                }
             }
-            self.table.we_infected(&item_id, sender)
-        };
-
-        match action {
-            GossipAction::ShouldGossip(should_gossip) => effects.extend(Self::gossip(
-                effect_builder,
-                item_id,
-                should_gossip.target,
-                should_gossip.count,
-                should_gossip.exclude_peers,
-            )),
-            GossipAction::Noop => (),
-            GossipAction::AnnounceFinished => {
-                effects.extend(effect_builder.announce_finished_gossiping(item_id).ignore())
+            TrustLevel::Rejected => {
+                debug!(item = %item_id, %sender, "got a gossip item from a rejected peer");
+                return Effects::new();
             }
-            GossipAction::GetRemainder { .. } => {
-                error!("shouldn't try to get remainder as result of receiving a gossip response");
+        }
+
+        if item.is_expired(Timestamp::now()) {
+            debug!(item = %item_id, %sender, "got an expired gossip item");
+            let mut effects = Effects::new();
+            if !self.reject_expired_items && self.table.is_acquiring(&item_id) {
+                effects.extend(self.put_or_queue_item(effect_builder, item, sender));
             }
-            GossipAction::AwaitingRemainder => {
-                warn!(
-                    "shouldn't have gossiped if we don't hold the complete item - possible \
-                    significant latency, or malicious peer"
-                );
+            effects.extend(
+                effect_builder
+                    .immediately()
+                    .event(move |_| Event::ItemExpired { item_id }),
+            );
+            return effects;
+        }
+
+        let mut effects = self.relay_read_through(effect_builder, &item, sender);
+
+        // We already hold an item under this ID, so compare the two before storing anything.
+        if !self.table.is_acquiring(&item_id) {
+            if let Some(held_content_hash) = self.held_content_hashes.get(&item_id).copied() {
+                effects.extend(self.resolve_collision(
+                    effect_builder,
+                    item,
+                    sender,
+                    Some(held_content_hash),
+                ));
+                return effects;
             }
+            self.record_get_from_storage_start(&item_id);
+            let storage_key = T::storage_key(&item_id);
+            effects.extend(
+                self.with_storage_permit(Self::get_from_storage(effect_builder, storage_key))
+                    .event(move |maybe_stored_item| Event::CollisionCheckResult {
+                        item,
+                        sender,
+                        maybe_stored_item,
+                    }),
+            );
+            return effects;
         }
 
+        self.preferred_holders.record_delivery(sender);
+        effects.extend(self.put_or_queue_item(effect_builder, item, sender));
         effects
     }
 
-    /// Handles the `Some` case when attempting to get the item from storage in order to send it to
-    /// the requester.
-    fn got_from_storage<REv>(
+    /// Returns the most preferred known holder of the given item which isn't already handling too
+    /// many of our requests, if any.
+    fn preferred_holder(&self, item_id: &T::Id) -> Option<NodeId> {
+        self.preferred_holders
+            .ranked()
+            .into_iter()
+            .find(|peer| self.table.is_holder(item_id, peer) && !self.exceeds_get_budget(peer))
+    }
+
+    /// Handles the result of getting the item we already hold under the ID of one received from a
+    /// peer.  If their contents differ, the collision is resolved according to the configured
+    /// `IdCollisionPolicy`.
+    fn handle_collision_check_result<REv>(
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         item: Box<T>,
-        requester: NodeId,
+        sender: NodeId,
+        maybe_stored_item: Option<Box<T>>,
     ) -> Effects<Event<T>>
     where
-        REv: From<NetworkRequest<Message<T>>> + Send,
+        REv: From<GossiperAnnouncement<T>> + Send,
     {
-        let message = Message::Item(item);
-        effect_builder.send_message(requester, message).ignore()
+        let item_id = item.gossip_id();
+        let stored_item = match maybe_stored_item {
+            Some(stored_item) => stored_item,
+            None => {
+                debug!(
+                    item = %item_id,
+                    %sender,
+                    "item no longer in storage, handling received item as new"
+                );
+                return self.put_or_queue_item(effect_builder, item, sender);
+            }
+        };
+        let stored_content_hash = content_hash(&*stored_item);
+        if let Some(held_content_hash) = stored_content_hash {
+            self.record_held_content_hash(item_id, held_content_hash);
+        }
+        self.resolve_collision(effect_builder, item, sender, stored_content_hash)
     }
 
-    /// Handles the `None` case when attempting to get the item from storage.
-    fn failed_to_get_from_storage<REv>(
+    /// Caches the content hash of a held item, first dropping those of items no longer in the
+    /// gossip table if there are as many cached as there are table entries.
+    fn record_held_content_hash(&mut self, item_id: T::Id, held_content_hash: Digest) {
+        if self.held_content_hashes.len()
+            >= self.table.items_current() + self.table.items_finished()
+        {
+            let table = &self.table;
+            self.held_content_hashes
+                .retain(|held_item_id, _| table.has_entry(held_item_id));
+        }
+        let _ = self.held_content_hashes.insert(item_id, held_content_hash);
+    }
+
+    /// Resolves a received item against the content hash of the item already held under the same
+    /// ID: an identical item is ignored, otherwise the collision is handled as per
+    /// `Config::id_collision_policy`.
+    fn resolve_collision<REv>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
-        item_id: T::Id,
+        item: Box<T>,
+        sender: NodeId,
+        held_content_hash: Option<Digest>,
     ) -> Effects<Event<T>>
     where
         REv: From<GossiperAnnouncement<T>> + Send,
     {
-        error!(
-            "finished gossiping {} since failed to get from storage",
-            item_id
-        );
+        let item_id = item.gossip_id();
+        if content_hash(&*item) == held_content_hash {
+            debug!(item = %item_id, %sender, "ignoring duplicate of item already held");
+            return Effects::new();
+        }
 
-        if self.table.force_finish(&item_id) {
-            return effect_builder.announce_finished_gossiping(item_id).ignore();
+        match self.id_collision_policy {
+            IdCollisionPolicy::KeepExisting => {
+                warn!(
+                    item = %item_id,
+                    %sender,
+                    "received item collides with one already held; keeping existing item"
+                );
+                Effects::new()
+            }
+            IdCollisionPolicy::RejectNew => {
+                warn!(
+                    item = %item_id,
+                    %sender,
+                    "received item collides with one already held; rejecting it"
+                );
+                self.rejected_peers.record(sender);
+                Effects::new()
+            }
+            IdCollisionPolicy::ReplaceWithNew => {
+                warn!(
+                    item = %item_id,
+                    %sender,
+                    "received item collides with one already held; replacing existing item"
+                );
+                // The held item is being replaced, so its content hash no longer applies.
+                let _ = self.held_content_hashes.remove(&item_id);
+                self.put_or_queue_item(effect_builder, item, sender)
+            }
         }
-
-        Effects::new()
     }
 
-    fn handle_get_item_request<REv>(
-        &self,
+    /// Puts the received item to storage, or queues it if the maximum number of puts are already
+    /// in flight.
+    fn put_or_queue_item<REv>(
+        &mut self,
         effect_builder: EffectBuilder<REv>,
-        item_id: T::Id,
-        requester: NodeId,
+        item: Box<T>,
+        sender: NodeId,
     ) -> Effects<Event<T>>
     where
-        REv: From<StorageRequest> + Send,
-        Self: ItemProvider<T>,
+        REv: From<GossiperAnnouncement<T>> + Send,
     {
-        if !self.table.has_entry(&item_id) {
+        let item_id = item.gossip_id();
+        if self.in_flight_puts.len() >= self.max_concurrent_puts
+            && !self.in_flight_puts.contains_key(&item_id)
+        {
             debug!(
                 item = %item_id,
-                %requester,
-                "got a gossip get-item request for an item we're not gossiping"
+                %sender,
+                in_flight = self.in_flight_puts.len(),
+                "queueing received gossip item until a put slot is free"
             );
+            self.queued_puts.push_back((item, sender));
             return Effects::new();
         }
 
-        Self::get_from_storage(effect_builder, item_id.clone()).event(move |maybe_item| {
-            Event::GetFromStorageResult {
-                item_id,
-                requester,
-                maybe_item,
-            }
-        })
+        self.put_item(effect_builder, item, sender)
     }
 
-    fn handle_item_received_from_peer<REv>(
-        &self,
+    /// Announces the received item body so that it can be validated and stored by the relevant
+    /// component, and sets a timeout to check that this happened.
+    fn put_item<REv>(
+        &mut self,
         effect_builder: EffectBuilder<REv>,
         item: Box<T>,
         sender: NodeId,
@@ -503,15 +2539,10 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         REv: From<GossiperAnnouncement<T>> + Send,
     {
         let item_id = item.gossip_id();
-        if !self.table.has_entry(&item_id) {
-            debug!(
-                item = %item_id,
-                %sender,
-                "got a full gossip item for an item we're not gossiping"
-            );
-            return Effects::new();
-        }
-
+        let _ = self
+            .in_flight_puts
+            .entry(item_id.clone())
+            .or_insert_with(Instant::now);
         let mut effects = effect_builder
             .announce_item_body_received_via_gossip(item, sender)
             .ignore();
@@ -523,6 +2554,43 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         effects
     }
 
+    /// Releases the put slot held by the given item, if any, and starts the put of the next queued
+    /// item which is still being gossiped.
+    fn finish_put<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        item_id: &T::Id,
+    ) -> Effects<Event<T>>
+    where
+        REv: From<GossiperAnnouncement<T>> + Send,
+    {
+        if self.in_flight_puts.remove(item_id).is_none() {
+            return Effects::new();
+        }
+        while let Some((item, sender)) = self.queued_puts.pop_front() {
+            if self.table.has_entry(&item.gossip_id()) {
+                return self.put_item(effect_builder, item, sender);
+            }
+        }
+        Effects::new()
+    }
+
+    /// Records the start of a retrieval of the given item from storage, unless one is already
+    /// underway.
+    fn record_get_from_storage_start(&mut self, item_id: &T::Id) {
+        let _ = self
+            .get_from_storage_started
+            .entry(item_id.clone())
+            .or_insert_with(Instant::now);
+    }
+
+    /// Records the latency of a completed retrieval of the given item from storage.
+    fn record_get_from_storage_end(&mut self, item_id: &T::Id) {
+        if let Some(get_started) = self.get_from_storage_started.remove(item_id) {
+            self.metrics.observe_get_from_storage(get_started.elapsed());
+        }
+    }
+
     /// Checks that having made a `NewItemBody` announcement (in `handle_item_received_from_peer`)
     /// we have subsequently received an `ItemReceived` for the item from whichever component is
     /// responsible for validating and storing the item.
@@ -534,10 +2602,74 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
     where
         REv: From<GossiperAnnouncement<T>> + Send,
     {
+        let mut effects = self.finish_put(effect_builder, &item_id);
         if self.table.finish_if_not_held_by_us(&item_id) {
-            return effect_builder.announce_finished_gossiping(item_id).ignore();
+            // The item wasn't validated and stored in time, so ignore it for a while if configured
+            // to.
+            debug!(item=%item_id, "gossip item wasn't validated and stored in time");
+            self.quarantine.insert(item_id.clone());
+            effects.extend(self.announce_finished(effect_builder, item_id));
         }
-        Effects::new()
+        effects
+    }
+
+    /// Returns `true` if the given item is quarantined, in which case gossip about it should be
+    /// dropped without interacting with the gossip table.
+    fn is_quarantined(&self, item_id: &T::Id, sender: NodeId) -> bool {
+        if self.quarantine.contains(item_id) {
+            debug!(item=%item_id, %sender, "ignoring gossip for quarantined item");
+            return true;
+        }
+        false
+    }
+
+    /// Registers this gossiper under its name with the given registry, after which its health is
+    /// published there after every handled event.
+    pub(crate) fn register_with(&mut self, gossiper_registry: &GossiperRegistry) {
+        let published_health = Arc::new(SharedGossipHealth::new(self.health()));
+        gossiper_registry.register(
+            self.name,
+            Arc::clone(&published_health) as Arc<dyn GossipHealthSource>,
+        );
+        self.published_health = Some(published_health);
+    }
+
+    /// Returns a summary of this gossiper's liveness.
+    ///
+    /// An item is deemed stalled if its gossip table entry has not been updated for longer than
+    /// the sum of the gossip, get-remainder and validate-and-store timeouts.
+    pub(crate) fn health(&self) -> GossipHealth {
+        let stall_threshold =
+            self.gossip_timeout + self.get_from_peer_timeout + self.validate_and_store_timeout;
+        GossipHealth {
+            is_accepting_items: self.in_flight_puts.len() < self.max_concurrent_puts,
+            active_item_count: self.table.items_current(),
+            stalled_item_count: self.table.items_stalled(stall_threshold),
+            last_successful_gossip: self.last_successful_gossip,
+        }
+    }
+
+    /// Returns events reporting items which have newly exceeded the
+    /// `awaiting_remainder_stall_threshold` while awaiting their remainder.
+    fn check_awaiting_remainder_stalls<REv>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Effects<Event<T>>
+    where
+        REv: Send,
+    {
+        if self.awaiting_remainder_stall_threshold.is_zero() {
+            return Effects::new();
+        }
+        self.table
+            .take_newly_stuck_awaiting_remainder(self.awaiting_remainder_stall_threshold)
+            .into_iter()
+            .flat_map(|(item_id, elapsed)| {
+                effect_builder
+                    .immediately()
+                    .event(move |_| Event::AwaitingRemainderStalled { item_id, elapsed })
+            })
+            .collect()
     }
 
     /// Updates the gossiper metrics from the state of the gossip table.
@@ -548,7 +2680,102 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Gossiper<ID_IS_CO
         self.metrics
             .table_items_finished
             .set(self.table.items_finished() as i64);
+        self.metrics
+            .table_items_tracked
+            .set(self.table.items_tracked() as i64);
+        self.metrics.queued_puts.set(self.queued_puts.len() as i64);
+        self.metrics
+            .rejected_peers
+            .set(self.rejected_peers.len() as i64);
+        let items_received = self.metrics.items_received.get();
+        if items_received > 0 {
+            self.metrics
+                .amplification_factor
+                .set(self.metrics.times_gossiped.get() as f64 / items_received as f64);
+        }
+        if let Some(published_health) = &self.published_health {
+            published_health.publish(self.health());
+        }
+    }
+}
+
+/// Returns the bincode-serialized size of the given value, or 0 if it can't be serialized.
+fn serialized_size<V: serde::Serialize + ?Sized>(value: &V) -> usize {
+    bincode::serialized_size(value)
+        .map(|size| size as usize)
+        .unwrap_or_default()
+}
+
+/// Returns the leading `len` bytes of the hash of the given serialized item ID.
+fn id_hash_prefix<Id: serde::Serialize>(item_id: &Id, len: usize) -> Vec<u8> {
+    let serialized_id = bincode::serialize(item_id).unwrap_or_default();
+    let hash = Digest::hash(serialized_id);
+    hash.as_ref()[..len.min(Digest::LENGTH)].to_vec()
+}
+
+/// Returns `true` if the hash of the given serialized item ID starts with `prefix`.
+fn id_has_hash_prefix<Id: serde::Serialize>(item_id: &Id, prefix: &[u8]) -> bool {
+    prefix.len() <= Digest::LENGTH && id_hash_prefix(item_id, prefix.len()) == prefix
+}
+
+/// Returns the `len` bytes starting at `offset` of the given serialized item, or an error if the
+/// range is too long or isn't wholly within the bounds of the item.
+fn item_range(serialized_item: &[u8], offset: u64, len: u32) -> Result<&[u8], RangeError> {
+    if len > MAX_GET_RANGE_LENGTH {
+        return Err(RangeError::TooLong { len });
+    }
+    let item_length = serialized_item.len() as u64;
+    let out_of_bounds = RangeError::OutOfBounds {
+        offset,
+        len,
+        item_length,
+    };
+    let end = offset
+        .checked_add(u64::from(len))
+        .filter(|end| *end <= item_length)
+        .ok_or(out_of_bounds)?;
+    Ok(&serialized_item[offset as usize..end as usize])
+}
+
+/// Returns a hash of the item's content, allowing items with the same ID to be compared, or `None`
+/// if the item can't be serialized.
+fn content_hash<T: GossipItem>(item: &T) -> Option<Digest> {
+    bincode::serialize(item).ok().map(Digest::hash)
+}
+
+/// Checks that an item retrieved from storage is held under the requested ID and survives a
+/// serialization round trip unchanged, i.e. is safe to send to a peer.
+fn check_item_consistency<T: GossipItem>(item_id: &T::Id, item: &T) -> Result<(), ItemCorruption> {
+    if item.gossip_id() != *item_id {
+        return Err(ItemCorruption::IdMismatch);
     }
+    let serialized = bincode::serialize(item).map_err(|_| ItemCorruption::Unserializable)?;
+    let serialized_length = serialized.len() as u64;
+    let round_tripped: T =
+        bincode::deserialize(&serialized).map_err(|_| ItemCorruption::InconsistentEncoding {
+            serialized_length,
+            reserialized_length: 0,
+        })?;
+    let reserialized_length =
+        bincode::serialized_size(&round_tripped).map_err(|_| ItemCorruption::Unserializable)?;
+    if reserialized_length != serialized_length || round_tripped != *item {
+        return Err(ItemCorruption::InconsistentEncoding {
+            serialized_length,
+            reserialized_length,
+        });
+    }
+    Ok(())
+}
+
+/// Schedules the next round of housekeeping after `HOUSEKEEPING_INTERVAL`.
+fn schedule_housekeeping<REv, T>(effect_builder: EffectBuilder<REv>) -> Effects<Event<T>>
+where
+    REv: Send,
+    T: GossipItem,
+{
+    effect_builder
+        .set_timeout(HOUSEKEEPING_INTERVAL)
+        .event(|_| Event::Housekeeping)
 }
 
 /// Impl for gossipers of large items, i.e. where `T::ID_IS_COMPLETE_ITEM` is false.
@@ -558,6 +2785,7 @@ where
     REv: From<NetworkRequest<Message<T>>>
         + From<StorageRequest>
         + From<GossiperAnnouncement<T>>
+        + From<FatalAnnouncement>
         + Send,
     Self: ItemProvider<T>,
 {
@@ -569,6 +2797,8 @@ where
         _rng: &mut NodeRng,
         event: Self::Event,
     ) -> Effects<Self::Event> {
+        let op_id = self.op_id_for(&event);
+        let _span_guard = op_id.map(|op_id| error_span!("gossip", %op_id).entered());
         let effects = match event {
             Event::BeginGossipRequest(BeginGossipRequest {
                 item_id,
@@ -585,38 +2815,157 @@ where
                 item_id,
                 source,
                 target,
-            } => self.handle_item_received(effect_builder, item_id, source, target),
+            } => {
+                if self.sync_after_put && source.node_id().is_none() {
+                    // Ensure a locally-submitted item is durably stored before peers are told of
+                    // it.  Items from peers can be re-acquired, so take the faster path.
+                    self.with_storage_permit(effect_builder.sync_storage_to_disk())
+                        .event(move |()| Event::StorageSynced {
+                            item_id,
+                            source,
+                            target,
+                        })
+                } else {
+                    self.handle_item_stored(effect_builder, item_id, source, target)
+                }
+            }
+            Event::StorageSynced {
+                item_id,
+                source,
+                target,
+            } => self.handle_item_stored(effect_builder, item_id, source, target),
+            Event::PutVerificationResult {
+                item_id,
+                source,
+                target,
+                maybe_item,
+            } => self.handle_put_verification_result(
+                effect_builder,
+                item_id,
+                source,
+                target,
+                maybe_item,
+            ),
+            Event::StorageWritabilityChecked {
+                item_id,
+                is_writable,
+            } => self.handle_storage_writability_checked(effect_builder, item_id, is_writable),
+            Event::ItemRejected { item_id, reason } => self.handle_item_rejected(item_id, reason),
+            Event::ItemConverged { item_id, elapsed } => {
+                self.handle_item_converged(item_id, elapsed)
+            }
+            Event::AmplificationLimitHit {
+                item_id,
+                messages_sent,
+            } => self.handle_amplification_limit_hit(item_id, messages_sent),
+            Event::ItemExpired { item_id } => self.handle_item_expired(effect_builder, item_id),
             Event::GossipedTo {
                 item_id,
                 requested_count,
                 peers,
             } => self.gossiped_to(effect_builder, item_id, requested_count, peers),
+            Event::GossipNetworkUnavailable {
+                item_id,
+                gossip_target,
+                requested_count,
+                exclude_peers,
+            } => self.handle_gossip_network_unavailable(
+                effect_builder,
+                item_id,
+                gossip_target,
+                requested_count,
+                exclude_peers,
+            ),
             Event::CheckGossipTimeout { item_id, peer } => {
                 self.check_gossip_timeout(effect_builder, item_id, peer)
             }
             Event::CheckGetFromPeerTimeout { item_id, peer } => {
                 self.check_get_from_peer_timeout(effect_builder, item_id, peer)
             }
+            Event::CheckGetResponseAckTimeout { item_id, requester } => {
+                self.check_get_response_ack_timeout(effect_builder, item_id, requester)
+            }
+            Event::Incoming(GossiperIncoming::<T> { sender, message })
+                if !self.is_acceptable_gossip(&message, sender) =>
+            {
+                Effects::new()
+            }
             Event::Incoming(GossiperIncoming::<T> { sender, message }) => match *message {
-                Message::Gossip(item_id) => {
-                    Self::is_stored(effect_builder, item_id.clone()).event(move |result| {
-                        Event::IsStoredResult {
-                            item_id,
-                            sender,
-                            result,
-                        }
-                    })
+                Message::Gossip(item_id) | Message::SignedGossip { item_id, .. } => {
+                    if self.is_quarantined(&item_id, sender) {
+                        Effects::new()
+                    } else if self.is_echoed_gossip(&item_id, sender) {
+                        self.handle_echoed_gossip(effect_builder, item_id, sender)
+                    } else {
+                        let storage_key = T::storage_key(&item_id);
+                        self.with_storage_permit(Self::is_stored(effect_builder, storage_key))
+                            .event(move |result| Event::IsStoredResult {
+                                item_id,
+                                sender,
+                                result,
+                            })
+                    }
                 }
                 Message::GossipResponse {
                     item_id,
                     is_already_held,
                 } => self.handle_gossip_response(effect_builder, item_id, is_already_held, sender),
                 Message::GetItem(item_id) => {
-                    self.handle_get_item_request(effect_builder, item_id, sender)
+                    self.handle_get_item_request(effect_builder, item_id, sender, 0)
                 }
                 Message::Item(item) => {
                     self.handle_item_received_from_peer(effect_builder, item, sender)
                 }
+                Message::GossipIdPrefix(prefix) => {
+                    self.handle_gossip_id_prefix(effect_builder, prefix, sender)
+                }
+                Message::GetFullId(prefix) => {
+                    self.handle_get_full_id(effect_builder, prefix, sender)
+                }
+                Message::GossipBatch(item_ids) => {
+                    let item_ids: Vec<_> = item_ids
+                        .into_iter()
+                        .filter(|item_id| !self.is_quarantined(item_id, sender))
+                        .collect();
+                    item_ids
+                        .into_iter()
+                        .flat_map(|item_id| {
+                            if self.is_echoed_gossip(&item_id, sender) {
+                                return self.handle_echoed_gossip(effect_builder, item_id, sender);
+                            }
+                            let storage_key = T::storage_key(&item_id);
+                            self.with_storage_permit(Self::is_stored(effect_builder, storage_key))
+                                .event(move |result| Event::IsStoredResult {
+                                    item_id,
+                                    sender,
+                                    result,
+                                })
+                        })
+                        .collect()
+                }
+                Message::NotAvailableYet { item_id } => {
+                    // The pending get-from-peer timeout will cause us to try a different holder, or
+                    // this one again if it's the only one we know of.
+                    debug!(%item_id, %sender, "peer is still acquiring requested item");
+                    Effects::new()
+                }
+                Message::GetRange {
+                    item_id,
+                    offset,
+                    len,
+                } => self.handle_get_range_request(effect_builder, item_id, offset, len, sender),
+                Message::GetRangeResponse {
+                    item_id, offset, ..
+                } => {
+                    debug!(%item_id, offset, %sender, "ignoring unsolicited gossip range");
+                    Effects::new()
+                }
+                Message::GetResponseAck { item_id } => {
+                    self.handle_get_response_ack(item_id, sender)
+                }
+                Message::RelayedGetItem { item_id, hops } => {
+                    self.handle_get_item_request(effect_builder, item_id, sender, hops)
+                }
             },
             Event::CheckItemReceivedTimeout { item_id } => {
                 self.check_item_received_timeout(effect_builder, item_id)
@@ -626,23 +2975,94 @@ where
                 sender,
                 result: is_stored_locally,
             } => {
-                let action = if self.table.has_entry(&item_id) || !is_stored_locally {
-                    self.table.new_data_id(&item_id, sender)
+                if !is_stored_locally && !self.table.make_room_for(&item_id) {
+                    self.handle_item_rejected(item_id, RejectionReason::TableFull)
                 } else {
-                    // We're not already handling this item, and we do have the full item stored, so
-                    // don't initiate gossiping for it.
-                    GossipAction::Noop
-                };
-                self.handle_gossip(effect_builder, item_id, sender, action)
+                    let action = if self.table.has_entry(&item_id) || !is_stored_locally {
+                        self.table.new_data_id(&item_id, sender)
+                    } else {
+                        // We're not already handling this item, and we do have the full item
+                        // stored, so don't initiate gossiping for it.
+                        GossipAction::Noop
+                    };
+                    self.handle_gossip(effect_builder, item_id, sender, action)
+                }
             }
             Event::GetFromStorageResult {
                 item_id,
                 requester,
                 maybe_item,
-            } => match maybe_item {
-                Some(item) => Self::got_from_storage(effect_builder, item, requester),
-                None => self.failed_to_get_from_storage(effect_builder, item_id),
-            },
+            } => {
+                self.record_get_from_storage_end(&item_id);
+                let hops = self
+                    .relayed_get_requests
+                    .remove(&(item_id.clone(), requester))
+                    .unwrap_or_default();
+                match maybe_item {
+                    Some(item) => self.got_from_storage(effect_builder, item_id, item, requester),
+                    None => {
+                        let mut effects =
+                            self.read_through(effect_builder, item_id.clone(), requester, hops);
+                        effects.extend(self.failed_to_get_from_storage(
+                            effect_builder,
+                            item_id,
+                            None,
+                        ));
+                        effects
+                    }
+                }
+            }
+            Event::GetRangeFromStorageResult {
+                item_id,
+                requester,
+                offset,
+                len,
+                maybe_item,
+            } => {
+                self.record_get_from_storage_end(&item_id);
+                self.get_range_from_storage(
+                    effect_builder,
+                    item_id,
+                    offset,
+                    len,
+                    maybe_item,
+                    requester,
+                )
+            }
+            Event::CollisionCheckResult {
+                item,
+                sender,
+                maybe_stored_item,
+            } => {
+                self.record_get_from_storage_end(&item.gossip_id());
+                self.handle_collision_check_result(effect_builder, item, sender, maybe_stored_item)
+            }
+            Event::GetResponseSent { size, .. } => {
+                self.handle_get_response_sent(effect_builder, size)
+            }
+            Event::RefreshMetrics => {
+                // The gauges are published below, as after handling any event.
+                trace!("{}: refreshing metrics", self.name);
+                Effects::new()
+            }
+            Event::Housekeeping => {
+                self.do_housekeeping();
+                self.get_cache.purge_expired();
+                let get_from_peer_timeout = self.get_from_peer_timeout;
+                self.read_throughs
+                    .retain(|_, (_, asked_at, _)| asked_at.elapsed() < get_from_peer_timeout);
+                let mut effects = self.check_awaiting_remainder_stalls(effect_builder);
+                effects.extend(schedule_housekeeping(effect_builder));
+                effects
+            }
+            Event::QuietHoursEnded => self.flush_quiet_queue(effect_builder),
+            Event::ServeGetRequestBatch => self.serve_get_request_batch(effect_builder),
+            Event::FatalInconsistency { detail } => {
+                self.handle_fatal_inconsistency(effect_builder, detail)
+            }
+            Event::AwaitingRemainderStalled { item_id, elapsed } => {
+                self.handle_awaiting_remainder_stalled(item_id, elapsed)
+            }
         };
         self.update_gossip_table_metrics();
         effects
@@ -660,6 +3080,7 @@ where
     REv: From<NetworkRequest<Message<T>>>
         + From<StorageRequest>
         + From<GossiperAnnouncement<T>>
+        + From<FatalAnnouncement>
         + Send,
     Self: ItemProvider<T>,
 {
@@ -671,6 +3092,8 @@ where
         _rng: &mut NodeRng,
         event: Self::Event,
     ) -> Effects<Self::Event> {
+        let op_id = self.op_id_for(&event);
+        let _span_guard = op_id.map(|op_id| error_span!("gossip", %op_id).entered());
         let effects = match event {
             Event::BeginGossipRequest(BeginGossipRequest {
                 item_id,
@@ -687,12 +3110,54 @@ where
                 item_id,
                 source,
                 target,
-            } => self.handle_item_received(effect_builder, item_id, source, target),
+            } => {
+                let item = <T as SmallGossipItem>::id_as_item(&item_id);
+                if !item.should_gossip() {
+                    self.handle_gossip_vetoed(effect_builder, item_id)
+                } else if item.is_expired(Timestamp::now()) {
+                    self.handle_item_expired(effect_builder, item_id)
+                } else {
+                    self.handle_item_received(effect_builder, item_id, source, target)
+                }
+            }
+            event @ Event::PutVerificationResult { .. } => {
+                error!(%event, "unexpected put verification result for small item");
+                Effects::new()
+            }
+            event @ Event::StorageSynced { .. } => {
+                error!(%event, "unexpected storage synced for small item");
+                Effects::new()
+            }
+            event @ Event::StorageWritabilityChecked { .. } => {
+                error!(%event, "unexpected storage writability check for small item");
+                Effects::new()
+            }
+            Event::ItemRejected { item_id, reason } => self.handle_item_rejected(item_id, reason),
+            Event::ItemConverged { item_id, elapsed } => {
+                self.handle_item_converged(item_id, elapsed)
+            }
+            Event::AmplificationLimitHit {
+                item_id,
+                messages_sent,
+            } => self.handle_amplification_limit_hit(item_id, messages_sent),
+            Event::ItemExpired { item_id } => self.handle_item_expired(effect_builder, item_id),
             Event::GossipedTo {
                 item_id,
                 requested_count,
                 peers,
             } => self.gossiped_to(effect_builder, item_id, requested_count, peers),
+            Event::GossipNetworkUnavailable {
+                item_id,
+                gossip_target,
+                requested_count,
+                exclude_peers,
+            } => self.handle_gossip_network_unavailable(
+                effect_builder,
+                item_id,
+                gossip_target,
+                requested_count,
+                exclude_peers,
+            ),
             Event::CheckGossipTimeout { item_id, peer } => {
                 self.check_gossip_timeout(effect_builder, item_id, peer)
             }
@@ -700,11 +3165,29 @@ where
                 error!(%item_id, %peer, "should not timeout getting small item from peer");
                 Effects::new()
             }
+            Event::CheckGetResponseAckTimeout { item_id, requester } => {
+                error!(
+                    %item_id, %requester,
+                    "should not timeout get-response ack for small item"
+                );
+                Effects::new()
+            }
+            Event::Incoming(GossiperIncoming::<T> { sender, message })
+                if !self.is_acceptable_gossip(&message, sender) =>
+            {
+                Effects::new()
+            }
             Event::Incoming(GossiperIncoming::<T> { sender, message }) => match *message {
-                Message::Gossip(item_id) => {
-                    let target = <T as SmallGossipItem>::id_as_item(&item_id).gossip_target();
-                    let action = self.table.new_complete_data(&item_id, Some(sender), target);
-                    self.handle_gossip(effect_builder, item_id, sender, action)
+                Message::Gossip(item_id) | Message::SignedGossip { item_id, .. } => {
+                    if self.is_quarantined(&item_id, sender) {
+                        Effects::new()
+                    } else if self.is_echoed_gossip(&item_id, sender) {
+                        self.handle_echoed_gossip(effect_builder, item_id, sender)
+                    } else {
+                        let target = <T as SmallGossipItem>::id_as_item(&item_id).gossip_target();
+                        let action = self.table.new_complete_data(&item_id, Some(sender), target);
+                        self.handle_gossip(effect_builder, item_id, sender, action)
+                    }
                 }
                 Message::GossipResponse {
                     item_id,
@@ -719,6 +3202,44 @@ where
                     debug!(%item_id, %sender, "unexpected get response for small item");
                     Effects::new()
                 }
+                Message::GossipIdPrefix(prefix) => {
+                    self.handle_gossip_id_prefix(effect_builder, prefix, sender)
+                }
+                Message::GetFullId(prefix) => {
+                    self.handle_get_full_id(effect_builder, prefix, sender)
+                }
+                Message::GossipBatch(item_ids) => {
+                    let mut effects = Effects::new();
+                    for item_id in item_ids {
+                        if self.is_quarantined(&item_id, sender) {
+                            continue;
+                        }
+                        let target = <T as SmallGossipItem>::id_as_item(&item_id).gossip_target();
+                        let action = self.table.new_complete_data(&item_id, Some(sender), target);
+                        effects.extend(self.handle_gossip(effect_builder, item_id, sender, action));
+                    }
+                    effects
+                }
+                Message::NotAvailableYet { item_id } => {
+                    debug!(%item_id, %sender, "unexpected not-available-yet for small item");
+                    Effects::new()
+                }
+                Message::GetRange { item_id, .. } => {
+                    debug!(%item_id, %sender, "unexpected get-range request for small item");
+                    Effects::new()
+                }
+                Message::GetRangeResponse { item_id, .. } => {
+                    debug!(%item_id, %sender, "unexpected get-range response for small item");
+                    Effects::new()
+                }
+                Message::GetResponseAck { item_id } => {
+                    debug!(%item_id, %sender, "unexpected get-response ack for small item");
+                    Effects::new()
+                }
+                Message::RelayedGetItem { item_id, .. } => {
+                    debug!(%item_id, %sender, "unexpected relayed get request for small item");
+                    Effects::new()
+                }
             },
             Event::CheckItemReceivedTimeout { item_id } => {
                 error!(%item_id, "should not timeout item-received for small item");
@@ -739,6 +3260,46 @@ where
                 );
                 Effects::new()
             }
+            Event::GetRangeFromStorageResult {
+                item_id, requester, ..
+            } => {
+                error!(
+                    %item_id, %requester,
+                    "unexpected get-range-from-storage result for small item"
+                );
+                Effects::new()
+            }
+            Event::GetResponseSent {
+                item_id, requester, ..
+            } => {
+                error!(
+                    %item_id, %requester,
+                    "unexpected get-response sent for small item"
+                );
+                Effects::new()
+            }
+            event @ Event::CollisionCheckResult { .. } => {
+                error!(%event, "unexpected collision check result for small item");
+                Effects::new()
+            }
+            Event::RefreshMetrics => {
+                // The gauges are published below, as after handling any event.
+                trace!("{}: refreshing metrics", self.name);
+                Effects::new()
+            }
+            Event::Housekeeping => {
+                self.do_housekeeping();
+                schedule_housekeeping(effect_builder)
+            }
+            Event::QuietHoursEnded => self.flush_quiet_queue(effect_builder),
+            Event::ServeGetRequestBatch => self.serve_get_request_batch(effect_builder),
+            Event::FatalInconsistency { detail } => {
+                self.handle_fatal_inconsistency(effect_builder, detail)
+            }
+            event @ Event::AwaitingRemainderStalled { .. } => {
+                error!(%event, "unexpected remainder stall for small item");
+                Effects::new()
+            }
         };
         self.update_gossip_table_metrics();
         effects
@@ -758,10 +3319,86 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> Debug
             .field("table", &self.table)
             .field("gossip_timeout", &self.gossip_timeout)
             .field("get_from_peer_timeout", &self.get_from_peer_timeout)
+            .field(
+                "get_from_peer_jitter_percent",
+                &self.get_from_peer_jitter_percent,
+            )
+            .field(
+                "max_inflight_get_response_bytes",
+                &self.max_inflight_get_response_bytes,
+            )
+            .field(
+                "inflight_get_response_bytes",
+                &self.inflight_get_response_bytes,
+            )
+            .field("queued_get_responses", &self.queued_get_responses.len())
+            .field("ack_get_responses", &self.ack_get_responses)
+            .field("unacked_get_responses", &self.unacked_get_responses.len())
             .field(
                 "validate_and_store_timeout",
                 &self.validate_and_store_timeout,
             )
+            .field("max_concurrent_puts", &self.max_concurrent_puts)
+            .field("in_flight_puts", &self.in_flight_puts.len())
+            .field(
+                "get_from_storage_started",
+                &self.get_from_storage_started.len(),
+            )
+            .field("queued_puts", &self.queued_puts.len())
+            .field("last_successful_gossip", &self.last_successful_gossip)
+            .field("quarantine", &self.quarantine.len())
+            .field("rejected_peers", &self.rejected_peers.len())
+            .field("verify_after_put", &self.verify_after_put)
+            .field("sync_after_put", &self.sync_after_put)
+            .field("require_signed_gossip", &self.require_signed_gossip)
+            .field("cascade_threshold", &self.cascade_threshold)
+            .field("acquiring_item_get_policy", &self.acquiring_item_get_policy)
+            .field("id_collision_policy", &self.id_collision_policy)
+            .field("resubmission_policy", &self.resubmission_policy)
+            .field("max_amplification", &self.max_amplification)
+            .field("reject_expired_items", &self.reject_expired_items)
+            .field(
+                "max_outstanding_gets_per_peer",
+                &self.max_outstanding_gets_per_peer,
+            )
+            .field("outstanding_gets", &self.outstanding_gets.len())
+            .field(
+                "awaiting_remainder_stall_threshold",
+                &self.awaiting_remainder_stall_threshold,
+            )
+            .field("max_untrusted_item_size", &self.max_untrusted_item_size)
+            .field("peer_trust_set", &self.peer_trust.is_some())
+            .field("quiet_hours_set", &self.quiet_hours.is_some())
+            .field("quiet_queue", &self.quiet_queue.len())
+            .field("our_id", &self.our_id)
+            .field("originated", &self.originated.len())
+            .field("gossip_id_prefix_len", &self.gossip_id_prefix_len)
+            .field("get_request_batch_window", &self.get_request_batch_window)
+            .field("pending_get_requests", &self.pending_get_requests.len())
+            .field(
+                "signing_key",
+                &self.signing_key.as_ref().map(|(_, public_key)| public_key),
+            )
+            .field("peer_signing_key_set", &self.peer_signing_key.is_some())
+            .field("storage_error_sampler", &self.storage_error_sampler)
+            .field("secondary_transports", &self.secondary_transports)
+            .field("published_health", &self.published_health)
+            .field("min_regossip_interval", &self.min_regossip_interval)
+            .field("last_gossiped", &self.last_gossiped)
+            .field("get_cache", &self.get_cache.len())
+            .field("preferred_holders", &self.preferred_holders)
+            .field("max_read_through_hops", &self.max_read_through_hops)
+            .field("relayed_get_requests", &self.relayed_get_requests.len())
+            .field("read_throughs", &self.read_throughs.len())
+            .field("serialized_sizes", &self.serialized_sizes.len())
+            .field("held_content_hashes", &self.held_content_hashes.len())
+            .field(
+                "available_storage_permits",
+                &self
+                    .storage_permits
+                    .as_ref()
+                    .map(|storage_permits| storage_permits.available_permits()),
+            )
             .finish()
     }
 }
@@ -773,13 +3410,67 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> DataSize
 
     const STATIC_HEAP_SIZE: usize = 0;
 
-    #[inline]
     fn estimate_heap_size(&self) -> usize {
         let Gossiper {
             table,
             gossip_timeout,
             get_from_peer_timeout,
+            get_from_peer_jitter_percent,
+            max_inflight_get_response_bytes,
+            inflight_get_response_bytes: _,
+            queued_get_responses,
+            ack_get_responses,
+            unacked_get_responses,
             validate_and_store_timeout,
+            max_concurrent_puts,
+            in_flight_puts,
+            get_from_storage_started,
+            queued_puts,
+            last_successful_gossip: _,
+            quarantine,
+            rejected_peers,
+            verify_after_put,
+            sync_after_put,
+            require_signed_gossip,
+            cascade_threshold,
+            acquiring_item_get_policy,
+            id_collision_policy,
+            resubmission_policy,
+            max_amplification,
+            reject_expired_items,
+            max_outstanding_gets_per_peer,
+            outstanding_gets,
+            awaiting_remainder_stall_threshold,
+            max_untrusted_item_size,
+            // The hooks are counted shallowly, as what their closures capture is unknown.
+            peer_trust,
+            quiet_hours,
+            quiet_queue,
+            our_id,
+            originated,
+            gossip_id_prefix_len,
+            get_request_batch_window,
+            pending_get_requests,
+            // The signing key, published health and storage permits are shared with other
+            // components, so are not counted here.
+            signing_key: _,
+            peer_signing_key,
+            storage_error_sampler,
+            secondary_transports,
+            published_health: _,
+            min_regossip_interval,
+            last_gossiped,
+            storage_permits: _,
+            get_cache,
+            preferred_holders,
+            max_read_through_hops,
+            relayed_get_requests,
+            read_throughs,
+            serialized_sizes,
+            held_content_hashes,
+            rng: _,
+            #[cfg(test)]
+                peer_selector: _,
             name,
             metrics: _,
         } = self;
@@ -787,7 +3478,99 @@ impl<const ID_IS_COMPLETE_ITEM: bool, T: GossipItem + 'static> DataSize
         table.estimate_heap_size()
             + gossip_timeout.estimate_heap_size()
             + get_from_peer_timeout.estimate_heap_size()
+            + get_from_peer_jitter_percent.estimate_heap_size()
+            + max_inflight_get_response_bytes.estimate_heap_size()
+            + ack_get_responses.estimate_heap_size()
             + validate_and_store_timeout.estimate_heap_size()
+            + max_concurrent_puts.estimate_heap_size()
+            + verify_after_put.estimate_heap_size()
+            + sync_after_put.estimate_heap_size()
+            + require_signed_gossip.estimate_heap_size()
+            + cascade_threshold.estimate_heap_size()
+            + acquiring_item_get_policy.estimate_heap_size()
+            + id_collision_policy.estimate_heap_size()
+            + resubmission_policy.estimate_heap_size()
+            + max_amplification.estimate_heap_size()
+            + reject_expired_items.estimate_heap_size()
+            + max_outstanding_gets_per_peer.estimate_heap_size()
+            + awaiting_remainder_stall_threshold.estimate_heap_size()
+            + max_untrusted_item_size.estimate_heap_size()
+            + our_id.estimate_heap_size()
+            + gossip_id_prefix_len.estimate_heap_size()
+            + get_request_batch_window.estimate_heap_size()
+            + max_read_through_hops.estimate_heap_size()
+            + min_regossip_interval.estimate_heap_size()
             + name.estimate_heap_size()
+            // Item IDs and items are counted shallowly, as `GossipItem` doesn't require them to
+            // implement `DataSize`.  Queued and cached items are boxed, so each adds one `T`.
+            + queued_get_responses.capacity() * mem::size_of::<(Box<T>, NodeId, usize)>()
+            + queued_get_responses.len() * mem::size_of::<T>()
+            + queued_puts.capacity() * mem::size_of::<(Box<T>, NodeId)>()
+            + queued_puts.len() * mem::size_of::<T>()
+            + get_cache.estimate_heap_size()
+            + get_cache.len() * mem::size_of::<T>()
+            + quiet_queue.capacity()
+                * mem::size_of::<(T::Id, GossipTarget, usize, HashSet<NodeId>)>()
+            + quiet_queue
+                .iter()
+                .map(|(_, _, _, exclude_peers)| {
+                    estimate_hash_table_heap_size::<NodeId>(exclude_peers.capacity())
+                })
+                .sum::<usize>()
+            + quarantine.estimate_heap_size()
+            + rejected_peers.estimate_heap_size()
+            + storage_error_sampler.estimate_heap_size()
+            + preferred_holders.estimate_heap_size()
+            + peer_trust
+                .as_ref()
+                .map_or(0, |peer_trust| mem::size_of_val(&**peer_trust))
+            + quiet_hours
+                .as_ref()
+                .map_or(0, |quiet_hours| mem::size_of_val(&**quiet_hours))
+            + peer_signing_key
+                .as_ref()
+                .map_or(0, |peer_signing_key| mem::size_of_val(&**peer_signing_key))
+            + secondary_transports.capacity() * mem::size_of::<Arc<dyn GossipTransport<T>>>()
+            + estimate_hash_table_heap_size::<((T::Id, NodeId), u32)>(
+                unacked_get_responses.capacity(),
+            )
+            + estimate_hash_table_heap_size::<(T::Id, Instant)>(in_flight_puts.capacity())
+            + estimate_hash_table_heap_size::<(T::Id, Instant)>(
+                get_from_storage_started.capacity(),
+            )
+            + estimate_hash_table_heap_size::<(T::Id, Instant)>(last_gossiped.capacity())
+            + estimate_hash_table_heap_size::<((T::Id, NodeId), u8)>(
+                relayed_get_requests.capacity(),
+            )
+            + estimate_hash_table_heap_size::<(NodeId, HashSet<T::Id>)>(
+                outstanding_gets.capacity(),
+            )
+            + outstanding_gets
+                .values()
+                .map(|item_ids| estimate_hash_table_heap_size::<T::Id>(item_ids.capacity()))
+                .sum::<usize>()
+            + estimate_hash_table_heap_size::<T::Id>(originated.capacity())
+            + estimate_hash_table_heap_size::<(T::Id, usize)>(serialized_sizes.capacity())
+            + estimate_hash_table_heap_size::<(T::Id, Digest)>(held_content_hashes.capacity())
+            + estimate_hash_table_heap_size::<(T::Id, Vec<NodeId>)>(pending_get_requests.capacity())
+            + pending_get_requests
+                .values()
+                .map(|requesters| requesters.capacity() * mem::size_of::<NodeId>())
+                .sum::<usize>()
+            + estimate_hash_table_heap_size::<(T::Id, (NodeId, Instant, Vec<NodeId>))>(
+                read_throughs.capacity(),
+            )
+            + read_throughs
+                .values()
+                .map(|(_, _, holders)| holders.capacity() * mem::size_of::<NodeId>())
+                .sum::<usize>()
     }
 }
+
+/// Estimates the heap memory used by a `HashMap` or `HashSet` with the given capacity and entry
+/// type, not including any heap memory owned by the entries themselves.
+///
+/// See https://docs.rs/datasize/0.2.9/src/datasize/std.rs.html#213-224 for details.
+fn estimate_hash_table_heap_size<E>(capacity: usize) -> usize {
+    capacity * (mem::size_of::<E>() + 1)
+}
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
 use datasize::DataSize;
 use serde::{
@@ -7,10 +7,11 @@ use serde::{
 };
 use tracing::error;
 
-use casper_types::TimeDiff;
+use casper_types::{TimeDiff, Timestamp};
 
 #[cfg(test)]
 use super::error::Error;
+use super::QuietHours;
 
 const DEFAULT_INFECTION_TARGET: u8 = 3;
 const DEFAULT_SATURATION_LIMIT_PERCENT: u8 = 80;
@@ -19,6 +20,44 @@ pub(super) const DEFAULT_FINISHED_ENTRY_DURATION: &str = "60sec";
 const DEFAULT_GOSSIP_REQUEST_TIMEOUT: &str = "10sec";
 const DEFAULT_GET_REMAINDER_TIMEOUT: &str = "60sec";
 const DEFAULT_VALIDATE_AND_STORE_TIMEOUT: &str = "60sec";
+const DEFAULT_MAX_CONCURRENT_PUTS: u32 = 50;
+const DEFAULT_MIN_VERIFIED_HOLDERS: u8 = 0;
+const DEFAULT_MAX_HOLDER_ATTEMPTS: u32 = 10;
+const DEFAULT_QUARANTINE_DURATION: &str = "0sec";
+const DEFAULT_VERIFY_AFTER_PUT: bool = false;
+const DEFAULT_MAX_TRACKED_ITEMS: u32 = 100_000;
+const DEFAULT_REQUIRE_SIGNED_GOSSIP: bool = false;
+const DEFAULT_CASCADE_THRESHOLD: u32 = 0;
+const DEFAULT_MIN_REGOSSIP_INTERVAL: &str = "0sec";
+const DEFAULT_UNKNOWN_RESPONSE_POLICY: UnknownResponsePolicy = UnknownResponsePolicy::Ignore;
+const DEFAULT_ACQUIRING_ITEM_GET_POLICY: AcquiringItemGetPolicy = AcquiringItemGetPolicy::Ignore;
+const DEFAULT_SYNC_AFTER_PUT: bool = false;
+const DEFAULT_GET_FROM_PEER_JITTER_PERCENT: u8 = 0;
+const DEFAULT_MAX_INFLIGHT_GET_RESPONSE_BYTES: u32 = 0;
+const DEFAULT_ACK_GET_RESPONSES: bool = false;
+const DEFAULT_ID_COLLISION_POLICY: IdCollisionPolicy = IdCollisionPolicy::RejectNew;
+const DEFAULT_MAX_AMPLIFICATION: u32 = 0;
+const DEFAULT_REJECT_EXPIRED_ITEMS: bool = true;
+const DEFAULT_MAX_OUTSTANDING_GETS_PER_PEER: u32 = 0;
+const DEFAULT_AWAITING_REMAINDER_STALL_THRESHOLD: &str = "0sec";
+const DEFAULT_MAX_UNTRUSTED_ITEM_SIZE: u32 = 0;
+const DEFAULT_GOSSIP_ID_PREFIX_LEN: u8 = 0;
+const DEFAULT_GET_REQUEST_BATCH_WINDOW: &str = "0sec";
+const DEFAULT_STORAGE_ERROR_LOG_INTERVAL: &str = "10sec";
+const DEFAULT_MAX_PEERS_IN_METRICS: u16 = 0;
+const DEFAULT_RESUBMISSION_POLICY: ResubmissionPolicy = ResubmissionPolicy::TopUp;
+const DEFAULT_HOLDER_SET_REPRESENTATION: HolderSetRepresentation = HolderSetRepresentation::HashSet;
+const DEFAULT_GET_CACHE_CAPACITY: u32 = 0;
+const DEFAULT_GET_CACHE_TTL: &str = "1min";
+const DEFAULT_MAX_PREFERRED_HOLDERS: u16 = 0;
+const DEFAULT_PREFERRED_HOLDER_HALF_LIFE: &str = "10min";
+const DEFAULT_MAX_READ_THROUGH_HOPS: u8 = 0;
+const DEFAULT_MAX_CONCURRENT_STORAGE_ACCESSES: u32 = 0;
+const DEFAULT_QUIET_HOURS_START: u8 = 0;
+const DEFAULT_QUIET_HOURS_END: u8 = 0;
+const DEFAULT_PEER_TRUST: TrustLevel = TrustLevel::Trusted;
+const HOURS_PER_DAY: u8 = 24;
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
 #[cfg(test)]
 const SMALL_TIMEOUTS_FINISHED_ENTRY_DURATION: &str = "2sec";
 #[cfg(test)]
@@ -28,6 +67,84 @@ const SMALL_TIMEOUTS_GET_REMAINDER_TIMEOUT: &str = "1sec";
 #[cfg(test)]
 const SMALL_TIMEOUTS_VALIDATE_AND_STORE_TIMEOUT: &str = "1sec";
 
+/// How far items pushed to us by a given peer are trusted.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustLevel {
+    /// Items from the peer are accepted without the gossiper's own size checks.
+    #[default]
+    Trusted,
+    /// Items from the peer are only accepted if within `Config::max_untrusted_item_size`.
+    Untrusted,
+    /// Gossip and items from the peer are dropped.
+    Rejected,
+}
+
+/// How to handle a gossip response from a peer for an item not present in the gossip table, e.g.
+/// as it finished gossiping and has since been purged.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UnknownResponsePolicy {
+    /// Ignore the response.
+    #[default]
+    Ignore,
+    /// Create a minimal entry for the item recording the peer as a holder, so the peer can be
+    /// asked for the item should we later need it.
+    Track,
+}
+
+/// How to handle a request from a peer to get an item which we are still acquiring ourselves, i.e.
+/// for which we know the ID but don't yet hold the full item.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AcquiringItemGetPolicy {
+    /// Respond with a `NotAvailableYet` message so the peer can retry later.
+    Defer,
+    /// Ignore the request.
+    #[default]
+    Ignore,
+}
+
+/// How to handle an item received from a peer under the ID of an item we already hold, but with
+/// different content.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IdCollisionPolicy {
+    /// Keep the item we already hold and drop the received one.
+    KeepExisting,
+    /// Drop the received item and record the sending peer as having been rejected.
+    #[default]
+    RejectNew,
+    /// Store the received item in place of the one we already hold.
+    ReplaceWithNew,
+}
+
+/// How to handle an item submitted locally, e.g. by a client, while we are already gossiping it,
+/// e.g. as the client retried the submission.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ResubmissionPolicy {
+    /// Gossip the item to only as many further peers as needed to make up for any gossip which
+    /// has failed so far, without restarting gossiping.
+    #[default]
+    TopUp,
+    /// Leave the gossiping of the item unchanged.
+    Ignore,
+}
+
+/// How the gossip table stores the set of peers known to hold each item.
+#[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HolderSetRepresentation {
+    /// A hash set of the holders' node IDs per item.
+    #[default]
+    HashSet,
+    /// A bitmap per item, indexed by a dense index assigned to each peer the first time it is
+    /// recorded as a holder of any item.  Far smaller than a hash set for items held by many
+    /// peers, at the cost of retaining the node ID of every peer ever recorded as a holder.
+    Bitmap,
+}
+
 /// Configuration options for gossiping.
 #[derive(Copy, Clone, DataSize, Debug, Deserialize, Serialize)]
 pub struct Config {
@@ -56,6 +173,144 @@ pub struct Config {
     /// The timeout duration for a newly-received, gossiped item to be validated and stored by
     /// another component before the gossiper abandons waiting to gossip the item onwards.
     pub validate_and_store_timeout: TimeDiff,
+    /// The maximum number of newly-received items which can be awaiting validation and storage by
+    /// another component at any one time.  Further items are queued until a slot becomes free.
+    pub max_concurrent_puts: u32,
+    /// The minimum number of peers which must be verified as holders, by having been newly
+    /// infected by us, before responses from other peers claiming to already hold the data are
+    /// trusted to indicate saturation.  If 0, such responses are always trusted.
+    pub min_verified_holders: u8,
+    /// The maximum number of holders which will be asked for the remainder of a given item before
+    /// we give up trying to get it.
+    pub max_holder_attempts: u32,
+    /// The duration for which gossip about an item which wasn't validated and stored in time is
+    /// ignored.  If 0, such items are not quarantined, so that slow validation or storage doesn't
+    /// cause valid items to be ignored.
+    pub quarantine_duration: TimeDiff,
+    /// Whether to confirm that storage holds a newly-stored item under its gossiped ID before
+    /// gossiping it onwards.
+    pub verify_after_put: bool,
+    /// The maximum number of distinct items tracked in the gossip table, whether currently being
+    /// gossiped or finished.  When full, the oldest finished item is evicted to make room for a
+    /// new one, or if there are no finished items, the new item is rejected.
+    pub max_tracked_items: u32,
+    /// Whether incoming gossip messages must carry a valid signature over the gossiped item ID.
+    /// Unsigned gossip messages are dropped if so, and our own gossip is signed with the node's
+    /// secret key.  A signature is only valid if made with the key the sending peer authenticated
+    /// with when connecting.  Peers running an older version can't decode signed gossip, so this
+    /// should only be enabled once all peers have upgraded.
+    pub require_signed_gossip: bool,
+    /// The number of known holders of an item at or above which a response to our gossip no
+    /// longer triggers further gossiping of it.  The responding peer is still recorded as a
+    /// holder.  A value of 0 disables this threshold.
+    pub cascade_threshold: u32,
+    /// The minimum duration between successive gossips of the same item.  Gossip triggered sooner
+    /// is deferred until the interval has elapsed.  A value of 0 disables this limit.
+    pub min_regossip_interval: TimeDiff,
+    /// How to handle a gossip response for an item not present in the gossip table.  Entries
+    /// created under the `track` policy count towards `max_tracked_items`.
+    pub unknown_response_policy: UnknownResponsePolicy,
+    /// How to handle a request to get an item which we are still acquiring ourselves.  Peers
+    /// running an older version can't decode the `NotAvailableYet` message sent under the `defer`
+    /// policy, so it should only be used once all peers have upgraded.
+    pub acquiring_item_get_policy: AcquiringItemGetPolicy,
+    /// Whether to flush storage to disk before gossiping a newly-stored item which was submitted
+    /// locally, e.g. a deploy received via the API, so that it isn't lost in a crash after peers
+    /// have been told of it.  Items received from peers are gossiped without flushing.
+    pub sync_after_put: bool,
+    /// The maximum random jitter, as a percentage of `get_remainder_timeout`, added to the timeout
+    /// when getting an item from a peer, so that retries for many items don't synchronize.  Values
+    /// above 100 are treated as 100.  A value of 0 disables jitter.
+    pub get_from_peer_jitter_percent: u8,
+    /// The maximum total serialized size in bytes of items being sent to peers in response to
+    /// their requests at any one time.  Further responses are queued until earlier ones have been
+    /// sent.  A response is always sent if no others are in flight, even if it exceeds the limit.
+    /// A value of 0 means unlimited.
+    pub max_inflight_get_response_bytes: u32,
+    /// Whether to acknowledge items received from peers in response to our requests once they
+    /// have been stored, and to re-send our own responses to peers which don't acknowledge them
+    /// within `gossip_request_timeout`.  Peers running an older version can't decode the
+    /// acknowledgements, so this should only be enabled once all peers have upgraded.
+    pub ack_get_responses: bool,
+    /// How to handle an item received from a peer under the ID of an item we already hold, but
+    /// with different content.
+    pub id_collision_policy: IdCollisionPolicy,
+    /// The maximum number of gossip requests sent to peers for a single item, after which
+    /// gossiping of that item is paused.  A value of 0 means unlimited.
+    pub max_amplification: u32,
+    /// Whether expired items received from peers are dropped rather than passed on to be stored.
+    /// Expired items are never gossiped onwards either way.
+    pub reject_expired_items: bool,
+    /// The maximum number of outstanding requests for items directed at any one peer.  When
+    /// choosing a different holder to get an item from after a request times out, peers at this
+    /// limit are skipped in favor of others where possible.  A value of 0 means unlimited.
+    pub max_outstanding_gets_per_peer: u32,
+    /// The duration for which an item can await its remainder before being reported as stalled
+    /// when metrics are refreshed.  Each item is reported at most once.  A value of 0 disables
+    /// reporting.
+    pub awaiting_remainder_stall_threshold: TimeDiff,
+    /// The maximum serialized size in bytes of an item accepted from a peer deemed untrusted by
+    /// the gossiper's peer trust hook.  Items from trusted peers are not limited.  A value of 0
+    /// means unlimited.
+    pub max_untrusted_item_size: u32,
+    /// The number of leading bytes of the hash of an item's ID to gossip in place of the full ID.
+    /// Peers not recognizing the prefix, or recognizing several IDs with it, request the full
+    /// IDs, costing an extra round trip.  Values above 32 are treated as 32.  Signed gossip always
+    /// carries the full ID.  A value of 0 disables gossiping prefixes.  Peers running an older
+    /// version can't decode prefixes, so this should only be set once all peers have upgraded.
+    pub gossip_id_prefix_len: u8,
+    /// The duration for which get requests from peers are collected before the requested items
+    /// are read from storage in a single transaction.  This delays responses, so values above
+    /// 100ms are treated as 100ms.  A value of 0 disables batching.
+    pub get_request_batch_window: TimeDiff,
+    /// The minimum interval between logging storage errors of the same kind.  The first error of
+    /// each kind is logged immediately, with those following it in the interval only counted and
+    /// summarized once it elapses.  A value of 0 logs every error.
+    pub storage_error_log_interval: TimeDiff,
+    /// The maximum number of peers given their own label in the per-peer gossip metrics.  Only the
+    /// most active peers are labeled, with the rest counted under the label `other`.  A value of 0
+    /// disables the per-peer metrics.
+    pub max_peers_in_metrics: u16,
+    /// How to handle an item submitted locally while we are already gossiping it.
+    pub resubmission_policy: ResubmissionPolicy,
+    /// How the gossip table stores the set of peers known to hold each item.
+    pub holder_set_representation: HolderSetRepresentation,
+    /// The maximum number of complete items read from storage to serve peers' get-item requests
+    /// which are cached in memory to serve further requests for them.  Once full, the least
+    /// recently used item is evicted.  A value of 0 disables the cache.
+    pub get_cache_capacity: u32,
+    /// The maximum time an item is cached to serve get-item requests, after which it is evicted
+    /// regardless of use, so that a change to the item in storage is served from then on.
+    pub get_cache_ttl: TimeDiff,
+    /// The maximum number of peers which have recently delivered items we were getting from them
+    /// to prefer as the holder to get further items from.  Once reached, the least preferred peer
+    /// is dropped.  A value of 0 disables preferring peers.
+    pub max_preferred_holders: u16,
+    /// The time after which a peer's preference, gained by delivering items, halves.  A peer is no
+    /// longer preferred once a little over this time has passed since it last delivered an item.
+    pub preferred_holder_half_life: TimeDiff,
+    /// The maximum number of times a get-item request for an item missing from storage is relayed
+    /// onwards.  If non-zero, an item requested by a peer but missing from storage is got from a
+    /// known holder and relayed to the requester, provided the request hasn't already been relayed
+    /// this many times.  A value of 0 disables relaying.  Peers running an older version can't
+    /// decode relayed requests, so this should only be set once all peers have upgraded.
+    pub max_read_through_hops: u8,
+    /// The maximum number of storage accesses which all gossipers combined can have outstanding
+    /// at any one time.  Further accesses wait, in the order requested, until one completes.  A
+    /// value of 0 leaves storage access unlimited.
+    pub max_concurrent_storage_accesses: u32,
+    /// The UTC hour of the day, from 0 to 23, at which the daily quiet window starts.  During the
+    /// quiet window, gossip of items other than high-priority ones is deferred until it ends.
+    #[serde(deserialize_with = "deserialize_hour_of_day")]
+    pub quiet_hours_start: u8,
+    /// The UTC hour of the day, from 0 to 23, at which the daily quiet window ends.  If equal to
+    /// `quiet_hours_start`, there is no quiet window.
+    #[serde(deserialize_with = "deserialize_hour_of_day")]
+    pub quiet_hours_end: u8,
+    /// How far items pushed to us by peers are trusted: `trusted` accepts them without the
+    /// gossiper's own size checks, `untrusted` limits them to `max_untrusted_item_size`, and
+    /// `rejected` drops all gossip and items from peers.
+    pub peer_trust: TrustLevel,
 }
 
 impl Config {
@@ -78,6 +333,7 @@ impl Config {
             gossip_request_timeout,
             get_remainder_timeout,
             validate_and_store_timeout,
+            ..Default::default()
         })
     }
 
@@ -121,6 +377,174 @@ impl Config {
     pub(crate) fn validate_and_store_timeout(&self) -> TimeDiff {
         self.validate_and_store_timeout
     }
+
+    pub(crate) fn max_concurrent_puts(&self) -> u32 {
+        self.max_concurrent_puts
+    }
+
+    pub(crate) fn min_verified_holders(&self) -> u8 {
+        self.min_verified_holders
+    }
+
+    pub(crate) fn max_holder_attempts(&self) -> u32 {
+        self.max_holder_attempts
+    }
+
+    pub(crate) fn quarantine_duration(&self) -> TimeDiff {
+        self.quarantine_duration
+    }
+
+    pub(crate) fn verify_after_put(&self) -> bool {
+        self.verify_after_put
+    }
+
+    pub(crate) fn max_tracked_items(&self) -> u32 {
+        self.max_tracked_items
+    }
+
+    pub(crate) fn require_signed_gossip(&self) -> bool {
+        self.require_signed_gossip
+    }
+
+    pub(crate) fn cascade_threshold(&self) -> u32 {
+        self.cascade_threshold
+    }
+
+    pub(crate) fn min_regossip_interval(&self) -> TimeDiff {
+        self.min_regossip_interval
+    }
+
+    pub(crate) fn unknown_response_policy(&self) -> UnknownResponsePolicy {
+        self.unknown_response_policy
+    }
+
+    pub(crate) fn acquiring_item_get_policy(&self) -> AcquiringItemGetPolicy {
+        self.acquiring_item_get_policy
+    }
+
+    pub(crate) fn sync_after_put(&self) -> bool {
+        self.sync_after_put
+    }
+
+    pub(crate) fn get_from_peer_jitter_percent(&self) -> u8 {
+        self.get_from_peer_jitter_percent
+    }
+
+    pub(crate) fn max_inflight_get_response_bytes(&self) -> u32 {
+        self.max_inflight_get_response_bytes
+    }
+
+    pub(crate) fn ack_get_responses(&self) -> bool {
+        self.ack_get_responses
+    }
+
+    pub(crate) fn id_collision_policy(&self) -> IdCollisionPolicy {
+        self.id_collision_policy
+    }
+
+    pub(crate) fn max_amplification(&self) -> u32 {
+        self.max_amplification
+    }
+
+    pub(crate) fn reject_expired_items(&self) -> bool {
+        self.reject_expired_items
+    }
+
+    pub(crate) fn max_outstanding_gets_per_peer(&self) -> u32 {
+        self.max_outstanding_gets_per_peer
+    }
+
+    pub(crate) fn awaiting_remainder_stall_threshold(&self) -> TimeDiff {
+        self.awaiting_remainder_stall_threshold
+    }
+
+    pub(crate) fn max_untrusted_item_size(&self) -> u32 {
+        self.max_untrusted_item_size
+    }
+
+    pub(crate) fn gossip_id_prefix_len(&self) -> u8 {
+        self.gossip_id_prefix_len
+    }
+
+    pub(crate) fn get_request_batch_window(&self) -> TimeDiff {
+        self.get_request_batch_window
+    }
+
+    pub(crate) fn storage_error_log_interval(&self) -> TimeDiff {
+        self.storage_error_log_interval
+    }
+
+    pub(crate) fn max_peers_in_metrics(&self) -> u16 {
+        self.max_peers_in_metrics
+    }
+
+    pub(crate) fn resubmission_policy(&self) -> ResubmissionPolicy {
+        self.resubmission_policy
+    }
+
+    pub(crate) fn holder_set_representation(&self) -> HolderSetRepresentation {
+        self.holder_set_representation
+    }
+
+    pub(crate) fn get_cache_capacity(&self) -> u32 {
+        self.get_cache_capacity
+    }
+
+    pub(crate) fn get_cache_ttl(&self) -> TimeDiff {
+        self.get_cache_ttl
+    }
+
+    pub(crate) fn max_preferred_holders(&self) -> u16 {
+        self.max_preferred_holders
+    }
+
+    pub(crate) fn preferred_holder_half_life(&self) -> TimeDiff {
+        self.preferred_holder_half_life
+    }
+
+    pub(crate) fn max_read_through_hops(&self) -> u8 {
+        self.max_read_through_hops
+    }
+
+    pub(crate) fn max_concurrent_storage_accesses(&self) -> u32 {
+        self.max_concurrent_storage_accesses
+    }
+
+    pub(crate) fn peer_trust(&self) -> TrustLevel {
+        self.peer_trust
+    }
+
+    /// Returns a function giving the time remaining until the configured daily quiet window ends,
+    /// or `None` if no quiet window is configured.
+    pub(crate) fn quiet_hours(&self) -> Option<QuietHours> {
+        if self.quiet_hours_start == self.quiet_hours_end {
+            return None;
+        }
+        let start = hour_of_day(self.quiet_hours_start);
+        let end = hour_of_day(self.quiet_hours_end);
+        Some(Box::new(move || {
+            let time_of_day =
+                Duration::from_millis(Timestamp::now().millis() % DAY.as_millis() as u64);
+            remaining_quiet_time(start, end, time_of_day)
+        }))
+    }
+}
+
+/// Returns the time of day at the start of the given hour.
+fn hour_of_day(hour: u8) -> Duration {
+    Duration::from_secs(u64::from(hour) * 60 * 60)
+}
+
+/// Returns the time remaining until the quiet window between `start` and `end` ends, or `None` if
+/// `time_of_day` is outside of it.  The window spans midnight if `end` is before `start`.
+fn remaining_quiet_time(start: Duration, end: Duration, time_of_day: Duration) -> Option<Duration> {
+    if start <= end {
+        (start <= time_of_day && time_of_day < end).then(|| end - time_of_day)
+    } else if time_of_day >= start {
+        Some(DAY - time_of_day + end)
+    } else {
+        (time_of_day < end).then(|| end - time_of_day)
+    }
 }
 
 impl Default for Config {
@@ -133,6 +557,47 @@ impl Default for Config {
             get_remainder_timeout: TimeDiff::from_str(DEFAULT_GET_REMAINDER_TIMEOUT).unwrap(),
             validate_and_store_timeout: TimeDiff::from_str(DEFAULT_VALIDATE_AND_STORE_TIMEOUT)
                 .unwrap(),
+            max_concurrent_puts: DEFAULT_MAX_CONCURRENT_PUTS,
+            min_verified_holders: DEFAULT_MIN_VERIFIED_HOLDERS,
+            max_holder_attempts: DEFAULT_MAX_HOLDER_ATTEMPTS,
+            quarantine_duration: TimeDiff::from_str(DEFAULT_QUARANTINE_DURATION).unwrap(),
+            verify_after_put: DEFAULT_VERIFY_AFTER_PUT,
+            max_tracked_items: DEFAULT_MAX_TRACKED_ITEMS,
+            require_signed_gossip: DEFAULT_REQUIRE_SIGNED_GOSSIP,
+            cascade_threshold: DEFAULT_CASCADE_THRESHOLD,
+            min_regossip_interval: TimeDiff::from_str(DEFAULT_MIN_REGOSSIP_INTERVAL).unwrap(),
+            unknown_response_policy: DEFAULT_UNKNOWN_RESPONSE_POLICY,
+            acquiring_item_get_policy: DEFAULT_ACQUIRING_ITEM_GET_POLICY,
+            sync_after_put: DEFAULT_SYNC_AFTER_PUT,
+            get_from_peer_jitter_percent: DEFAULT_GET_FROM_PEER_JITTER_PERCENT,
+            max_inflight_get_response_bytes: DEFAULT_MAX_INFLIGHT_GET_RESPONSE_BYTES,
+            ack_get_responses: DEFAULT_ACK_GET_RESPONSES,
+            id_collision_policy: DEFAULT_ID_COLLISION_POLICY,
+            max_amplification: DEFAULT_MAX_AMPLIFICATION,
+            reject_expired_items: DEFAULT_REJECT_EXPIRED_ITEMS,
+            max_outstanding_gets_per_peer: DEFAULT_MAX_OUTSTANDING_GETS_PER_PEER,
+            awaiting_remainder_stall_threshold: TimeDiff::from_str(
+                DEFAULT_AWAITING_REMAINDER_STALL_THRESHOLD,
+            )
+            .unwrap(),
+            max_untrusted_item_size: DEFAULT_MAX_UNTRUSTED_ITEM_SIZE,
+            gossip_id_prefix_len: DEFAULT_GOSSIP_ID_PREFIX_LEN,
+            get_request_batch_window: TimeDiff::from_str(DEFAULT_GET_REQUEST_BATCH_WINDOW).unwrap(),
+            storage_error_log_interval: TimeDiff::from_str(DEFAULT_STORAGE_ERROR_LOG_INTERVAL)
+                .unwrap(),
+            max_peers_in_metrics: DEFAULT_MAX_PEERS_IN_METRICS,
+            resubmission_policy: DEFAULT_RESUBMISSION_POLICY,
+            holder_set_representation: DEFAULT_HOLDER_SET_REPRESENTATION,
+            get_cache_capacity: DEFAULT_GET_CACHE_CAPACITY,
+            get_cache_ttl: TimeDiff::from_str(DEFAULT_GET_CACHE_TTL).unwrap(),
+            max_preferred_holders: DEFAULT_MAX_PREFERRED_HOLDERS,
+            preferred_holder_half_life: TimeDiff::from_str(DEFAULT_PREFERRED_HOLDER_HALF_LIFE)
+                .unwrap(),
+            max_read_through_hops: DEFAULT_MAX_READ_THROUGH_HOPS,
+            max_concurrent_storage_accesses: DEFAULT_MAX_CONCURRENT_STORAGE_ACCESSES,
+            quiet_hours_start: DEFAULT_QUIET_HOURS_START,
+            quiet_hours_end: DEFAULT_QUIET_HOURS_END,
+            peer_trust: DEFAULT_PEER_TRUST,
         }
     }
 }
@@ -157,6 +622,23 @@ where
     Ok(saturation_limit_percent)
 }
 
+/// Deserializes a `u8` but fails if it's not a valid hour of the day.
+fn deserialize_hour_of_day<'de, D>(deserializer: D) -> Result<u8, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let hour = u8::deserialize(deserializer)?;
+    if hour >= HOURS_PER_DAY {
+        error!("quiet hour of {} is not a valid hour of the day", hour);
+        return Err(SerdeError::invalid_value(
+            Unexpected::Unsigned(hour as u64),
+            &"a value between 0 and 23 inclusive",
+        ));
+    }
+
+    Ok(hour)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,6 +654,7 @@ mod tests {
             get_remainder_timeout: TimeDiff::from_str(DEFAULT_GET_REMAINDER_TIMEOUT).unwrap(),
             validate_and_store_timeout: TimeDiff::from_str(DEFAULT_VALIDATE_AND_STORE_TIMEOUT)
                 .unwrap(),
+            ..Default::default()
         };
 
         // Parsing should fail.
@@ -189,4 +672,40 @@ mod tests {
         )
         .is_err())
     }
+
+    #[test]
+    fn should_compute_remaining_quiet_time() {
+        let at = |hour: u8, minutes: u64| hour_of_day(hour) + Duration::from_secs(minutes * 60);
+
+        // A window within a single day.
+        let (start, end) = (hour_of_day(1), hour_of_day(5));
+        assert_eq!(remaining_quiet_time(start, end, at(0, 59)), None);
+        assert_eq!(
+            remaining_quiet_time(start, end, at(1, 0)),
+            Some(hour_of_day(4))
+        );
+        assert_eq!(remaining_quiet_time(start, end, at(4, 30)), Some(at(0, 30)));
+        assert_eq!(remaining_quiet_time(start, end, at(5, 0)), None);
+
+        // A window spanning midnight.
+        let (start, end) = (hour_of_day(22), hour_of_day(2));
+        assert_eq!(remaining_quiet_time(start, end, at(21, 59)), None);
+        assert_eq!(
+            remaining_quiet_time(start, end, at(23, 0)),
+            Some(hour_of_day(3))
+        );
+        assert_eq!(
+            remaining_quiet_time(start, end, at(1, 0)),
+            Some(hour_of_day(1))
+        );
+        assert_eq!(remaining_quiet_time(start, end, at(2, 0)), None);
+
+        // Out-of-range hours are rejected.
+        let invalid_config = Config {
+            quiet_hours_start: HOURS_PER_DAY,
+            ..Default::default()
+        };
+        let config_as_json = serde_json::to_string(&invalid_config).unwrap();
+        assert!(serde_json::from_str::<Config>(&config_as_json).is_err());
+    }
 }
@@ -0,0 +1,134 @@
+#[cfg(not(test))]
+use std::time::Instant;
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    hash::Hash,
+    mem,
+    time::Duration,
+};
+
+#[cfg(test)]
+use fake_instant::FakeClock as Instant;
+
+use super::event::ItemCorruption;
+
+/// The kind of a storage error encountered by the gossiper.  Repeated errors of the same kind are
+/// sampled rather than each being logged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(super) enum StorageErrorKind {
+    /// An item to be sent to a peer wasn't found in storage.
+    NotFound,
+    /// An item to be sent to a peer was retrieved from storage, but found to be corrupt.
+    Corrupt(ItemCorruption),
+    /// A newly-stored item wasn't found in storage.
+    MissingAfterPut,
+}
+
+impl Display for StorageErrorKind {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageErrorKind::NotFound => write!(formatter, "item not found in storage"),
+            StorageErrorKind::Corrupt(corruption) => {
+                write!(formatter, "corrupt item in storage: {}", corruption)
+            }
+            StorageErrorKind::MissingAfterPut => {
+                write!(formatter, "newly-stored item missing from storage")
+            }
+        }
+    }
+}
+
+/// The errors of a single kind since the last one logged.
+#[derive(Debug)]
+struct Window {
+    /// The time the last error of this kind was logged.
+    started: Instant,
+    /// The number of errors of this kind not logged since then.
+    suppressed: u64,
+}
+
+/// Limits logging of each kind of error to at most once per interval, counting the errors not
+/// logged so that they can be summarized.
+#[derive(Debug)]
+pub(super) struct ErrorSampler<K> {
+    /// See `Config::storage_error_log_interval`.
+    interval: Duration,
+    windows: HashMap<K, Window>,
+}
+
+impl<K: Copy + Eq + Hash> ErrorSampler<K> {
+    pub(super) fn new(interval: Duration) -> Self {
+        ErrorSampler {
+            interval,
+            windows: HashMap::new(),
+        }
+    }
+
+    /// Returns an estimate of the heap memory used by the sampler.
+    pub(super) fn estimate_heap_size(&self) -> usize {
+        super::estimate_hash_table_heap_size::<(K, Window)>(self.windows.capacity())
+    }
+
+    /// Records an error of the given kind.
+    ///
+    /// Returns `None` if it should not be logged, otherwise the number of errors of this kind not
+    /// logged since the last one was, and the time since then.
+    pub(super) fn sample(&mut self, kind: K) -> Option<(u64, Duration)> {
+        if self.interval.is_zero() {
+            return Some((0, Duration::ZERO));
+        }
+        let now = Instant::now();
+        match self.windows.get_mut(&kind) {
+            Some(window) => {
+                let elapsed = now.saturating_duration_since(window.started);
+                if elapsed < self.interval {
+                    window.suppressed += 1;
+                    return None;
+                }
+                let suppressed = window.suppressed;
+                window.started = now;
+                window.suppressed = 0;
+                Some((suppressed, elapsed))
+            }
+            None => {
+                let _ = self.windows.insert(
+                    kind,
+                    Window {
+                        started: now,
+                        suppressed: 0,
+                    },
+                );
+                Some((0, Duration::ZERO))
+            }
+        }
+    }
+
+    /// Returns the number of errors of each kind not logged during windows which have since
+    /// elapsed, along with the time since the last error of that kind was logged, so that they can
+    /// be summarized even if no further errors of that kind occur.
+    ///
+    /// Elapsed windows are dropped, so the next error of each such kind is logged immediately.
+    pub(super) fn take_elapsed(&mut self) -> Vec<(K, u64, Duration)> {
+        let now = Instant::now();
+        let interval = self.interval;
+        let mut elapsed_windows = vec![];
+        self.windows.retain(|kind, window| {
+            let elapsed = now.saturating_duration_since(window.started);
+            if elapsed < interval {
+                return true;
+            }
+            if window.suppressed > 0 {
+                elapsed_windows.push((*kind, window.suppressed, elapsed));
+            }
+            false
+        });
+        elapsed_windows
+    }
+
+    /// Returns the number of errors of the given kind not logged since the last one was.
+    #[cfg(test)]
+    pub(super) fn suppressed(&self, kind: &K) -> u64 {
+        self.windows.get(kind).map_or(0, |window| window.suppressed)
+    }
+}
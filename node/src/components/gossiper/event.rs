@@ -1,6 +1,7 @@
 use std::{
     collections::HashSet,
     fmt::{self, Display, Formatter},
+    time::Duration,
 };
 
 use derive_more::From;
@@ -13,6 +14,82 @@ use crate::{
     utils::{DisplayIter, Source},
 };
 
+/// The reason a new item was rejected by the gossiper.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) enum RejectionReason {
+    /// The gossip table is tracking its maximum number of items, none of which could be evicted.
+    TableFull,
+}
+
+impl Display for RejectionReason {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectionReason::TableFull => write!(formatter, "gossip table full"),
+        }
+    }
+}
+
+/// The way in which an item retrieved from storage was found to be corrupt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub(crate) enum ItemCorruption {
+    /// The item's ID doesn't match the ID under which it was requested.
+    IdMismatch,
+    /// The item couldn't be serialized.
+    Unserializable,
+    /// The item doesn't survive a serialization round trip unchanged.
+    InconsistentEncoding {
+        serialized_length: u64,
+        reserialized_length: u64,
+    },
+}
+
+impl Display for ItemCorruption {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemCorruption::IdMismatch => write!(formatter, "item ID mismatch"),
+            ItemCorruption::Unserializable => write!(formatter, "item not serializable"),
+            ItemCorruption::InconsistentEncoding {
+                serialized_length,
+                reserialized_length,
+            } => write!(
+                formatter,
+                "inconsistent encoding: {} bytes re-serialized as {} bytes",
+                serialized_length, reserialized_length
+            ),
+        }
+    }
+}
+
+/// The reason a requested range of an item couldn't be served.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub(crate) enum RangeError {
+    /// The requested length exceeds the maximum permitted in a single request.
+    TooLong { len: u32 },
+    /// The requested range isn't wholly within the bounds of the serialized item.
+    OutOfBounds {
+        offset: u64,
+        len: u32,
+        item_length: u64,
+    },
+}
+
+impl Display for RangeError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RangeError::TooLong { len } => write!(formatter, "range of {} bytes too long", len),
+            RangeError::OutOfBounds {
+                offset,
+                len,
+                item_length,
+            } => write!(
+                formatter,
+                "range of {} bytes at {} out of bounds of {} byte item",
+                len, offset, item_length
+            ),
+        }
+    }
+}
+
 /// `Gossiper` events.
 #[derive(Debug, From, Serialize)]
 pub(crate) enum Event<T: GossipItem> {
@@ -25,18 +102,61 @@ pub(crate) enum Event<T: GossipItem> {
         source: Source,
         target: GossipTarget,
     },
+    /// The result of retrieving a newly-stored item from storage to verify it is held under its
+    /// gossiped ID.
+    PutVerificationResult {
+        item_id: T::Id,
+        source: Source,
+        target: GossipTarget,
+        maybe_item: Option<Box<T>>,
+    },
+    /// Storage was flushed to disk after a locally-submitted item was stored, so it can now be
+    /// gossiped.
+    StorageSynced {
+        item_id: T::Id,
+        source: Source,
+        target: GossipTarget,
+    },
+    /// The result of checking whether storage is writable after a locally-submitted item wasn't
+    /// found there having been put.
+    StorageWritabilityChecked { item_id: T::Id, is_writable: bool },
+    /// A new item was rejected rather than being gossiped.
+    ItemRejected {
+        item_id: T::Id,
+        reason: RejectionReason,
+    },
+    /// An item finished gossiping by becoming saturated, `elapsed` after it was first seen.
+    ItemConverged { item_id: T::Id, elapsed: Duration },
+    /// Gossiping of an item was paused having sent `messages_sent` gossip requests, exceeding
+    /// `max_amplification`.
+    AmplificationLimitHit {
+        item_id: T::Id,
+        messages_sent: usize,
+    },
+    /// An item was found to have expired, so is not gossiped onwards.
+    ItemExpired { item_id: T::Id },
     /// The network component gossiped to the included peers.
     GossipedTo {
         item_id: T::Id,
         requested_count: usize,
         peers: HashSet<NodeId>,
     },
+    /// The network component was unavailable to gossip the item, so gossiping should be retried.
+    GossipNetworkUnavailable {
+        item_id: T::Id,
+        gossip_target: GossipTarget,
+        requested_count: usize,
+        exclude_peers: HashSet<NodeId>,
+    },
     /// The timeout for waiting for a gossip response has elapsed and we should check the response
     /// arrived.
     CheckGossipTimeout { item_id: T::Id, peer: NodeId },
     /// The timeout for waiting for the full item has elapsed and we should check the response
     /// arrived.
     CheckGetFromPeerTimeout { item_id: T::Id, peer: NodeId },
+    /// The timeout for waiting for the given peer to acknowledge the item we sent in response to
+    /// its request has elapsed and we should check whether it has been acknowledged by now.
+    CheckGetResponseAckTimeout { item_id: T::Id, requester: NodeId },
     /// An incoming gossip network message.
     #[from]
     Incoming(GossiperIncoming<T>),
@@ -56,6 +176,82 @@ pub(crate) enum Event<T: GossipItem> {
         requester: NodeId,
         maybe_item: Option<Box<T>>,
     },
+    /// The result of the gossiper getting an item from storage in order to send the requested
+    /// range of it to the requesting peer.
+    GetRangeFromStorageResult {
+        item_id: T::Id,
+        requester: NodeId,
+        offset: u64,
+        len: u32,
+        maybe_item: Option<Box<T>>,
+    },
+    /// The result of getting the item we already hold under the ID of one received from a peer,
+    /// so that the two can be compared.
+    CollisionCheckResult {
+        item: Box<T>,
+        sender: NodeId,
+        maybe_stored_item: Option<Box<T>>,
+    },
+    /// An item sent to a peer in response to its request has been handed to the network.
+    GetResponseSent {
+        item_id: T::Id,
+        requester: NodeId,
+        size: usize,
+    },
+    /// A request to publish the gauge metrics from the current state, e.g. on a timer so that they
+    /// remain accurate while the gossiper is idle.  Handling it changes no other state.
+    RefreshMetrics,
+    /// A request to purge expired state and report items stalled awaiting their remainder.
+    /// Handling it schedules the next one, so the reactor need only send the first.
+    Housekeeping,
+    /// The quiet window during which non-urgent gossip was queued has ended, so the queued gossip
+    /// should be sent.
+    QuietHoursEnded,
+    /// An item has been awaiting its remainder for longer than the
+    /// `awaiting_remainder_stall_threshold`.
+    AwaitingRemainderStalled { item_id: T::Id, elapsed: Duration },
+    /// The `get_request_batch_window` has elapsed, so get requests collected during it should be
+    /// served.
+    ServeGetRequestBatch,
+    /// The gossiper reached an internal state which should be impossible, and from which it can't
+    /// recover.
+    FatalInconsistency { detail: String },
+}
+
+impl<T: GossipItem> Event<T> {
+    /// Returns the ID of the single item this event relates to, or `None` if it relates to none or
+    /// several.
+    pub(super) fn item_id(&self) -> Option<T::Id> {
+        match self {
+            Event::BeginGossipRequest(BeginGossipRequest { item_id, .. })
+            | Event::ItemReceived { item_id, .. }
+            | Event::PutVerificationResult { item_id, .. }
+            | Event::StorageSynced { item_id, .. }
+            | Event::StorageWritabilityChecked { item_id, .. }
+            | Event::ItemRejected { item_id, .. }
+            | Event::ItemConverged { item_id, .. }
+            | Event::AmplificationLimitHit { item_id, .. }
+            | Event::ItemExpired { item_id }
+            | Event::GossipedTo { item_id, .. }
+            | Event::GossipNetworkUnavailable { item_id, .. }
+            | Event::CheckGossipTimeout { item_id, .. }
+            | Event::CheckGetFromPeerTimeout { item_id, .. }
+            | Event::CheckGetResponseAckTimeout { item_id, .. }
+            | Event::CheckItemReceivedTimeout { item_id }
+            | Event::IsStoredResult { item_id, .. }
+            | Event::GetFromStorageResult { item_id, .. }
+            | Event::GetRangeFromStorageResult { item_id, .. }
+            | Event::GetResponseSent { item_id, .. }
+            | Event::AwaitingRemainderStalled { item_id, .. } => Some(item_id.clone()),
+            Event::CollisionCheckResult { item, .. } => Some(item.gossip_id()),
+            Event::Incoming(incoming) => incoming.message.item_id(),
+            Event::RefreshMetrics
+            | Event::Housekeeping
+            | Event::QuietHoursEnded
+            | Event::ServeGetRequestBatch
+            | Event::FatalInconsistency { .. } => None,
+        }
+    }
 }
 
 impl<T: GossipItem> Display for Event<T> {
@@ -75,12 +271,66 @@ impl<T: GossipItem> Display for Event<T> {
             } => {
                 write!(formatter, "new item {} received from {}", item_id, source)
             }
+            Event::PutVerificationResult {
+                item_id,
+                maybe_item,
+                ..
+            } => {
+                if maybe_item.is_some() {
+                    write!(
+                        formatter,
+                        "got {} from storage for put verification",
+                        item_id
+                    )
+                } else {
+                    write!(
+                        formatter,
+                        "failed to get {} from storage for put verification",
+                        item_id
+                    )
+                }
+            }
+            Event::StorageSynced { item_id, .. } => {
+                write!(
+                    formatter,
+                    "synced storage to disk after storing {}",
+                    item_id
+                )
+            }
+            Event::StorageWritabilityChecked {
+                item_id,
+                is_writable,
+            } => {
+                write!(
+                    formatter,
+                    "checked storage is writable after failing to store {}: {}",
+                    item_id, is_writable
+                )
+            }
+            Event::ItemRejected { item_id, reason } => {
+                write!(formatter, "rejected new item {}: {}", item_id, reason)
+            }
+            Event::ItemConverged { item_id, elapsed } => {
+                write!(formatter, "{} converged after {:?}", item_id, elapsed)
+            }
+            Event::AmplificationLimitHit {
+                item_id,
+                messages_sent,
+            } => write!(
+                formatter,
+                "paused gossiping {} after sending {} gossip requests",
+                item_id, messages_sent
+            ),
+            Event::ItemExpired { item_id } => write!(formatter, "{} expired", item_id),
             Event::GossipedTo { item_id, peers, .. } => write!(
                 formatter,
                 "gossiped {} to {}",
                 item_id,
                 DisplayIter::new(peers)
             ),
+            Event::GossipNetworkUnavailable { item_id, .. } => {
+                write!(formatter, "network unavailable to gossip {}", item_id)
+            }
             Event::CheckGossipTimeout { item_id, peer } => write!(
                 formatter,
                 "check gossip timeout for {} with {}",
@@ -91,6 +341,11 @@ impl<T: GossipItem> Display for Event<T> {
                 "check get from peer timeout for {} with {}",
                 item_id, peer
             ),
+            Event::CheckGetResponseAckTimeout { item_id, requester } => write!(
+                formatter,
+                "check get-response ack timeout for {} with {}",
+                item_id, requester
+            ),
             Event::Incoming(incoming) => {
                 write!(formatter, "incoming: {}", incoming)
             }
@@ -119,6 +374,63 @@ impl<T: GossipItem> Display for Event<T> {
                     write!(formatter, "failed to get {} from storage", item_id)
                 }
             }
+            Event::GetRangeFromStorageResult {
+                item_id,
+                offset,
+                len,
+                maybe_item,
+                ..
+            } => {
+                if maybe_item.is_some() {
+                    write!(
+                        formatter,
+                        "got {} from storage for range of {} bytes at {}",
+                        item_id, len, offset
+                    )
+                } else {
+                    write!(
+                        formatter,
+                        "failed to get {} from storage for range of {} bytes at {}",
+                        item_id, len, offset
+                    )
+                }
+            }
+            Event::CollisionCheckResult {
+                item,
+                sender,
+                maybe_stored_item,
+            } => {
+                if maybe_stored_item.is_some() {
+                    write!(
+                        formatter,
+                        "got stored item to compare with {} from {}",
+                        item.gossip_id(),
+                        sender
+                    )
+                } else {
+                    write!(
+                        formatter,
+                        "failed to get stored item to compare with {} from {}",
+                        item.gossip_id(),
+                        sender
+                    )
+                }
+            }
+            Event::GetResponseSent {
+                item_id, requester, ..
+            } => write!(formatter, "sent {} to {}", item_id, requester),
+            Event::RefreshMetrics => write!(formatter, "refresh metrics"),
+            Event::Housekeeping => write!(formatter, "housekeeping"),
+            Event::QuietHoursEnded => write!(formatter, "quiet hours ended"),
+            Event::ServeGetRequestBatch => write!(formatter, "serve get request batch"),
+            Event::FatalInconsistency { detail } => {
+                write!(formatter, "fatal inconsistency: {}", detail)
+            }
+            Event::AwaitingRemainderStalled { item_id, elapsed } => write!(
+                formatter,
+                "{} stalled awaiting remainder for {:?}",
+                item_id, elapsed
+            ),
         }
     }
 }
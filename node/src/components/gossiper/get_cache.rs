@@ -0,0 +1,89 @@
+#[cfg(not(test))]
+use std::time::Instant;
+use std::{hash::Hash, mem, time::Duration};
+
+#[cfg(test)]
+use fake_instant::FakeClock as Instant;
+use linked_hash_map::LinkedHashMap;
+
+/// Complete items recently read from storage to serve get-item requests, so that repeated requests
+/// for the same item don't each read it from storage.
+///
+/// Once full, the least recently used item is evicted to make room for a new one.  Regardless of
+/// use, an item is evicted once it has been cached for the configured TTL, so that the cache never
+/// serves an item which has since changed in or been removed from storage for longer than that.
+#[derive(Debug)]
+pub(super) struct GetCache<K: Hash + Eq, V> {
+    /// See `Config::get_cache_capacity`.
+    capacity: usize,
+    /// See `Config::get_cache_ttl`.
+    ttl: Duration,
+    /// The cached items with the time each was cached, least recently used first.
+    entries: LinkedHashMap<K, (Instant, V)>,
+}
+
+impl<K: Clone + Hash + Eq, V: Clone> GetCache<K, V> {
+    pub(super) fn new(capacity: usize, ttl: Duration) -> Self {
+        GetCache {
+            capacity,
+            ttl,
+            entries: LinkedHashMap::new(),
+        }
+    }
+
+    /// Returns a copy of the cached item, or `None` if it isn't cached or has been cached for at
+    /// least the TTL, in which case it is evicted.
+    pub(super) fn get(&mut self, key: &K) -> Option<V> {
+        let is_expired = self.entries.get(key)?.0.elapsed() >= self.ttl;
+        if is_expired {
+            let _ = self.entries.remove(key);
+            return None;
+        }
+        self.entries
+            .get_refresh(key)
+            .map(|(_, value)| value.clone())
+    }
+
+    /// Caches a copy of the given item, replacing any already cached under the same key.  Does
+    /// nothing if the capacity is 0.
+    pub(super) fn insert(&mut self, key: &K, value: &V) {
+        if self.capacity == 0 {
+            return;
+        }
+        let _ = self.entries.remove(key);
+        while self.entries.len() >= self.capacity {
+            let _ = self.entries.pop_front();
+        }
+        let _ = self
+            .entries
+            .insert(key.clone(), (Instant::now(), value.clone()));
+    }
+
+    /// Evicts all items which have been cached for at least the TTL.
+    pub(super) fn purge_expired(&mut self) {
+        let ttl = self.ttl;
+        let expired: Vec<K> = self
+            .entries
+            .iter()
+            .filter(|(_, (cached_at, _))| cached_at.elapsed() >= ttl)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired {
+            let _ = self.entries.remove(&key);
+        }
+    }
+
+    /// Returns the number of cached items, including any expired but not yet evicted.
+    pub(super) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns an estimate of the heap memory used by the cache, not including any heap memory
+    /// owned by the keys or values themselves.
+    pub(super) fn estimate_heap_size(&self) -> usize {
+        // Each entry lives in its own node along with two links, and the nodes are indexed by a
+        // hash table of pointers to them.
+        self.entries.len() * (mem::size_of::<(K, (Instant, V))>() + 2 * mem::size_of::<usize>())
+            + super::estimate_hash_table_heap_size::<(usize, usize)>(self.entries.capacity())
+    }
+}
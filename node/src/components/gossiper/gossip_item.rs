@@ -3,27 +3,70 @@ use std::{
     hash::Hash,
 };
 
+use datasize::DataSize;
 use serde::{de::DeserializeOwned, Serialize};
 
+use casper_types::Timestamp;
+
 use crate::effect::GossipTarget;
 
+/// The relative urgency with which items of a given type are gossiped.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, DataSize)]
+pub(crate) enum Priority {
+    Low,
+    Normal,
+    High,
+}
+
 /// A trait which allows an implementing type to be used by a gossiper component.
 pub(crate) trait GossipItem:
     Clone + Serialize + DeserializeOwned + Send + Sync + Debug + Display + Eq
 {
     /// The type of ID of the item.
     type Id: Clone + Eq + Hash + Serialize + DeserializeOwned + Send + Sync + Debug + Display;
+    /// The type of key under which the item is held in storage.  For most items, this is the same
+    /// as the ID.
+    type StorageKey: Clone + Send + Sync + Debug;
 
     /// Whether the item's ID _is_ the complete item or not.
     const ID_IS_COMPLETE_ITEM: bool;
     /// Whether the arrival of a new gossip message should be announced or not.
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool;
+    /// The priority with which items of this type are gossiped.
+    const PRIORITY: Priority = Priority::Normal;
 
     /// The ID of the specific item.
     fn gossip_id(&self) -> Self::Id;
 
+    /// The key under which the item with the given ID is held in storage.
+    ///
+    /// The gossip protocol only ever uses the ID, with the key used for storage operations.
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey;
+
     /// Identifies the kind of peers which should be targeted for onwards gossiping.
     fn gossip_target(&self) -> GossipTarget;
+
+    /// The serialized size of the item, if known without serializing it.
+    ///
+    /// Types which track their own size should provide this to avoid repeated serialization.
+    fn serialized_size_hint(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether this specific item may be gossiped.
+    ///
+    /// An item returning `false`, e.g. one intended to stay local to this node, is still held as
+    /// normal once received, but is never gossiped onwards.
+    fn should_gossip(&self) -> bool {
+        true
+    }
+
+    /// Whether this specific item has expired as of `now`.
+    ///
+    /// Expired items are not gossiped onwards, as peers would have no use for them.
+    fn is_expired(&self, _now: Timestamp) -> bool {
+        false
+    }
 }
 
 pub(crate) trait LargeGossipItem: GossipItem {}
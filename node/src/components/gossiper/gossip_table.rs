@@ -4,15 +4,23 @@ use std::{
     collections::{HashMap, HashSet},
     fmt::{self, Display, Formatter},
     hash::Hash,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 
 use datasize::DataSize;
 #[cfg(test)]
 use fake_instant::FakeClock as Instant;
-use tracing::{error, trace, warn};
+use tracing::{debug, error, trace, warn};
 
-use super::Config;
+use super::{
+    config::{HolderSetRepresentation, UnknownResponsePolicy},
+    holder_set::{HolderSet, NodeIndex},
+    Config,
+};
 use crate::{effect::GossipTarget, types::NodeId, utils::DisplayIter};
 
 #[derive(Debug, PartialEq, Eq)]
@@ -84,19 +92,52 @@ impl Display for ShouldGossip {
     }
 }
 
+/// Identifies the passage of a single item through the gossiper, from its entry first being created
+/// in the gossip table until gossiping it finishes, so that the related log lines can be
+/// correlated.  IDs are assigned in increasing order.
+#[derive(Copy, Clone, DataSize, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(super) struct GossipOpId(u64);
+
+impl Display for GossipOpId {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(formatter, "gossip-op-{}", self.0)
+    }
+}
+
 #[derive(DataSize, Debug, Default)]
 pub(super) struct State {
     /// The peers excluding us which hold the data.
-    holders: HashSet<NodeId>,
+    holders: HolderSet,
     /// The subset of `holders` we have infected.  Not just a count so we don't attribute the same
     /// peer multiple times.
     infected_by_us: HashSet<NodeId>,
+    /// The peers which have responded to our gossip for this data, whether or not we infected
+    /// them.
+    acknowledged_by: HashSet<NodeId>,
     /// The count of in-flight gossip messages sent by us for this data.
     in_flight_count: usize,
     /// The relevant target for this data, if known yet.
     target: Option<GossipTarget>,
     /// The set of peers we attempted to infect.
     attempted_to_infect: HashSet<NodeId>,
+    /// The time this entry was created.
+    first_seen: Option<Instant>,
+    /// The last time this entry was created or updated.
+    last_progress: Option<Instant>,
+    /// The number of holders we have asked for the remainder of the data.
+    get_remainder_attempts: usize,
+    /// The ID of the gossip operation for this data.
+    op_id: GossipOpId,
+    /// The total number of gossip messages sent to peers for this data, including repeats to the
+    /// same peer.
+    messages_sent: usize,
+    /// Whether gossiping of this data has been paused, having sent too many gossip messages.
+    paused: bool,
+    /// The time we started awaiting the remainder of the data, i.e. when the entry was created.
+    /// Only relevant while we don't hold the data.
+    awaiting_remainder_since: Option<Instant>,
+    /// Whether this entry has been reported as stalled awaiting its remainder.
+    stall_reported: bool,
 }
 
 impl State {
@@ -106,26 +147,45 @@ impl State {
     }
 
     /// Returns whether we should finish gossiping this data.
-    fn is_finished(&self, infection_target: usize, attempted_to_infect_limit: usize) -> bool {
+    ///
+    /// Saturation (reaching `attempted_to_infect_limit`) is only trusted once at least
+    /// `min_verified_holders` peers have been newly infected by us, so that peers falsely claiming
+    /// to already hold the data cannot prematurely end gossiping.
+    fn is_finished(
+        &self,
+        infection_target: usize,
+        attempted_to_infect_limit: usize,
+        min_verified_holders: usize,
+    ) -> bool {
         self.infected_by_us.len() >= infection_target
-            || self.attempted_to_infect.len() >= attempted_to_infect_limit
+            || (self.attempted_to_infect.len() >= attempted_to_infect_limit
+                && self.infected_by_us.len() >= min_verified_holders)
     }
 
-    /// Returns a `GossipAction` derived from the given state.
+    /// Returns a `GossipAction` derived from the given state.  The number of peers to gossip to is
+    /// limited to `max_gossip_count`.
     fn action(
         &mut self,
         infection_target: usize,
         attempted_to_infect_limit: usize,
+        min_verified_holders: usize,
+        max_gossip_count: usize,
         is_new: bool,
+        node_index: &NodeIndex,
     ) -> GossipAction {
-        if self.is_finished(infection_target, attempted_to_infect_limit) {
+        if self.is_finished(
+            infection_target,
+            attempted_to_infect_limit,
+            min_verified_holders,
+        ) {
             return GossipAction::Noop;
         }
 
         if let Some(target) = self.target {
             // The item is held by us, decide whether we should gossip it or not.
-            let count =
-                infection_target.saturating_sub(self.in_flight_count + self.infected_by_us.len());
+            let count = infection_target
+                .saturating_sub(self.in_flight_count + self.infected_by_us.len())
+                .min(max_gossip_count);
             if count > 0 {
                 self.in_flight_count += count;
                 return GossipAction::ShouldGossip(ShouldGossip {
@@ -139,12 +199,14 @@ impl State {
             }
         }
 
-        if is_new {
-            let holder = *self
+        // Entries created only to track holders have not yet been asked for the remainder.
+        if is_new || self.get_remainder_attempts == 0 {
+            let holder = self
                 .holders
-                .iter()
+                .iter(node_index)
                 .next()
                 .expect("holders cannot be empty if we don't hold the data");
+            self.get_remainder_attempts += 1;
             GossipAction::GetRemainder { holder }
         } else {
             GossipAction::AwaitingRemainder
@@ -152,6 +214,23 @@ impl State {
     }
 }
 
+/// A summary of a single gossip table entry, used to inspect the table in tests.
+#[cfg(test)]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub(super) struct EntrySummary {
+    /// The ID of the data.
+    pub(super) id: String,
+    /// The peers known to hold the data, in ascending order.
+    pub(super) holders: Vec<NodeId>,
+    /// Whether we hold the full data.  Always `false` for finished entries, since their details
+    /// are no longer retained.
+    pub(super) is_complete: bool,
+    /// Milliseconds since the entry was created, or `None` for finished entries.
+    pub(super) age_millis: Option<u64>,
+    /// Whether gossiping of the data has finished.
+    pub(super) is_finished: bool,
+}
+
 #[derive(DataSize, Debug)]
 pub(super) struct Timeouts<T> {
     values: Vec<(Instant, T)>,
@@ -191,13 +270,36 @@ pub(super) struct GossipTable<T> {
     finished: HashSet<T>,
     /// Timeouts for removal of items from the `finished` cache.
     timeouts: Timeouts<T>,
+    /// The time taken from first seeing the data to it becoming saturated, for `finished` entries
+    /// which have not yet had this taken via `take_convergence_time`.
+    convergence_times: HashMap<T, Duration>,
+    /// The number of distinct peers which responded to our gossip, for `finished` entries.
+    achieved_degrees: HashMap<T, usize>,
     /// See `Config::infection_target`.
     infection_target: usize,
     /// Derived from `Config::saturation_limit_percent` - we gossip data while the number of
     /// attempts to infect doesn't exceed `attempted_to_infect_limit`.
     attempted_to_infect_limit: usize,
+    /// See `Config::min_verified_holders`.
+    min_verified_holders: usize,
+    /// See `Config::max_holder_attempts`.
+    max_holder_attempts: usize,
     /// See `Config::finished_entry_duration`.
     finished_entry_duration: Duration,
+    /// See `Config::max_tracked_items`.
+    max_tracked_items: usize,
+    /// See `Config::unknown_response_policy`.
+    unknown_response_policy: UnknownResponsePolicy,
+    /// The ID to assign to the next gossip operation.
+    next_op_id: u64,
+    /// See `Config::holder_set_representation`.
+    holder_set_representation: HolderSetRepresentation,
+    /// The indices of peers in bitmap holder sets.  Unused for hash set holder sets.
+    node_index: NodeIndex,
+    /// The number of currently connected peers, kept up to date by the reactor, used to limit the
+    /// number of peers gossiped to at once.  If `None`, the number isn't limited.
+    #[data_size(skip)]
+    peer_count_hint: Option<Arc<AtomicUsize>>,
 }
 
 impl<T> GossipTable<T> {
@@ -206,10 +308,32 @@ impl<T> GossipTable<T> {
         self.current.len()
     }
 
+    /// Returns the IDs of all items currently being gossiped or finished gossiping.
+    pub(super) fn data_ids(&self) -> impl Iterator<Item = &T> {
+        self.current.keys().chain(self.finished.iter())
+    }
+
     /// Number of items that are kept but are finished gossiping.
     pub(super) fn items_finished(&self) -> usize {
         self.finished.len()
     }
+
+    /// Total number of items tracked, whether currently being gossiped or finished.
+    pub(super) fn items_tracked(&self) -> usize {
+        self.current.len() + self.finished.len()
+    }
+
+    /// Number of items currently being gossiped which have not been updated within `threshold`.
+    pub(super) fn items_stalled(&self, threshold: Duration) -> usize {
+        self.current
+            .values()
+            .filter(|state| {
+                state
+                    .last_progress
+                    .map_or(false, |last_progress| last_progress.elapsed() >= threshold)
+            })
+            .count()
+    }
 }
 
 impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
@@ -221,9 +345,75 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
             current: HashMap::new(),
             finished: HashSet::new(),
             timeouts: Timeouts::new(),
+            convergence_times: HashMap::new(),
+            achieved_degrees: HashMap::new(),
             infection_target: usize::from(config.infection_target()),
             attempted_to_infect_limit,
+            min_verified_holders: usize::from(config.min_verified_holders()),
+            max_holder_attempts: config.max_holder_attempts() as usize,
             finished_entry_duration: config.finished_entry_duration().into(),
+            max_tracked_items: config.max_tracked_items() as usize,
+            unknown_response_policy: config.unknown_response_policy(),
+            next_op_id: 0,
+            holder_set_representation: config.holder_set_representation(),
+            node_index: NodeIndex::default(),
+            peer_count_hint: None,
+        }
+    }
+
+    /// Sets the shared count of connected peers, to be updated by the reactor as peers connect and
+    /// disconnect.
+    pub(super) fn set_peer_count_hint(&mut self, peer_count: Arc<AtomicUsize>) {
+        self.peer_count_hint = Some(peer_count);
+    }
+
+    /// Returns the maximum number of peers to gossip to at once, i.e. the number of connected
+    /// peers if known.  At least one is always allowed, so that running out of peers is still
+    /// detected and the entry finishes.
+    fn max_gossip_count(&self) -> usize {
+        self.peer_count_hint
+            .as_ref()
+            .map_or(usize::MAX, |peer_count| {
+                peer_count.load(Ordering::Relaxed).max(1)
+            })
+    }
+
+    /// Returns the data IDs of current entries which we don't hold, which have been awaiting their
+    /// remainder for at least `threshold` and which have not been returned by this method before,
+    /// along with how long each has been awaiting it.
+    pub(super) fn take_newly_stuck_awaiting_remainder(
+        &mut self,
+        threshold: Duration,
+    ) -> Vec<(T, Duration)> {
+        self.current
+            .iter_mut()
+            .filter_map(|(data_id, state)| {
+                if state.held_by_us() || state.stall_reported {
+                    return None;
+                }
+                let elapsed = state.awaiting_remainder_since?.elapsed();
+                if elapsed < threshold {
+                    return None;
+                }
+                state.stall_reported = true;
+                Some((data_id.clone(), elapsed))
+            })
+            .collect()
+    }
+
+    /// Returns a new state for an entry first being added to `current`, with the next gossip
+    /// operation ID.
+    fn new_state(&mut self) -> State {
+        let op_id = GossipOpId(self.next_op_id);
+        self.next_op_id += 1;
+        let now = Instant::now();
+        State {
+            first_seen: Some(now),
+            last_progress: Some(now),
+            awaiting_remainder_since: Some(now),
+            op_id,
+            holders: HolderSet::new(self.holder_set_representation),
+            ..State::default()
         }
     }
 
@@ -244,8 +434,8 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
             return GossipAction::Noop;
         }
 
-        let update = |state: &mut State| {
-            let _ = state.holders.insert(holder);
+        let update = |state: &mut State, node_index: &mut NodeIndex| {
+            let _ = state.holders.insert(holder, node_index);
         };
 
         if let Some(action) = self.update_current(data_id, update) {
@@ -254,16 +444,20 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         }
 
         // This isn't in finished or current - add a new entry to current.
-        let mut state = State::default();
-        update(&mut state);
+        let mut state = self.new_state();
+        update(&mut state, &mut self.node_index);
         let is_new = true;
         let action = state.action(
             self.infection_target,
             self.attempted_to_infect_limit,
+            self.min_verified_holders,
+            self.max_gossip_count(),
             is_new,
+            &self.node_index,
         );
+        let op_id = state.op_id;
         let _ = self.current.insert(data_id.clone(), state);
-        trace!(item=%data_id, %action, "gossiping new item should begin");
+        trace!(item=%data_id, %op_id, %action, "gossiping new item should begin");
         action
     }
 
@@ -288,27 +482,30 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
             return GossipAction::Noop;
         }
 
-        let update = |state: &mut State| {
-            state.holders.extend(maybe_holder);
+        let update = |state: &mut State, node_index: &mut NodeIndex| {
+            state.holders.extend(maybe_holder, node_index);
             state.target = Some(target);
         };
-
         if let Some(action) = self.update_current(data_id, update) {
             trace!(item=%data_id, %action, "item is currently being gossiped");
             return action;
         }
 
         // This isn't in finished or current - add a new entry to current.
-        let mut state = State::default();
-        update(&mut state);
+        let mut state = self.new_state();
+        update(&mut state, &mut self.node_index);
         let is_new = true;
         let action = state.action(
             self.infection_target,
             self.attempted_to_infect_limit,
+            self.min_verified_holders,
+            self.max_gossip_count(),
             is_new,
+            &self.node_index,
         );
+        let op_id = state.op_id;
         let _ = self.current.insert(data_id.clone(), state);
-        trace!(item=%data_id, %action, "gossiping new item should begin");
+        trace!(item=%data_id, %op_id, %action, "gossiping new item should begin");
         action
     }
 
@@ -318,7 +515,11 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         peers: impl Iterator<Item = &'a NodeId>,
     ) {
         if let Some(state) = self.current.get_mut(item_id) {
-            state.attempted_to_infect.extend(peers);
+            for peer in peers {
+                let _ = state.attempted_to_infect.insert(*peer);
+                state.messages_sent += 1;
+            }
+            state.last_progress = Some(Instant::now());
         }
     }
 
@@ -345,7 +546,7 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     }
 
     fn infected(&mut self, data_id: &T, peer: NodeId, by_us: bool) -> GossipAction {
-        let update = |state: &mut State| {
+        let update = |state: &mut State, node_index: &mut NodeIndex| {
             if !state.held_by_us() {
                 warn!(
                     item=%data_id,
@@ -353,7 +554,8 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
                 );
                 return;
             }
-            let _ = state.holders.insert(peer);
+            let _ = state.holders.insert(peer, node_index);
+            let _ = state.acknowledged_by.insert(peer);
             if by_us {
                 let _ = state.infected_by_us.insert(peer);
             }
@@ -364,6 +566,128 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
             .unwrap_or(GossipAction::Noop)
     }
 
+    /// We got a gossip response from a peer for data which has neither a current nor a finished
+    /// entry, e.g. as it finished gossiping and has since been purged.
+    ///
+    /// Under `UnknownResponsePolicy::Track`, a minimal current entry is created recording the peer
+    /// as a holder, provided there is room in the table.  Returns `true` if an entry was created.
+    pub(super) fn unknown_response(&mut self, data_id: &T, peer: NodeId) -> bool {
+        if self.unknown_response_policy == UnknownResponsePolicy::Ignore
+            || self.has_entry(data_id)
+            || !self.make_room_for(data_id)
+        {
+            return false;
+        }
+
+        let mut state = self.new_state();
+        let _ = state.holders.insert(peer, &mut self.node_index);
+        let op_id = state.op_id;
+        let _ = self.current.insert(data_id.clone(), state);
+        trace!(item=%data_id, %op_id, %peer, "tracking holder of unknown item");
+        true
+    }
+
+    /// Merges holders of the given data learned out-of-band, e.g. by a different component fetching
+    /// it directly, into the current entry and returns the action we should now take.
+    ///
+    /// If the data is not currently being gossiped, no entry is created and `GossipAction::Noop` is
+    /// returned.
+    pub(super) fn merge_holders(&mut self, data_id: &T, holders: HashSet<NodeId>) -> GossipAction {
+        let update = |state: &mut State, node_index: &mut NodeIndex| {
+            state.holders.extend(holders.iter().copied(), node_index)
+        };
+        match self.update_current(data_id, update) {
+            Some(action) => {
+                trace!(
+                    item=%data_id,
+                    holders=%DisplayIter::new(holders.iter()),
+                    %action,
+                    "merged holders of item"
+                );
+                action
+            }
+            None => {
+                trace!(item=%data_id, "not merging holders of item not currently being gossiped");
+                GossipAction::Noop
+            }
+        }
+    }
+
+    /// Removes and returns the time taken from first seeing the given data to it finishing by
+    /// becoming saturated, or `None` if it hasn't finished that way, or the time has already been
+    /// taken.
+    pub(super) fn take_convergence_time(&mut self, data_id: &T) -> Option<Duration> {
+        self.convergence_times.remove(data_id)
+    }
+
+    /// Returns the ID of the gossip operation for the given data, or `None` if it is not currently
+    /// being gossiped.
+    pub(super) fn op_id(&self, data_id: &T) -> Option<GossipOpId> {
+        self.current.get(data_id).map(|state| state.op_id)
+    }
+
+    /// Returns the total number of gossip messages sent for the given data, or `None` if it is not
+    /// currently being gossiped.
+    pub(super) fn messages_sent(&self, data_id: &T) -> Option<usize> {
+        self.current.get(data_id).map(|state| state.messages_sent)
+    }
+
+    /// Returns the number of distinct peers which have responded to our gossip for the given data,
+    /// or `None` if it is neither currently being gossiped nor finished.
+    pub(super) fn achieved_degree(&self, data_id: &T) -> Option<usize> {
+        match self.current.get(data_id) {
+            Some(state) => Some(state.acknowledged_by.len()),
+            None => self.achieved_degrees.get(data_id).copied(),
+        }
+    }
+
+    /// Pauses gossiping of the given data.  Returns `true` if it is currently being gossiped and
+    /// wasn't already paused.
+    pub(super) fn pause(&mut self, data_id: &T) -> bool {
+        match self.current.get_mut(data_id) {
+            Some(state) if !state.paused => {
+                state.paused = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if gossiping of the given data has been paused.
+    pub(super) fn is_paused(&self, data_id: &T) -> bool {
+        self.current
+            .get(data_id)
+            .map_or(false, |state| state.paused)
+    }
+
+    /// Returns the number of known holders of the given data, or `None` if it is not currently
+    /// being gossiped.
+    pub(super) fn holder_count(&self, data_id: &T) -> Option<usize> {
+        self.current.get(data_id).map(|state| state.holders.len())
+    }
+
+    /// Returns `true` if the given peer is a known holder of the given data which is currently
+    /// being gossiped.
+    pub(super) fn is_holder(&self, data_id: &T, peer: &NodeId) -> bool {
+        self.current.get(data_id).map_or(false, |state| {
+            state.holders.contains(peer, &self.node_index)
+        })
+    }
+
+    /// Returns a known holder of the given data for which `predicate` returns `true`, or `None` if
+    /// there is none or the data is not currently being gossiped.
+    pub(super) fn find_holder<F: Fn(&NodeId) -> bool>(
+        &self,
+        data_id: &T,
+        predicate: F,
+    ) -> Option<NodeId> {
+        self.current
+            .get(data_id)?
+            .holders
+            .iter(&self.node_index)
+            .find(|holder| predicate(holder))
+    }
+
     /// Directly reduces the in-flight count of gossip requests for the given item by the given
     /// amount.
     ///
@@ -398,11 +722,7 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     /// If the peer is already counted as a holder, it has previously responded and this method
     /// returns Noop.  Otherwise it has timed out and we return the appropriate action to take.
     pub(super) fn check_timeout(&mut self, data_id: &T, peer: NodeId) -> GossipAction {
-        let update = |state: &mut State| {
-            debug_assert!(
-                state.held_by_us(),
-                "shouldn't check timeout for a gossip response for data we don't hold"
-            );
+        let update = |state: &mut State, node_index: &mut NodeIndex| {
             if !state.held_by_us() {
                 error!(
                     item=%data_id,
@@ -411,9 +731,8 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
                 return;
             }
 
-            if !state.holders.contains(&peer) {
-                // Add the peer as a holder just to avoid retrying it.
-                let _ = state.holders.insert(peer);
+            // Add the peer as a holder if not already one, just to avoid retrying it.
+            if state.holders.insert(peer, node_index) {
                 state.in_flight_count = state.in_flight_count.saturating_sub(1);
             }
         };
@@ -426,7 +745,9 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     /// holder.  Otherwise, assume `peer` was unresponsive and remove from list of holders.
     ///
     /// If this causes the list of holders to become empty, and we also don't hold the full data,
-    /// then this entry is removed as if we'd never heard of it.
+    /// then this entry is removed as if we'd never heard of it.  If instead we have already asked
+    /// `Config::max_holder_attempts` holders for the remainder, the entry is marked `finished` so
+    /// no further holders are tried.
     pub(super) fn remove_holder_if_unresponsive(
         &mut self,
         data_id: &T,
@@ -434,19 +755,31 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     ) -> GossipAction {
         if let Some(mut state) = self.current.remove(data_id) {
             if !state.held_by_us() {
-                let _ = state.holders.remove(&peer);
+                let _ = state.holders.remove(&peer, &self.node_index);
                 trace!(item=%data_id, %peer, "removed peer as a holder of the item");
                 if state.holders.is_empty() {
                     // We don't hold the full data, and we don't know any holders - remove the entry
                     trace!(item=%data_id, "no further action: item now removed as no holders");
                     return GossipAction::Noop;
                 }
+                if state.get_remainder_attempts >= self.max_holder_attempts {
+                    trace!(
+                        item=%data_id,
+                        attempts=%state.get_remainder_attempts,
+                        "finished gossiping since too many holders failed to provide the item"
+                    );
+                    self.insert_to_finished(data_id, state.acknowledged_by.len());
+                    return GossipAction::AnnounceFinished;
+                }
             }
             let is_new = !state.held_by_us();
             let action = state.action(
                 self.infection_target,
                 self.attempted_to_infect_limit,
+                self.min_verified_holders,
+                self.max_gossip_count(),
                 is_new,
+                &self.node_index,
             );
             let _ = self.current.insert(data_id.clone(), state);
             trace!(item=%data_id, %action, "assuming peer response did not timeout");
@@ -461,10 +794,36 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     ///
     /// Returns `true` if there was a current entry for this data.
     pub(super) fn force_finish(&mut self, data_id: &T) -> bool {
-        if self.current.remove(data_id).is_some() {
-            self.insert_to_finished(data_id);
+        if let Some(state) = self.current.remove(data_id) {
+            self.insert_to_finished(data_id, state.acknowledged_by.len());
+            return true;
+        }
+        false
+    }
+
+    /// Ensures there is room in the table to track the given data, evicting the oldest finished
+    /// entry if the table is full.
+    ///
+    /// Returns `false` if the table is full and has no finished entries to evict, in which case the
+    /// data should be rejected.
+    pub(super) fn make_room_for(&mut self, data_id: &T) -> bool {
+        self.purge_finished();
+
+        if self.has_entry(data_id) || self.items_tracked() < self.max_tracked_items {
             return true;
         }
+
+        // The timeouts are ordered by insertion time, so the first one still held in `finished` is
+        // the oldest finished entry.
+        while !self.timeouts.values.is_empty() {
+            let (_timeout, evicted) = self.timeouts.values.remove(0);
+            if self.finished.remove(&evicted) {
+                let _ = self.convergence_times.remove(&evicted);
+                let _ = self.achieved_degrees.remove(&evicted);
+                debug!(item=%evicted, new_item=%data_id, "evicted finished item from full table");
+                return true;
+            }
+        }
         false
     }
 
@@ -485,6 +844,47 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
         false
     }
 
+    /// Returns a summary of every entry in `current` and `finished`, sorted by ID.
+    #[cfg(test)]
+    pub(super) fn summaries(&self) -> Vec<EntrySummary> {
+        let current = self.current.iter().map(|(data_id, state)| {
+            let mut holders: Vec<_> = state.holders.iter(&self.node_index).collect();
+            holders.sort();
+            EntrySummary {
+                id: data_id.to_string(),
+                holders,
+                is_complete: state.held_by_us(),
+                age_millis: state
+                    .first_seen
+                    .map(|first_seen| first_seen.elapsed().as_millis() as u64),
+                is_finished: false,
+            }
+        });
+        let finished = self.finished.iter().map(|data_id| EntrySummary {
+            id: data_id.to_string(),
+            holders: vec![],
+            is_complete: false,
+            age_millis: None,
+            is_finished: true,
+        });
+        let mut summaries: Vec<_> = current.chain(finished).collect();
+        summaries.sort_by(|lhs, rhs| lhs.id.cmp(&rhs.id));
+        summaries
+    }
+
+    /// Returns `true` if the given ID is in `current`.
+    pub(super) fn is_current(&self, data_id: &T) -> bool {
+        self.current.contains_key(data_id)
+    }
+
+    /// Returns `true` if the given data is currently being gossiped, but we don't yet hold it in
+    /// full, i.e. we are still acquiring the remainder.
+    pub(super) fn is_acquiring(&self, data_id: &T) -> bool {
+        self.current
+            .get(data_id)
+            .map_or(false, |state| !state.held_by_us())
+    }
+
     /// Returns `true` if the given ID is in `current` or `finished`.
     pub(super) fn has_entry(&self, data_id: &T) -> bool {
         self.current.contains_key(data_id) || self.finished.contains(data_id)
@@ -494,30 +894,46 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     /// take, or `None` if the entry does not exist.
     ///
     /// If the entry becomes finished, it is moved from `self.current` to `self.finished`.
-    fn update_current<F: Fn(&mut State)>(
+    fn update_current<F: Fn(&mut State, &mut NodeIndex)>(
         &mut self,
         data_id: &T,
         update: F,
     ) -> Option<GossipAction> {
         let mut state = self.current.remove(data_id)?;
-        update(&mut state);
-        if state.is_finished(self.infection_target, self.attempted_to_infect_limit) {
-            self.insert_to_finished(data_id);
+        update(&mut state, &mut self.node_index);
+        state.last_progress = Some(Instant::now());
+        if state.is_finished(
+            self.infection_target,
+            self.attempted_to_infect_limit,
+            self.min_verified_holders,
+        ) {
+            if let Some(first_seen) = state.first_seen {
+                let _ = self
+                    .convergence_times
+                    .insert(data_id.clone(), first_seen.elapsed());
+            }
+            self.insert_to_finished(data_id, state.acknowledged_by.len());
             return Some(GossipAction::AnnounceFinished);
         }
         let is_new = false;
         let action = state.action(
             self.infection_target,
             self.attempted_to_infect_limit,
+            self.min_verified_holders,
+            self.max_gossip_count(),
             is_new,
+            &self.node_index,
         );
         let _ = self.current.insert(data_id.clone(), state);
         Some(action)
     }
 
-    fn insert_to_finished(&mut self, data_id: &T) {
+    fn insert_to_finished(&mut self, data_id: &T, achieved_degree: usize) {
         let timeout = Instant::now() + self.finished_entry_duration;
         let _ = self.finished.insert(data_id.clone());
+        let _ = self
+            .achieved_degrees
+            .insert(data_id.clone(), achieved_degree);
         self.timeouts.push(timeout, data_id.clone());
     }
 
@@ -525,8 +941,11 @@ impl<T: Clone + Eq + Hash + Display> GossipTable<T> {
     fn purge_finished(&mut self) {
         let now = Instant::now();
 
-        for expired_finished in self.timeouts.purge(&now) {
+        let expired: Vec<T> = self.timeouts.purge(&now).collect();
+        for expired_finished in expired {
             let _ = self.finished.remove(&expired_finished);
+            let _ = self.convergence_times.remove(&expired_finished);
+            let _ = self.achieved_degrees.remove(&expired_finished);
         }
     }
 
@@ -559,11 +978,13 @@ mod tests {
     }
 
     fn check_holders(expected: &[NodeId], gossip_table: &GossipTable<u64>, data_id: &u64) {
-        let expected: BTreeSet<_> = expected.iter().collect();
+        let expected: BTreeSet<_> = expected.iter().copied().collect();
         let actual: BTreeSet<_> = gossip_table
             .current
             .get(data_id)
-            .map_or_else(BTreeSet::new, |state| state.holders.iter().collect());
+            .map_or_else(BTreeSet::new, |state| {
+                state.holders.iter(&gossip_table.node_index).collect()
+            });
         assert!(
             expected == actual,
             "\nexpected: {}\nactual:   {}\n",
@@ -633,6 +1054,31 @@ mod tests {
         check_holders(&node_ids[..1], &gossip_table, &data_id);
     }
 
+    #[test]
+    fn should_count_stalled_items() {
+        const THRESHOLD: Duration = Duration::from_secs(10);
+
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let stalled_id: u64 = rng.gen();
+        let progressing_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+        let _ = gossip_table.new_complete_data(&stalled_id, None, GossipTarget::All);
+        let _ = gossip_table.new_complete_data(&progressing_id, None, GossipTarget::All);
+        assert_eq!(0, gossip_table.items_stalled(THRESHOLD));
+
+        // Let both entries go quiet, then update only one of them.
+        Instant::advance_time(THRESHOLD.as_millis() as u64);
+        let _ = gossip_table.we_infected(&progressing_id, node_ids[0]);
+        assert_eq!(1, gossip_table.items_stalled(THRESHOLD));
+
+        // Let both entries go quiet again.
+        Instant::advance_time(THRESHOLD.as_millis() as u64);
+        assert_eq!(2, gossip_table.items_stalled(THRESHOLD));
+    }
+
     #[test]
     fn should_noop_if_we_dont_hold_data_and_get_gossip_response() {
         let _ = logging::init();
@@ -651,6 +1097,47 @@ mod tests {
         assert_eq!(GossipAction::AwaitingRemainder, action);
     }
 
+    #[test]
+    fn should_ignore_response_for_unknown_data_by_default() {
+        let mut rng = crate::new_rng();
+        let node_id = NodeId::random(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+
+        assert!(!gossip_table.unknown_response(&data_id, node_id));
+        assert!(!gossip_table.has_entry(&data_id));
+        assert!(gossip_table.is_empty());
+    }
+
+    #[test]
+    fn should_track_holder_of_unknown_data_if_configured() {
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let config = Config {
+            unknown_response_policy: UnknownResponsePolicy::Track,
+            ..Default::default()
+        };
+        let mut gossip_table = GossipTable::new(config);
+
+        assert!(gossip_table.unknown_response(&data_id, node_ids[0]));
+        assert_eq!(gossip_table.items_current(), 1);
+        check_holders(&node_ids[..1], &gossip_table, &data_id);
+
+        // A repeated response doesn't create a further entry.
+        assert!(!gossip_table.unknown_response(&data_id, node_ids[1]));
+        check_holders(&node_ids[..1], &gossip_table, &data_id);
+
+        // The remainder is requested from a known holder once another peer gossips the ID.
+        let action = gossip_table.new_data_id(&data_id, node_ids[2]);
+        assert!(matches!(action, GossipAction::GetRemainder { .. }));
+        check_holders(&[node_ids[0], node_ids[2]], &gossip_table, &data_id);
+        let action = gossip_table.new_data_id(&data_id, node_ids[3]);
+        assert_eq!(GossipAction::AwaitingRemainder, action);
+    }
+
     #[test]
     fn new_complete_data() {
         let _ = logging::init();
@@ -904,6 +1391,61 @@ mod tests {
         assert_eq!(GossipAction::AnnounceFinished, action);
     }
 
+    #[test]
+    fn should_terminate_via_honest_already_held_responses_by_default() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+
+        // Have every peer we gossip to already hold the data, as is usual for widely-propagated
+        // data, and check we stop gossiping once the saturation limit is reached.
+        let _ = gossip_table.new_complete_data(&data_id, None, GossipTarget::All);
+        let limit = EXPECTED_DEFAULT_ATTEMPTED_TO_INFECT_LIMIT - 1;
+        for node_id in &node_ids[..limit] {
+            gossip_table.register_infection_attempt(&data_id, iter::once(node_id));
+            let action = gossip_table.already_infected(&data_id, *node_id);
+            assert!(matches!(action, GossipAction::ShouldGossip(_)));
+        }
+        gossip_table.register_infection_attempt(&data_id, iter::once(&node_ids[limit]));
+        let action = gossip_table.already_infected(&data_id, node_ids[limit]);
+        assert_eq!(GossipAction::AnnounceFinished, action);
+        assert!(gossip_table.finished.contains(&data_id));
+    }
+
+    #[test]
+    fn should_not_terminate_via_false_already_held_responses() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let config = Config {
+            min_verified_holders: 1,
+            ..Config::default()
+        };
+        let mut gossip_table = GossipTable::new(config);
+
+        // Have every peer claim to already hold the data, taking us beyond the saturation limit
+        // without any verified holders, and check we don't stop gossiping.
+        let _ = gossip_table.new_complete_data(&data_id, None, GossipTarget::All);
+        let (last, liars) = node_ids.split_last().unwrap();
+        assert!(liars.len() > EXPECTED_DEFAULT_ATTEMPTED_TO_INFECT_LIMIT);
+        for node_id in liars {
+            gossip_table.register_infection_attempt(&data_id, iter::once(node_id));
+            let action = gossip_table.already_infected(&data_id, *node_id);
+            assert!(matches!(action, GossipAction::ShouldGossip(_)));
+        }
+        assert!(gossip_table.current.contains_key(&data_id));
+
+        // Check a single verified holder is enough to trust the saturation estimate.
+        gossip_table.register_infection_attempt(&data_id, iter::once(last));
+        let action = gossip_table.we_infected(&data_id, *last);
+        assert_eq!(GossipAction::AnnounceFinished, action);
+    }
+
     #[test]
     fn should_not_terminate_below_infection_limit_and_saturation() {
         let _ = logging::init();
@@ -974,13 +1516,7 @@ mod tests {
     }
 
     #[test]
-    #[cfg_attr(
-        debug_assertions,
-        should_panic(
-            expected = "shouldn't check timeout for a gossip response for data we don't hold"
-        )
-    )]
-    fn check_timeout_should_panic_for_data_we_dont_hold() {
+    fn check_timeout_should_not_update_data_we_dont_hold() {
         let _ = logging::init();
         let mut rng = crate::new_rng();
         let node_ids = random_node_ids(&mut rng);
@@ -988,7 +1524,9 @@ mod tests {
 
         let mut gossip_table = GossipTable::new(Config::default());
         let _ = gossip_table.new_data_id(&data_id, node_ids[0]);
-        let _ = gossip_table.check_timeout(&data_id, node_ids[0]);
+        let action = gossip_table.check_timeout(&data_id, node_ids[1]);
+        assert_eq!(GossipAction::AwaitingRemainder, action);
+        check_holders(&node_ids[..1], &gossip_table, &data_id);
     }
 
     #[test]
@@ -1021,6 +1559,81 @@ mod tests {
         assert!(!gossip_table.finished.contains(&data_id));
     }
 
+    #[test]
+    fn should_merge_holders() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config::default());
+
+        // Merging holders of unknown data shouldn't create an entry.
+        let action = gossip_table.merge_holders(&data_id, node_ids[1..3].iter().copied().collect());
+        assert_eq!(GossipAction::Noop, action);
+        assert!(!gossip_table.has_entry(&data_id));
+
+        // Learn of node 0 via gossip, and of nodes 1 and 2 out-of-band.  The holder sets are
+        // merged, and since we're already getting the remainder from node 0, we should keep
+        // awaiting it.
+        let action = gossip_table.new_data_id(&data_id, node_ids[0]);
+        let expected = GossipAction::GetRemainder {
+            holder: node_ids[0],
+        };
+        assert_eq!(expected, action);
+        let action = gossip_table.merge_holders(&data_id, node_ids[1..3].iter().copied().collect());
+        assert_eq!(GossipAction::AwaitingRemainder, action);
+        check_holders(&node_ids[..3], &gossip_table, &data_id);
+
+        // Node 0 failing to provide the data shouldn't cause the entry to be removed; instead we
+        // should be told to get the remainder from one of the merged holders.
+        let action = gossip_table.remove_holder_if_unresponsive(&data_id, node_ids[0]);
+        match action {
+            GossipAction::GetRemainder { holder } => assert!(node_ids[1..3].contains(&holder)),
+            _ => panic!("expected GetRemainder, got {}", action),
+        }
+        check_holders(&node_ids[1..3], &gossip_table, &data_id);
+    }
+
+    #[test]
+    fn should_finish_after_max_holder_attempts() {
+        const MAX_HOLDER_ATTEMPTS: usize = 3;
+
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let node_ids = random_node_ids(&mut rng);
+        let data_id: u64 = rng.gen();
+
+        let mut gossip_table = GossipTable::new(Config {
+            max_holder_attempts: MAX_HOLDER_ATTEMPTS as u32,
+            ..Default::default()
+        });
+
+        // Add new data ID from every node, then have each requested holder fail to provide it.
+        let mut action = gossip_table.new_data_id(&data_id, node_ids[0]);
+        for node_id in &node_ids[1..] {
+            let _ = gossip_table.new_data_id(&data_id, *node_id);
+        }
+        for _ in 1..MAX_HOLDER_ATTEMPTS {
+            let holder = match action {
+                GossipAction::GetRemainder { holder } => holder,
+                _ => panic!("expected GetRemainder, got {}", action),
+            };
+            action = gossip_table.remove_holder_if_unresponsive(&data_id, holder);
+        }
+
+        // The final attempt should fail too, and no further holders should be tried even though
+        // plenty remain.
+        let holder = match action {
+            GossipAction::GetRemainder { holder } => holder,
+            _ => panic!("expected GetRemainder, got {}", action),
+        };
+        let action = gossip_table.remove_holder_if_unresponsive(&data_id, holder);
+        assert_eq!(GossipAction::AnnounceFinished, action);
+        assert!(!gossip_table.current.contains_key(&data_id));
+        assert!(gossip_table.finished.contains(&data_id));
+    }
+
     #[test]
     fn should_not_remove_holder_if_responsive() {
         let _ = logging::init();
@@ -1059,6 +1672,35 @@ mod tests {
         assert!(!gossip_table.force_finish(&data_id));
     }
 
+    #[test]
+    fn should_evict_finished_then_reject_when_full() {
+        let _ = logging::init();
+        let mut gossip_table = GossipTable::new(Config {
+            max_tracked_items: 2,
+            ..Default::default()
+        });
+
+        // Fill the table, and finish the first entry.
+        for data_id in [1_u64, 2] {
+            assert!(gossip_table.make_room_for(&data_id));
+            let _ = gossip_table.new_complete_data(&data_id, None, GossipTarget::All);
+        }
+        assert!(gossip_table.force_finish(&1));
+        assert_eq!(gossip_table.items_tracked(), 2);
+
+        // A new entry causes the finished one to be evicted.
+        assert!(gossip_table.make_room_for(&3));
+        assert!(!gossip_table.has_entry(&1));
+        let _ = gossip_table.new_complete_data(&3, None, GossipTarget::All);
+        assert_eq!(gossip_table.items_tracked(), 2);
+
+        // With no finished entries left to evict, further new entries are rejected, while tracked
+        // ones are still accepted.
+        assert!(!gossip_table.make_room_for(&4));
+        assert!(gossip_table.make_room_for(&2));
+        assert_eq!(gossip_table.items_tracked(), 2);
+    }
+
     #[test]
     fn should_purge() {
         let _ = logging::init();
@@ -1094,6 +1736,79 @@ mod tests {
         assert!(!gossip_table.finished.contains(&data_id));
     }
 
+    #[test]
+    fn bitmap_and_hash_set_holders_should_produce_identical_decisions() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        // Enough peers for the bitmaps to span several words.
+        let node_ids: Vec<NodeId> = iter::repeat_with(|| NodeId::random(&mut rng))
+            .take(150)
+            .collect();
+
+        let run = |holder_set_representation| {
+            let mut gossip_table = GossipTable::new(Config {
+                holder_set_representation,
+                ..Default::default()
+            });
+            let mut actions = vec![];
+            let mut holder_counts = vec![];
+
+            // Learn of item 1 from node 0 then node 1, and have node 0 fail to provide it, leaving
+            // node 1 as the only holder to get the remainder from.
+            actions.push(gossip_table.new_data_id(&1, node_ids[0]));
+            actions.push(gossip_table.new_data_id(&1, node_ids[1]));
+            actions.push(gossip_table.remove_holder_if_unresponsive(&1, node_ids[0]));
+            holder_counts.push(gossip_table.holder_count(&1));
+
+            // Get item 1 and gossip it until finished.
+            actions.push(gossip_table.new_complete_data(&1, Some(node_ids[1]), GossipTarget::All));
+            gossip_table.register_infection_attempt(&1, node_ids[2..6].iter());
+            actions.push(gossip_table.already_infected(&1, node_ids[2]));
+            actions.push(gossip_table.check_timeout(&1, node_ids[3]));
+            actions.push(gossip_table.check_timeout(&1, node_ids[3]));
+            holder_counts.push(gossip_table.holder_count(&1));
+            actions.push(gossip_table.we_infected(&1, node_ids[4]));
+            actions.push(gossip_table.we_infected(&1, node_ids[5]));
+            actions.push(gossip_table.we_infected(&1, node_ids[6]));
+            holder_counts.push(gossip_table.holder_count(&1));
+
+            // Learn of item 2 from many peers, including one recorded last, then lose them all.
+            actions.push(gossip_table.new_data_id(&2, node_ids[149]));
+            actions
+                .push(gossip_table.merge_holders(&2, node_ids[7..140].iter().copied().collect()));
+            holder_counts.push(gossip_table.holder_count(&2));
+            let summaries = gossip_table.summaries();
+            let found = gossip_table.find_holder(&2, |holder| *holder == node_ids[100]);
+            assert_eq!(Some(node_ids[100]), found);
+            for node_id in node_ids[7..140].iter().chain(iter::once(&node_ids[149])) {
+                gossip_table.reset_get_remainder_attempts(&2);
+                actions.push(gossip_table.remove_holder_if_unresponsive(&2, *node_id));
+            }
+            holder_counts.push(gossip_table.holder_count(&2));
+
+            (actions, holder_counts, summaries)
+        };
+
+        let (hash_set_actions, hash_set_holder_counts, hash_set_summaries) =
+            run(HolderSetRepresentation::HashSet);
+        let (bitmap_actions, bitmap_holder_counts, bitmap_summaries) =
+            run(HolderSetRepresentation::Bitmap);
+        assert_eq!(
+            vec![Some(1), Some(3), None, Some(134), None],
+            hash_set_holder_counts
+        );
+        assert_eq!(hash_set_holder_counts, bitmap_holder_counts);
+        assert_eq!(hash_set_summaries, bitmap_summaries);
+        assert_eq!(hash_set_actions.len(), bitmap_actions.len());
+        for (hash_set_action, bitmap_action) in hash_set_actions.iter().zip(&bitmap_actions) {
+            match (hash_set_action, bitmap_action) {
+                // Which of several holders to get the remainder from is unspecified.
+                (GossipAction::GetRemainder { .. }, GossipAction::GetRemainder { .. }) => (),
+                _ => assert_eq!(hash_set_action, bitmap_action),
+            }
+        }
+    }
+
     #[test]
     fn timeouts_purge_in_order() {
         let mut timeouts = Timeouts::new();
@@ -0,0 +1,22 @@
+use std::time::Instant;
+
+/// A summary of a gossiper's liveness, suitable for translating into a readiness response.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct GossipHealth {
+    /// Whether newly-received items are handed off for storing immediately rather than queued.
+    pub(crate) is_accepting_items: bool,
+    /// The number of items currently being gossiped.
+    pub(crate) active_item_count: usize,
+    /// The number of items currently being gossiped which have made no recent progress.
+    pub(crate) stalled_item_count: usize,
+    /// The last time at least one peer was sent a gossip message.
+    pub(crate) last_successful_gossip: Option<Instant>,
+}
+
+impl GossipHealth {
+    /// Returns `true` if the gossiper is accepting items and not every active item is stalled.
+    pub(crate) fn is_healthy(&self) -> bool {
+        self.is_accepting_items
+            && (self.active_item_count == 0 || self.stalled_item_count < self.active_item_count)
+    }
+}
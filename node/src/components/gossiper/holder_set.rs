@@ -0,0 +1,197 @@
+use std::collections::{hash_set, HashMap, HashSet};
+
+use datasize::DataSize;
+
+use super::config::HolderSetRepresentation;
+use crate::types::NodeId;
+
+const BITS_PER_WORD: usize = u64::BITS as usize;
+
+/// Assigns each peer recorded as a holder a dense index, shared by all bitmap holder sets in a
+/// gossip table.
+///
+/// Indices are never reclaimed, so this grows with the number of distinct peers ever recorded.
+#[derive(DataSize, Debug, Default)]
+pub(super) struct NodeIndex {
+    indices: HashMap<NodeId, u32>,
+    node_ids: Vec<NodeId>,
+}
+
+impl NodeIndex {
+    /// Returns the index of the given peer, assigning it the next index if it has none yet.
+    fn intern(&mut self, node_id: NodeId) -> usize {
+        if let Some(index) = self.index_of(&node_id) {
+            return index;
+        }
+        let index = self.node_ids.len();
+        let _ = self.indices.insert(node_id, index as u32);
+        self.node_ids.push(node_id);
+        index
+    }
+
+    /// Returns the index of the given peer, or `None` if it has never been recorded as a holder.
+    fn index_of(&self, node_id: &NodeId) -> Option<usize> {
+        self.indices.get(node_id).map(|index| *index as usize)
+    }
+
+    fn node_id(&self, index: usize) -> NodeId {
+        self.node_ids[index]
+    }
+}
+
+/// The set of peers known to hold a single item.
+///
+/// Operations on a bitmap set resolve peers via the table's `NodeIndex`, which must be passed to
+/// every call, and which is ignored by a hash set.
+#[derive(DataSize, Debug)]
+pub(super) enum HolderSet {
+    Hashed(HashSet<NodeId>),
+    Bitmap(Vec<u64>),
+}
+
+impl Default for HolderSet {
+    fn default() -> Self {
+        HolderSet::Hashed(HashSet::new())
+    }
+}
+
+impl HolderSet {
+    pub(super) fn new(representation: HolderSetRepresentation) -> Self {
+        match representation {
+            HolderSetRepresentation::HashSet => HolderSet::Hashed(HashSet::new()),
+            HolderSetRepresentation::Bitmap => HolderSet::Bitmap(Vec::new()),
+        }
+    }
+
+    /// Adds the given peer, returning `true` if it wasn't already present.
+    pub(super) fn insert(&mut self, node_id: NodeId, node_index: &mut NodeIndex) -> bool {
+        match self {
+            HolderSet::Hashed(holders) => holders.insert(node_id),
+            HolderSet::Bitmap(words) => {
+                let index = node_index.intern(node_id);
+                let (word, bit) = (index / BITS_PER_WORD, index % BITS_PER_WORD);
+                if words.len() <= word {
+                    words.resize(word + 1, 0);
+                }
+                let was_present = words[word] & (1 << bit) != 0;
+                words[word] |= 1 << bit;
+                !was_present
+            }
+        }
+    }
+
+    pub(super) fn extend(
+        &mut self,
+        node_ids: impl IntoIterator<Item = NodeId>,
+        node_index: &mut NodeIndex,
+    ) {
+        for node_id in node_ids {
+            let _ = self.insert(node_id, node_index);
+        }
+    }
+
+    /// Removes the given peer, returning `true` if it was present.
+    pub(super) fn remove(&mut self, node_id: &NodeId, node_index: &NodeIndex) -> bool {
+        match self {
+            HolderSet::Hashed(holders) => holders.remove(node_id),
+            HolderSet::Bitmap(words) => {
+                let index = match node_index.index_of(node_id) {
+                    Some(index) => index,
+                    None => return false,
+                };
+                let (word, bit) = (index / BITS_PER_WORD, index % BITS_PER_WORD);
+                match words.get_mut(word) {
+                    Some(bits) if *bits & (1 << bit) != 0 => *bits &= !(1 << bit),
+                    _ => return false,
+                }
+                // Drop trailing empty words so the bitmap only spans the highest index present.
+                while words.last() == Some(&0) {
+                    let _ = words.pop();
+                }
+                true
+            }
+        }
+    }
+
+    pub(super) fn contains(&self, node_id: &NodeId, node_index: &NodeIndex) -> bool {
+        match self {
+            HolderSet::Hashed(holders) => holders.contains(node_id),
+            HolderSet::Bitmap(words) => Self::bitmap_contains(words, node_id, node_index),
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        match self {
+            HolderSet::Hashed(holders) => holders.len(),
+            HolderSet::Bitmap(words) => words.iter().map(|word| word.count_ones() as usize).sum(),
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        match self {
+            HolderSet::Hashed(holders) => holders.is_empty(),
+            HolderSet::Bitmap(words) => words.iter().all(|word| *word == 0),
+        }
+    }
+
+    /// Returns an iterator over the holders.  A bitmap set yields them in the order they were
+    /// first recorded as a holder of any item in the table.
+    pub(super) fn iter<'a>(&'a self, node_index: &'a NodeIndex) -> Iter<'a> {
+        match self {
+            HolderSet::Hashed(holders) => Iter::Hashed(holders.iter()),
+            HolderSet::Bitmap(words) => Iter::Bitmap {
+                words,
+                position: 0,
+                node_index,
+            },
+        }
+    }
+
+    fn bitmap_contains(words: &[u64], node_id: &NodeId, node_index: &NodeIndex) -> bool {
+        node_index.index_of(node_id).map_or(false, |index| {
+            words
+                .get(index / BITS_PER_WORD)
+                .map_or(false, |word| word & (1 << (index % BITS_PER_WORD)) != 0)
+        })
+    }
+}
+
+/// An iterator over the holders in a `HolderSet`.
+pub(super) enum Iter<'a> {
+    Hashed(hash_set::Iter<'a, NodeId>),
+    Bitmap {
+        words: &'a [u64],
+        /// The index of the next bit to check.
+        position: usize,
+        node_index: &'a NodeIndex,
+    },
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = NodeId;
+
+    fn next(&mut self) -> Option<NodeId> {
+        match self {
+            Iter::Hashed(iter) => iter.next().copied(),
+            Iter::Bitmap {
+                words,
+                position,
+                node_index,
+            } => {
+                while let Some(word) = words.get(*position / BITS_PER_WORD) {
+                    // Mask out the bits already visited in this word.
+                    let remaining = word & (u64::MAX << (*position % BITS_PER_WORD));
+                    if remaining == 0 {
+                        *position = (*position / BITS_PER_WORD + 1) * BITS_PER_WORD;
+                        continue;
+                    }
+                    let index = (*position / BITS_PER_WORD) * BITS_PER_WORD
+                        + remaining.trailing_zeros() as usize;
+                    *position = index + 1;
+                    return Some(node_index.node_id(index));
+                }
+                None
+            }
+        }
+    }
+}
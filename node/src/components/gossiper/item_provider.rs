@@ -3,15 +3,33 @@ use async_trait::async_trait;
 use super::GossipItem;
 use crate::effect::{requests::StorageRequest, EffectBuilder};
 
+/// Provides access to items held in storage, keyed by `GossipItem::storage_key` rather than by
+/// their gossip IDs.
 #[async_trait]
 pub(super) trait ItemProvider<T: GossipItem> {
     async fn is_stored<REv: From<StorageRequest> + Send>(
         effect_builder: EffectBuilder<REv>,
-        item_id: T::Id,
+        storage_key: T::StorageKey,
     ) -> bool;
 
     async fn get_from_storage<REv: From<StorageRequest> + Send>(
         effect_builder: EffectBuilder<REv>,
-        item_id: T::Id,
+        storage_key: T::StorageKey,
     ) -> Option<Box<T>>;
+
+    /// Gets the given items from storage, returning the results in the same order as
+    /// `storage_keys`.
+    ///
+    /// Providers able to read several items in a single storage transaction should override this,
+    /// as by default each item is read separately.
+    async fn get_many_from_storage<REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        storage_keys: Vec<T::StorageKey>,
+    ) -> Vec<Option<Box<T>>> {
+        let mut maybe_items = Vec::with_capacity(storage_keys.len());
+        for storage_key in storage_keys {
+            maybe_items.push(Self::get_from_storage(effect_builder, storage_key).await);
+        }
+        maybe_items
+    }
 }
@@ -6,8 +6,63 @@ use std::{
 use serde::{Deserialize, Serialize};
 use strum::EnumDiscriminants;
 
+use casper_hashing::Digest;
+use casper_types::{crypto, PublicKey, SecretKey, Signature};
+
 use super::GossipItem;
 
+/// The maximum number of item IDs included in a single `GossipBatch`.
+pub(super) const MAX_GOSSIP_BATCH_ITEM_COUNT: usize = 100;
+
+/// The maximum length of the item ID hash prefix carried by a `GossipIdPrefix` or `GetFullId`.
+pub(super) const MAX_GOSSIP_ID_PREFIX_LEN: usize = Digest::LENGTH;
+
+/// The maximum number of bytes which can be requested in a single `GetRange`.
+pub(super) const MAX_GET_RANGE_LENGTH: u32 = 1024 * 1024;
+
+/// A signature over a gossiped item ID, made by the node gossiping it.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct GossipSignature {
+    /// The public key of the signer.
+    public_key: PublicKey,
+    /// The signature over the serialized item ID.
+    signature: Signature,
+}
+
+impl GossipSignature {
+    /// Signs the given item ID.
+    pub(super) fn create<Id: Serialize>(
+        item_id: &Id,
+        secret_key: &SecretKey,
+        public_key: &PublicKey,
+    ) -> Self {
+        let signature = crypto::sign(Self::bytes_to_sign(item_id), secret_key, public_key);
+        GossipSignature {
+            public_key: public_key.clone(),
+            signature,
+        }
+    }
+
+    /// Returns the public key of the signer.
+    pub(super) fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Returns `true` if this is a valid signature over the given item ID.
+    pub(super) fn is_valid_for<Id: Serialize>(&self, item_id: &Id) -> bool {
+        crypto::verify(
+            Self::bytes_to_sign(item_id),
+            &self.signature,
+            &self.public_key,
+        )
+        .is_ok()
+    }
+
+    fn bytes_to_sign<Id: Serialize>(item_id: &Id) -> Vec<u8> {
+        bincode::serialize(item_id).expect("should serialize gossip item id")
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize, EnumDiscriminants)]
 #[strum_discriminants(derive(strum::EnumIter))]
 #[serde(bound = "for<'a> T: Deserialize<'a>")]
@@ -26,6 +81,73 @@ pub(crate) enum Message<T: GossipItem> {
     // Response to either a `GossipResponse` with `is_already_held` set to `false` or to a
     // `GetItem` message. Contains the actual item requested.
     Item(Box<T>),
+    /// Response to a `GetItem` message for an item which the recipient knows of but is still
+    /// acquiring itself.  The requester should retry later rather than treating this as a miss.
+    NotAvailableYet {
+        item_id: T::Id,
+    },
+    /// Request for `len` bytes starting at `offset` of the serialized form of an item, allowing a
+    /// large item to be retrieved in parts.  `len` may not exceed `MAX_GET_RANGE_LENGTH`.
+    GetRange {
+        item_id: T::Id,
+        offset: u64,
+        len: u32,
+    },
+    /// Response to a `GetRange` message.  `maybe_bytes` is `None` if the requested range couldn't
+    /// be served, e.g. because it isn't wholly within the bounds of the serialized item.
+    GetRangeResponse {
+        item_id: T::Id,
+        offset: u64,
+        maybe_bytes: Option<Vec<u8>>,
+    },
+    /// Acknowledges that an item sent in response to a `GetItem` message has been stored by the
+    /// requester.
+    GetResponseAck {
+        item_id: T::Id,
+    },
+    /// As per `GetItem`, but sent by a peer which was itself asked for the item and missed it in
+    /// storage, and which will relay the item to the original requester.  `hops` is the number of
+    /// times the request has been relayed so far, including this one.
+    RelayedGetItem {
+        item_id: T::Id,
+        hops: u8,
+    },
+    /// As per `Gossip`, but carrying the gossiping node's signature over the item ID.
+    SignedGossip {
+        item_id: T::Id,
+        signature: GossipSignature,
+    },
+    /// As per `Gossip`, but for several items at once.  Contains at most
+    /// `MAX_GOSSIP_BATCH_ITEM_COUNT` item IDs.
+    GossipBatch(Vec<T::Id>),
+    /// As per `Gossip`, but carrying only the leading bytes of the hash of the item ID, as
+    /// configured by `gossip_id_prefix_len`.  A recipient not recognizing exactly one item ID by
+    /// this prefix should request the full IDs via `GetFullId`.
+    GossipIdPrefix(Vec<u8>),
+    /// Request for the full IDs of items whose ID hashes start with the given prefix, sent in
+    /// response to a `GossipIdPrefix` message.  The IDs are sent as `Gossip` or `GossipBatch`
+    /// messages.
+    GetFullId(Vec<u8>),
+}
+
+impl<T: GossipItem> Message<T> {
+    /// Returns the ID of the single item this message relates to, or `None` if it relates to
+    /// several.
+    pub(super) fn item_id(&self) -> Option<T::Id> {
+        match self {
+            Message::Gossip(item_id)
+            | Message::SignedGossip { item_id, .. }
+            | Message::GossipResponse { item_id, .. }
+            | Message::GetItem(item_id)
+            | Message::NotAvailableYet { item_id }
+            | Message::GetRange { item_id, .. }
+            | Message::GetRangeResponse { item_id, .. }
+            | Message::GetResponseAck { item_id }
+            | Message::RelayedGetItem { item_id, .. } => Some(item_id.clone()),
+            Message::Item(item) => Some(item.gossip_id()),
+            Message::GossipBatch(_) | Message::GossipIdPrefix(_) | Message::GetFullId(_) => None,
+        }
+    }
 }
 
 impl<T: GossipItem> Display for Message<T> {
@@ -42,6 +164,64 @@ impl<T: GossipItem> Display for Message<T> {
             ),
             Message::GetItem(item_id) => write!(formatter, "gossip-get-item({})", item_id),
             Message::Item(item) => write!(formatter, "gossip-item({})", item.gossip_id()),
+            Message::NotAvailableYet { item_id } => {
+                write!(formatter, "gossip-not-available-yet({})", item_id)
+            }
+            Message::GetRange {
+                item_id,
+                offset,
+                len,
+            } => write!(
+                formatter,
+                "gossip-get-range({}, {} bytes at {})",
+                item_id, len, offset
+            ),
+            Message::GetRangeResponse {
+                item_id,
+                offset,
+                maybe_bytes,
+            } => match maybe_bytes {
+                Some(bytes) => write!(
+                    formatter,
+                    "gossip-range({}, {} bytes at {})",
+                    item_id,
+                    bytes.len(),
+                    offset
+                ),
+                None => write!(
+                    formatter,
+                    "gossip-range({}, unavailable at {})",
+                    item_id, offset
+                ),
+            },
+            Message::GetResponseAck { item_id } => {
+                write!(formatter, "gossip-get-response-ack({})", item_id)
+            }
+            Message::RelayedGetItem { item_id, hops } => write!(
+                formatter,
+                "gossip-relayed-get-item({}, {} hops)",
+                item_id, hops
+            ),
+            Message::SignedGossip { item_id, .. } => {
+                write!(formatter, "signed-gossip({})", item_id)
+            }
+            Message::GossipBatch(item_ids) => {
+                write!(formatter, "gossip-batch({} items)", item_ids.len())
+            }
+            Message::GossipIdPrefix(prefix) => {
+                write!(
+                    formatter,
+                    "gossip-id-prefix({})",
+                    base16::encode_lower(prefix)
+                )
+            }
+            Message::GetFullId(prefix) => {
+                write!(
+                    formatter,
+                    "gossip-get-full-id({})",
+                    base16::encode_lower(prefix)
+                )
+            }
         }
     }
 }
@@ -49,10 +229,15 @@ impl<T: GossipItem> Display for Message<T> {
 mod specimen_support {
     use crate::{
         components::gossiper::GossipItem,
-        utils::specimen::{largest_variant, Cache, LargestSpecimen, SizeEstimator},
+        utils::specimen::{
+            largest_variant, vec_of_largest_specimen, Cache, LargestSpecimen, SizeEstimator,
+        },
     };
 
-    use super::{Message, MessageDiscriminants};
+    use super::{
+        GossipSignature, Message, MessageDiscriminants, MAX_GET_RANGE_LENGTH,
+        MAX_GOSSIP_BATCH_ITEM_COUNT, MAX_GOSSIP_ID_PREFIX_LEN,
+    };
 
     impl<T> LargestSpecimen for Message<T>
     where
@@ -76,8 +261,56 @@ mod specimen_support {
                     MessageDiscriminants::Item => {
                         Message::Item(LargestSpecimen::largest_specimen(estimator, cache))
                     }
+                    MessageDiscriminants::NotAvailableYet => Message::NotAvailableYet {
+                        item_id: LargestSpecimen::largest_specimen(estimator, cache),
+                    },
+                    MessageDiscriminants::GetRange => Message::GetRange {
+                        item_id: LargestSpecimen::largest_specimen(estimator, cache),
+                        offset: LargestSpecimen::largest_specimen(estimator, cache),
+                        len: LargestSpecimen::largest_specimen(estimator, cache),
+                    },
+                    MessageDiscriminants::GetRangeResponse => Message::GetRangeResponse {
+                        item_id: LargestSpecimen::largest_specimen(estimator, cache),
+                        offset: LargestSpecimen::largest_specimen(estimator, cache),
+                        maybe_bytes: Some(vec_of_largest_specimen(
+                            estimator,
+                            MAX_GET_RANGE_LENGTH as usize,
+                            cache,
+                        )),
+                    },
+                    MessageDiscriminants::GetResponseAck => Message::GetResponseAck {
+                        item_id: LargestSpecimen::largest_specimen(estimator, cache),
+                    },
+                    MessageDiscriminants::RelayedGetItem => Message::RelayedGetItem {
+                        item_id: LargestSpecimen::largest_specimen(estimator, cache),
+                        hops: LargestSpecimen::largest_specimen(estimator, cache),
+                    },
+                    MessageDiscriminants::SignedGossip => Message::SignedGossip {
+                        item_id: LargestSpecimen::largest_specimen(estimator, cache),
+                        signature: LargestSpecimen::largest_specimen(estimator, cache),
+                    },
+                    MessageDiscriminants::GossipBatch => Message::GossipBatch(
+                        vec_of_largest_specimen(estimator, MAX_GOSSIP_BATCH_ITEM_COUNT, cache),
+                    ),
+                    MessageDiscriminants::GossipIdPrefix => Message::GossipIdPrefix(
+                        vec_of_largest_specimen(estimator, MAX_GOSSIP_ID_PREFIX_LEN, cache),
+                    ),
+                    MessageDiscriminants::GetFullId => Message::GetFullId(vec_of_largest_specimen(
+                        estimator,
+                        MAX_GOSSIP_ID_PREFIX_LEN,
+                        cache,
+                    )),
                 },
             )
         }
     }
+
+    impl LargestSpecimen for GossipSignature {
+        fn largest_specimen<E: SizeEstimator>(estimator: &E, cache: &mut Cache) -> Self {
+            GossipSignature {
+                public_key: LargestSpecimen::largest_specimen(estimator, cache),
+                signature: LargestSpecimen::largest_specimen(estimator, cache),
+            }
+        }
+    }
 }
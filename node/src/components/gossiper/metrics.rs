@@ -1,6 +1,33 @@
-use prometheus::{IntCounter, IntGauge, Registry};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Debug, Formatter},
+    time::Duration,
+};
 
-use crate::unregister_metric;
+use prometheus::{Gauge, Histogram, IntCounter, IntCounterVec, IntGauge, Opts, Registry};
+
+use crate::{types::NodeId, unregister_metric, utils};
+
+/// Value of upper bound of the first bucket.  In seconds.
+const EXPONENTIAL_BUCKET_START: f64 = 0.01;
+
+/// Multiplier of previous upper bound for next bound.
+const EXPONENTIAL_BUCKET_FACTOR: f64 = 2.0;
+
+/// Bucket count, with the last bucket going to +Inf which will not be included in the results.
+const EXPONENTIAL_BUCKET_COUNT: usize = 10;
+
+/// Value of upper bound of the first bucket of the convergence time histogram.  In seconds.
+const CONVERGENCE_BUCKET_START: f64 = 0.1;
+
+/// Value of upper bound of the first bucket of the achieved degree histogram.  In peers.
+const ACHIEVED_DEGREE_BUCKET_START: f64 = 1.0;
+
+/// Name of the label identifying the peer in per-peer metrics.
+const PEER_LABEL: &str = "peer";
+
+/// Value of the peer label under which peers without their own label are counted.
+const OTHER_PEERS_LABEL: &str = "other";
 
 /// Metrics for the gossiper component.
 #[derive(Debug)]
@@ -11,17 +38,46 @@ pub(super) struct Metrics {
     pub(super) times_gossiped: IntCounter,
     /// Number of times the process had to pause due to running out of peers.
     pub(super) times_ran_out_of_peers: IntCounter,
+    /// Number of gossip requests sent to peers per item received.
+    pub(super) amplification_factor: Gauge,
+    /// Number of items whose gossiping was paused for exceeding `max_amplification`.
+    pub(super) amplification_limit_hits: IntCounter,
     /// Number of items in the gossip table that are currently being gossiped.
     pub(super) table_items_current: IntGauge,
     /// Number of items in the gossip table that are finished.
     pub(super) table_items_finished: IntGauge,
+    /// Total number of items tracked in the gossip table, whether current or finished.
+    pub(super) table_items_tracked: IntGauge,
+    /// Total number of new items rejected due to the gossip table being full.
+    pub(super) items_rejected: IntCounter,
+    /// Number of received items queued awaiting a free put slot.
+    pub(super) queued_puts: IntGauge,
+    /// Number of peers for which rejection records are held.
+    pub(super) rejected_peers: IntGauge,
+    /// Time taken for a received item to be validated and stored by another component.
+    pub(super) put_to_storage_seconds: Histogram,
+    /// Time taken to retrieve an item from storage in order to send it to a peer.
+    pub(super) get_from_storage_seconds: Histogram,
+    /// Time taken from first seeing an item to it becoming saturated.
+    pub(super) convergence_seconds: Histogram,
+    /// Number of distinct peers which responded to our gossip about an item, as at it finishing
+    /// gossiping.
+    pub(super) achieved_degree: Histogram,
+    /// Metrics broken down by peer, if enabled.
+    pub(super) peers: Option<PeerMetrics>,
     /// Reference to the registry for unregistering.
     registry: Registry,
 }
 
 impl Metrics {
     /// Creates a new instance of gossiper metrics, using the given prefix.
-    pub fn new(name: &str, registry: &Registry) -> Result<Self, prometheus::Error> {
+    ///
+    /// If `max_labeled_peers` is non-zero, metrics broken down by peer are also created.
+    pub fn new(
+        name: &str,
+        registry: &Registry,
+        max_labeled_peers: usize,
+    ) -> Result<Self, prometheus::Error> {
         let items_received = IntCounter::new(
             format!("{}_items_received", name),
             format!("number of items received by the {}", name),
@@ -37,6 +93,20 @@ impl Metrics {
                 name
             ),
         )?;
+        let amplification_factor = Gauge::new(
+            format!("{}_amplification_factor", name),
+            format!(
+                "number of gossip requests sent to peers per item received by the {}",
+                name
+            ),
+        )?;
+        let amplification_limit_hits = IntCounter::new(
+            format!("{}_amplification_limit_hits", name),
+            format!(
+                "number of items the {} paused gossiping for sending too many requests",
+                name
+            ),
+        )?;
         let table_items_current = IntGauge::new(
             format!("{}_table_items_current", name),
             format!(
@@ -51,22 +121,319 @@ impl Metrics {
                 name
             ),
         )?;
+        let table_items_tracked = IntGauge::new(
+            format!("{}_table_items_tracked", name),
+            format!(
+                "total number of items tracked in the gossip table of {}",
+                name
+            ),
+        )?;
+        let items_rejected = IntCounter::new(
+            format!("{}_items_rejected", name),
+            format!(
+                "number of new items rejected by {} due to its gossip table being full",
+                name
+            ),
+        )?;
+
+        let queued_puts = IntGauge::new(
+            format!("{}_queued_puts", name),
+            format!(
+                "number of received items queued by {} awaiting a free put slot",
+                name
+            ),
+        )?;
+        let rejected_peers = IntGauge::new(
+            format!("{}_rejected_peers", name),
+            format!(
+                "number of peers for which {} holds records of rejected messages",
+                name
+            ),
+        )?;
 
         registry.register(Box::new(items_received.clone()))?;
         registry.register(Box::new(times_gossiped.clone()))?;
         registry.register(Box::new(times_ran_out_of_peers.clone()))?;
+        registry.register(Box::new(amplification_factor.clone()))?;
+        registry.register(Box::new(amplification_limit_hits.clone()))?;
         registry.register(Box::new(table_items_current.clone()))?;
         registry.register(Box::new(table_items_finished.clone()))?;
+        registry.register(Box::new(table_items_tracked.clone()))?;
+        registry.register(Box::new(items_rejected.clone()))?;
+        registry.register(Box::new(queued_puts.clone()))?;
+        registry.register(Box::new(rejected_peers.clone()))?;
+
+        let buckets = prometheus::exponential_buckets(
+            EXPONENTIAL_BUCKET_START,
+            EXPONENTIAL_BUCKET_FACTOR,
+            EXPONENTIAL_BUCKET_COUNT,
+        )?;
+        let put_to_storage_seconds = utils::register_histogram_metric(
+            registry,
+            &format!("{}_put_to_storage_seconds", name),
+            &format!(
+                "time in seconds for an item received by {} to be validated and stored",
+                name
+            ),
+            buckets.clone(),
+        )?;
+        let get_from_storage_seconds = utils::register_histogram_metric(
+            registry,
+            &format!("{}_get_from_storage_seconds", name),
+            &format!(
+                "time in seconds for {} to retrieve an item from storage",
+                name
+            ),
+            buckets,
+        )?;
+        let convergence_seconds = utils::register_histogram_metric(
+            registry,
+            &format!("{}_convergence_seconds", name),
+            &format!(
+                "time in seconds from {} first seeing an item to it becoming saturated",
+                name
+            ),
+            prometheus::exponential_buckets(
+                CONVERGENCE_BUCKET_START,
+                EXPONENTIAL_BUCKET_FACTOR,
+                EXPONENTIAL_BUCKET_COUNT,
+            )?,
+        )?;
+        let achieved_degree = utils::register_histogram_metric(
+            registry,
+            &format!("{}_achieved_degree", name),
+            &format!(
+                "number of distinct peers which responded to {} gossiping an item",
+                name
+            ),
+            prometheus::exponential_buckets(
+                ACHIEVED_DEGREE_BUCKET_START,
+                EXPONENTIAL_BUCKET_FACTOR,
+                EXPONENTIAL_BUCKET_COUNT,
+            )?,
+        )?;
+
+        let peers = if max_labeled_peers == 0 {
+            None
+        } else {
+            Some(PeerMetrics::new(name, registry, max_labeled_peers)?)
+        };
 
         Ok(Metrics {
             items_received,
             times_gossiped,
             times_ran_out_of_peers,
+            amplification_factor,
+            amplification_limit_hits,
             table_items_current,
             table_items_finished,
+            table_items_tracked,
+            items_rejected,
+            queued_puts,
+            rejected_peers,
+            put_to_storage_seconds,
+            get_from_storage_seconds,
+            convergence_seconds,
+            achieved_degree,
+            peers,
             registry: registry.clone(),
         })
     }
+
+    pub(super) fn observe_put_to_storage(&self, elapsed: Duration) {
+        self.put_to_storage_seconds.observe(elapsed.as_secs_f64());
+    }
+
+    pub(super) fn observe_get_from_storage(&self, elapsed: Duration) {
+        self.get_from_storage_seconds.observe(elapsed.as_secs_f64());
+    }
+
+    pub(super) fn observe_convergence(&self, elapsed: Duration) {
+        self.convergence_seconds.observe(elapsed.as_secs_f64());
+    }
+
+    pub(super) fn observe_achieved_degree(&self, achieved_degree: usize) {
+        self.achieved_degree.observe(achieved_degree as f64);
+    }
+
+    /// Records an event involving the given peer in the per-peer metrics, if enabled.
+    pub(super) fn record_peer(&mut self, peer: NodeId, metric: PeerMetric) {
+        if let Some(peers) = self.peers.as_mut() {
+            peers.record(peer, metric);
+        }
+    }
+}
+
+/// A per-peer gossip metric.
+#[derive(Clone, Copy, Debug)]
+pub(super) enum PeerMetric {
+    /// The peer didn't respond to our gossip in time.
+    GossipTimeout,
+    /// The peer didn't provide an item we requested from it in time.
+    FailedGet,
+    /// The peer was the first to tell us about an item.
+    ItemLearned,
+}
+
+/// Gossip metrics broken down by peer.
+///
+/// To bound the number of time series, only the most active peers are given their own label, with
+/// the rest counted under the `other` label.
+pub(super) struct PeerMetrics {
+    gossip_timeouts: IntCounterVec,
+    failed_gets: IntCounterVec,
+    items_learned: IntCounterVec,
+    /// The maximum number of peers given their own label.
+    max_labeled_peers: usize,
+    /// The number of recent events involving each peer.
+    activity: HashMap<NodeId, u64>,
+    /// The peers given their own label.
+    labeled: HashSet<NodeId>,
+}
+
+impl PeerMetrics {
+    fn new(
+        name: &str,
+        registry: &Registry,
+        max_labeled_peers: usize,
+    ) -> Result<Self, prometheus::Error> {
+        let gossip_timeouts = IntCounterVec::new(
+            Opts::new(
+                format!("{}_peer_gossip_timeouts", name),
+                format!(
+                    "number of gossip requests sent by {} which timed out, by peer",
+                    name
+                ),
+            ),
+            &[PEER_LABEL],
+        )?;
+        let failed_gets = IntCounterVec::new(
+            Opts::new(
+                format!("{}_peer_failed_gets", name),
+                format!(
+                    "number of items requested by {} which weren't provided in time, by peer",
+                    name
+                ),
+            ),
+            &[PEER_LABEL],
+        )?;
+        let items_learned = IntCounterVec::new(
+            Opts::new(
+                format!("{}_peer_items_learned", name),
+                format!(
+                    "number of items {} was first told about, by the peer telling it",
+                    name
+                ),
+            ),
+            &[PEER_LABEL],
+        )?;
+
+        registry.register(Box::new(gossip_timeouts.clone()))?;
+        registry.register(Box::new(failed_gets.clone()))?;
+        registry.register(Box::new(items_learned.clone()))?;
+
+        Ok(PeerMetrics {
+            gossip_timeouts,
+            failed_gets,
+            items_learned,
+            max_labeled_peers,
+            activity: HashMap::new(),
+            labeled: HashSet::new(),
+        })
+    }
+
+    fn counter(&self, metric: PeerMetric) -> &IntCounterVec {
+        match metric {
+            PeerMetric::GossipTimeout => &self.gossip_timeouts,
+            PeerMetric::FailedGet => &self.failed_gets,
+            PeerMetric::ItemLearned => &self.items_learned,
+        }
+    }
+
+    /// Records an event involving the given peer, under its own label if it's among the most
+    /// active peers, or under the `other` label otherwise.
+    fn record(&mut self, peer: NodeId, metric: PeerMetric) {
+        let activity = self.activity.entry(peer).or_default();
+        *activity += 1;
+        let activity = *activity;
+        if self.labeled.contains(&peer) || self.try_label(peer, activity) {
+            self.counter(metric)
+                .with_label_values(&[&peer.to_string()])
+                .inc();
+        } else {
+            self.counter(metric)
+                .with_label_values(&[OTHER_PEERS_LABEL])
+                .inc();
+        }
+    }
+
+    /// Gives the peer its own label if there's room for one, or if it has been more active than
+    /// the least active labeled peer, which then loses its label.  Returns whether the peer was
+    /// given a label.
+    fn try_label(&mut self, peer: NodeId, activity: u64) -> bool {
+        if self.labeled.len() >= self.max_labeled_peers {
+            let least_active = self
+                .labeled
+                .iter()
+                .map(|labeled| (*labeled, self.activity.get(labeled).copied().unwrap_or(0)))
+                .min_by_key(|(_, activity)| *activity);
+            match least_active {
+                Some((least_active, least_activity)) if least_activity < activity => {
+                    self.unlabel(least_active)
+                }
+                _ => return false,
+            }
+        }
+        let _ = self.labeled.insert(peer);
+        true
+    }
+
+    /// Removes the given peer's label, folding its counts into those under the `other` label.
+    fn unlabel(&mut self, peer: NodeId) {
+        let _ = self.labeled.remove(&peer);
+        let label = peer.to_string();
+        for counter in [
+            &self.gossip_timeouts,
+            &self.failed_gets,
+            &self.items_learned,
+        ] {
+            if let Ok(count) = counter.get_metric_with_label_values(&[&label]) {
+                counter
+                    .with_label_values(&[OTHER_PEERS_LABEL])
+                    .inc_by(count.get());
+            }
+            let _ = counter.remove_label_values(&[&label]);
+        }
+    }
+
+    /// Halves the recorded activity of each peer, so that peers' ranking reflects their recent
+    /// activity, forgetting unlabeled peers with no recent activity.
+    pub(super) fn decay_activity(&mut self) {
+        let labeled = &self.labeled;
+        self.activity.retain(|peer, activity| {
+            *activity /= 2;
+            *activity > 0 || labeled.contains(peer)
+        });
+    }
+
+    /// Returns the count of the given metric under the given label.
+    #[cfg(test)]
+    pub(super) fn count(&self, metric: PeerMetric, label: &str) -> u64 {
+        self.counter(metric)
+            .get_metric_with_label_values(&[label])
+            .map_or(0, |counter| counter.get())
+    }
+}
+
+impl Debug for PeerMetrics {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("PeerMetrics")
+            .field("max_labeled_peers", &self.max_labeled_peers)
+            .field("activity", &self.activity.len())
+            .field("labeled", &self.labeled)
+            .finish()
+    }
 }
 
 impl Drop for Metrics {
@@ -74,7 +441,22 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.items_received);
         unregister_metric!(self.registry, self.times_gossiped);
         unregister_metric!(self.registry, self.times_ran_out_of_peers);
+        unregister_metric!(self.registry, self.amplification_factor);
+        unregister_metric!(self.registry, self.amplification_limit_hits);
         unregister_metric!(self.registry, self.table_items_current);
         unregister_metric!(self.registry, self.table_items_finished);
+        unregister_metric!(self.registry, self.table_items_tracked);
+        unregister_metric!(self.registry, self.items_rejected);
+        unregister_metric!(self.registry, self.queued_puts);
+        unregister_metric!(self.registry, self.rejected_peers);
+        unregister_metric!(self.registry, self.put_to_storage_seconds);
+        unregister_metric!(self.registry, self.get_from_storage_seconds);
+        unregister_metric!(self.registry, self.convergence_seconds);
+        unregister_metric!(self.registry, self.achieved_degree);
+        if let Some(peers) = &self.peers {
+            unregister_metric!(self.registry, peers.gossip_timeouts);
+            unregister_metric!(self.registry, peers.failed_gets);
+            unregister_metric!(self.registry, peers.items_learned);
+        }
     }
 }
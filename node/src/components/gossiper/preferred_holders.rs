@@ -0,0 +1,102 @@
+#[cfg(not(test))]
+use std::time::Instant;
+use std::{collections::HashMap, time::Duration};
+
+#[cfg(test)]
+use fake_instant::FakeClock as Instant;
+
+use crate::types::NodeId;
+
+/// The score below which a peer is no longer preferred.  A peer which has delivered a single item
+/// is forgotten once a little over one half-life has passed without it delivering another.
+const MIN_SCORE: f64 = 0.5;
+
+/// Peers which have recently delivered items we were getting from them, so that further gets can
+/// favor reliable peers.
+///
+/// Each delivery adds one to the peer's score, which halves with every half-life that passes.
+#[derive(Debug)]
+pub(super) struct PreferredHolders {
+    /// See `Config::max_preferred_holders`.
+    max_count: usize,
+    /// See `Config::preferred_holder_half_life`.
+    half_life: Duration,
+    /// The score of each preferred peer, as of the time it was last updated.
+    scores: HashMap<NodeId, (f64, Instant)>,
+}
+
+impl PreferredHolders {
+    pub(super) fn new(max_count: usize, half_life: Duration) -> Self {
+        PreferredHolders {
+            max_count,
+            half_life,
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Records that the given peer delivered an item we were getting from it.
+    ///
+    /// If this would exceed the maximum number of preferred peers, the least preferred is dropped.
+    pub(super) fn record_delivery(&mut self, peer: NodeId) {
+        if self.max_count == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let half_life = self.half_life;
+        // Forget peers no longer preferred, so they don't count towards the maximum.
+        self.scores.retain(|_, (score, updated)| {
+            Self::decay(*score, *updated, now, half_life) >= MIN_SCORE
+        });
+        let score = self.scores.get(&peer).map_or(0.0, |(score, updated)| {
+            Self::decay(*score, *updated, now, half_life)
+        }) + 1.0;
+        if !self.scores.contains_key(&peer) && self.scores.len() >= self.max_count {
+            if let Some(least_preferred) = self.ranked_at(now).pop() {
+                let _ = self.scores.remove(&least_preferred);
+            }
+        }
+        let _ = self.scores.insert(peer, (score, now));
+    }
+
+    /// Stops preferring the given peer, e.g. as it failed to deliver an item.
+    pub(super) fn remove(&mut self, peer: &NodeId) {
+        let _ = self.scores.remove(peer);
+    }
+
+    /// Returns an estimate of the heap memory used by the preferred peers' scores.
+    pub(super) fn estimate_heap_size(&self) -> usize {
+        super::estimate_hash_table_heap_size::<(NodeId, (f64, Instant))>(self.scores.capacity())
+    }
+
+    /// Returns the preferred peers, most preferred first.
+    pub(super) fn ranked(&self) -> Vec<NodeId> {
+        self.ranked_at(Instant::now())
+    }
+
+    fn ranked_at(&self, now: Instant) -> Vec<NodeId> {
+        let mut ranked: Vec<_> = self
+            .scores
+            .iter()
+            .map(|(peer, (score, updated))| {
+                (*peer, Self::decay(*score, *updated, now, self.half_life))
+            })
+            .filter(|(_, score)| *score >= MIN_SCORE)
+            .collect();
+        ranked.sort_by(|(lhs_peer, lhs_score), (rhs_peer, rhs_score)| {
+            rhs_score
+                .total_cmp(lhs_score)
+                .then_with(|| lhs_peer.cmp(rhs_peer))
+        });
+        ranked.into_iter().map(|(peer, _)| peer).collect()
+    }
+
+    /// Returns the given score, last updated at `updated`, decayed as at `now`.
+    fn decay(score: f64, updated: Instant, now: Instant, half_life: Duration) -> f64 {
+        if half_life.is_zero() {
+            return score;
+        }
+        let half_lives =
+            now.saturating_duration_since(updated).as_secs_f64() / half_life.as_secs_f64();
+        score * 0.5_f64.powf(half_lives)
+    }
+}
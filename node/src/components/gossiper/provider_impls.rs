@@ -1,4 +1,6 @@
 mod address_provider;
+mod block_header_provider;
 mod block_provider;
 mod deploy_provider;
+mod execution_result_provider;
 mod finality_signature_provider;
@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::{
+    components::gossiper::{GossipItem, Gossiper, ItemProvider},
+    effect::{requests::StorageRequest, EffectBuilder},
+    types::{BlockHash, BlockHeader},
+};
+
+#[async_trait]
+impl ItemProvider<BlockHeader> for Gossiper<{ BlockHeader::ID_IS_COMPLETE_ITEM }, BlockHeader> {
+    async fn is_stored<REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        item_id: BlockHash,
+    ) -> bool {
+        effect_builder
+            .get_block_header_from_storage(item_id, false)
+            .await
+            .is_some()
+    }
+
+    async fn get_from_storage<REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        item_id: BlockHash,
+    ) -> Option<Box<BlockHeader>> {
+        effect_builder
+            .get_block_header_from_storage(item_id, false)
+            .await
+            .map(Box::new)
+    }
+}
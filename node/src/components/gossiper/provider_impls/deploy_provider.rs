@@ -25,4 +25,16 @@ impl ItemProvider<Deploy> for Gossiper<{ Deploy::ID_IS_COMPLETE_ITEM }, Deploy>
             .await
             .map(Box::new)
     }
+
+    async fn get_many_from_storage<REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        item_ids: Vec<DeployId>,
+    ) -> Vec<Option<Box<Deploy>>> {
+        effect_builder
+            .get_stored_deploys(item_ids)
+            .await
+            .into_iter()
+            .map(|maybe_deploy| maybe_deploy.map(Box::new))
+            .collect()
+    }
 }
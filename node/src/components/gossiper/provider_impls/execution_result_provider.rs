@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+use crate::{
+    components::gossiper::{GossipItem, Gossiper, ItemProvider},
+    effect::{requests::StorageRequest, EffectBuilder},
+    types::{DeployExecutionResult, DeployHash},
+};
+
+#[async_trait]
+impl ItemProvider<DeployExecutionResult>
+    for Gossiper<{ DeployExecutionResult::ID_IS_COMPLETE_ITEM }, DeployExecutionResult>
+{
+    async fn is_stored<REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        item_id: DeployHash,
+    ) -> bool {
+        Self::get_from_storage(effect_builder, item_id)
+            .await
+            .is_some()
+    }
+
+    async fn get_from_storage<REv: From<StorageRequest> + Send>(
+        effect_builder: EffectBuilder<REv>,
+        item_id: DeployHash,
+    ) -> Option<Box<DeployExecutionResult>> {
+        let (_deploy, metadata) = effect_builder
+            .get_deploy_and_metadata_from_storage(item_id)
+            .await?;
+        DeployExecutionResult::from_metadata(item_id, metadata).map(Box::new)
+    }
+}
@@ -0,0 +1,98 @@
+#[cfg(not(test))]
+use std::time::Instant;
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    mem,
+    time::Duration,
+};
+
+#[cfg(test)]
+use fake_instant::FakeClock as Instant;
+
+/// The maximum number of item IDs held in quarantine.  Once reached, the oldest entries are evicted
+/// early to make room for new ones.
+const MAX_QUARANTINED_ITEMS: usize = 10_000;
+
+/// A bounded set of IDs of items which weren't validated and stored in time, each of which should
+/// be ignored until its cooldown has elapsed.
+#[derive(Debug)]
+pub(super) struct Quarantine<T> {
+    /// The time at which each quarantined ID is released.
+    releases: HashMap<T, Instant>,
+    /// Quarantined IDs in the order they were added, which is also the order of their release.
+    order: VecDeque<(Instant, T)>,
+    /// See `Config::quarantine_duration`.
+    cooldown: Duration,
+}
+
+impl<T: Clone + Eq + Hash> Quarantine<T> {
+    pub(super) fn new(cooldown: Duration) -> Self {
+        Quarantine {
+            releases: HashMap::new(),
+            order: VecDeque::new(),
+            cooldown,
+        }
+    }
+
+    /// Quarantines the given ID for the cooldown period, restarting the cooldown if it is already
+    /// quarantined.  Does nothing if the cooldown is 0.
+    pub(super) fn insert(&mut self, item_id: T) {
+        if self.cooldown.is_zero() {
+            return;
+        }
+        self.purge();
+        while self.releases.len() >= MAX_QUARANTINED_ITEMS {
+            if !self.pop_oldest() {
+                break;
+            }
+        }
+        let release = Instant::now() + self.cooldown;
+        let _ = self.releases.insert(item_id.clone(), release);
+        self.order.push_back((release, item_id));
+    }
+
+    /// Returns `true` if the given ID is quarantined and its cooldown has not yet elapsed.
+    pub(super) fn contains(&self, item_id: &T) -> bool {
+        self.releases
+            .get(item_id)
+            .map_or(false, |release| *release > Instant::now())
+    }
+
+    /// Number of IDs currently quarantined.
+    pub(super) fn len(&self) -> usize {
+        self.releases.len()
+    }
+
+    /// Returns an estimate of the heap memory used by the quarantine, not including any heap
+    /// memory owned by the IDs themselves.
+    pub(super) fn estimate_heap_size(&self) -> usize {
+        super::estimate_hash_table_heap_size::<(T, Instant)>(self.releases.capacity())
+            + self.order.capacity() * mem::size_of::<(Instant, T)>()
+    }
+
+    /// Releases all IDs whose cooldown has elapsed.
+    pub(super) fn purge(&mut self) {
+        let now = Instant::now();
+        while self
+            .order
+            .front()
+            .map_or(false, |(release, _)| *release <= now)
+        {
+            let _ = self.pop_oldest();
+        }
+    }
+
+    /// Removes the oldest entry in `order`, releasing its ID unless it has been re-quarantined
+    /// since.  Returns `false` if there were no entries.
+    fn pop_oldest(&mut self) -> bool {
+        let (release, item_id) = match self.order.pop_front() {
+            Some(entry) => entry,
+            None => return false,
+        };
+        if self.releases.get(&item_id) == Some(&release) {
+            let _ = self.releases.remove(&item_id);
+        }
+        true
+    }
+}
@@ -0,0 +1,127 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{Arc, RwLock},
+};
+
+use super::GossipHealth;
+
+/// A type-erased source of a gossiper's health, allowing gossipers of different item types to be
+/// held in a single `GossiperRegistry`.
+pub(crate) trait GossipHealthSource: Debug + Send + Sync {
+    /// Returns the most recently reported health of the gossiper.
+    fn health(&self) -> GossipHealth;
+}
+
+/// The health of a gossiper as last published by it, shared with a `GossiperRegistry`.
+#[derive(Debug)]
+pub(super) struct SharedGossipHealth(RwLock<GossipHealth>);
+
+impl SharedGossipHealth {
+    pub(super) fn new(health: GossipHealth) -> Self {
+        SharedGossipHealth(RwLock::new(health))
+    }
+
+    /// Replaces the published health.
+    pub(super) fn publish(&self, health: GossipHealth) {
+        match self.0.write() {
+            Ok(mut published) => *published = health,
+            Err(_) => tracing::error!("gossip health lock has been poisoned"),
+        }
+    }
+}
+
+impl GossipHealthSource for SharedGossipHealth {
+    fn health(&self) -> GossipHealth {
+        match self.0.read() {
+            Ok(health) => *health,
+            Err(poisoned) => *poisoned.into_inner(),
+        }
+    }
+}
+
+/// A registry of the gossipers running in a node, keyed by the name of each gossiper, for
+/// diagnostic purposes.
+///
+/// Cloning the registry yields a handle to the same set of gossipers.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct GossiperRegistry {
+    gossipers: Arc<RwLock<BTreeMap<&'static str, Arc<dyn GossipHealthSource>>>>,
+}
+
+impl GossiperRegistry {
+    /// Registers a gossiper under the given name, replacing any previously registered under it.
+    pub(crate) fn register(&self, name: &'static str, source: Arc<dyn GossipHealthSource>) {
+        match self.gossipers.write() {
+            Ok(mut gossipers) => {
+                let _ = gossipers.insert(name, source);
+            }
+            Err(_) => tracing::error!("gossiper registry lock has been poisoned"),
+        }
+    }
+
+    /// Returns the names of all registered gossipers, in alphabetical order.
+    pub(crate) fn active_types(&self) -> Vec<&'static str> {
+        self.gossipers
+            .read()
+            .map(|gossipers| gossipers.keys().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the health of the gossiper registered under the given name.
+    pub(crate) fn health_of(&self, name: &str) -> Option<GossipHealth> {
+        self.gossipers
+            .read()
+            .ok()?
+            .get(name)
+            .map(|source| source.health())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockGossiper(GossipHealth);
+
+    impl GossipHealthSource for MockGossiper {
+        fn health(&self) -> GossipHealth {
+            self.0
+        }
+    }
+
+    #[test]
+    fn should_list_registered_gossipers_and_their_health() {
+        let healthy = GossipHealth {
+            is_accepting_items: true,
+            active_item_count: 2,
+            stalled_item_count: 0,
+            last_successful_gossip: None,
+        };
+        let stalled = GossipHealth {
+            is_accepting_items: true,
+            active_item_count: 2,
+            stalled_item_count: 2,
+            last_successful_gossip: None,
+        };
+
+        let registry = GossiperRegistry::default();
+        assert!(registry.active_types().is_empty());
+        registry.register("deploy_gossiper", Arc::new(MockGossiper(healthy)));
+        // Registration through a clone is visible via the original.
+        registry
+            .clone()
+            .register("block_gossiper", Arc::new(MockGossiper(stalled)));
+
+        assert_eq!(
+            registry.active_types(),
+            vec!["block_gossiper", "deploy_gossiper"]
+        );
+        assert_eq!(registry.health_of("deploy_gossiper"), Some(healthy));
+        assert!(registry.health_of("deploy_gossiper").unwrap().is_healthy());
+        assert_eq!(registry.health_of("block_gossiper"), Some(stalled));
+        assert!(!registry.health_of("block_gossiper").unwrap().is_healthy());
+        assert!(registry.health_of("address_gossiper").is_none());
+    }
+}
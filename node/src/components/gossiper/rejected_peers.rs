@@ -0,0 +1,98 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    mem,
+};
+
+use crate::types::NodeId;
+
+/// The maximum number of peers for which rejection records are held.  Once reached, the least
+/// recently rejected peer's record is evicted to make room for a new one.
+const MAX_REJECTED_PEERS: usize = 1_000;
+
+/// A bounded, least-recently-used set of records of peers whose messages have been rejected.
+///
+/// A peer whose record is evicted is treated as though it had never been rejected.
+#[derive(Debug)]
+pub(super) struct RejectedPeers {
+    /// The number of rejections recorded against each peer.
+    rejection_counts: HashMap<NodeId, u32>,
+    /// Peers in order of their most recent rejection, oldest first.
+    order: VecDeque<NodeId>,
+    capacity: usize,
+}
+
+impl RejectedPeers {
+    pub(super) fn new() -> Self {
+        Self::with_capacity(MAX_REJECTED_PEERS)
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        RejectedPeers {
+            rejection_counts: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    /// Records a rejection of the given peer, evicting the least recently rejected peer if full.
+    pub(super) fn record(&mut self, peer: NodeId) {
+        if self.rejection_counts.contains_key(&peer) {
+            self.order.retain(|rejected_peer| *rejected_peer != peer);
+        } else if self.rejection_counts.len() >= self.capacity {
+            if let Some(evicted) = self.order.pop_front() {
+                let _ = self.rejection_counts.remove(&evicted);
+            }
+        }
+        *self.rejection_counts.entry(peer).or_default() += 1;
+        self.order.push_back(peer);
+    }
+
+    /// Returns the number of rejections recorded against the given peer.
+    #[cfg(test)]
+    pub(super) fn rejection_count(&self, peer: &NodeId) -> u32 {
+        self.rejection_counts.get(peer).copied().unwrap_or_default()
+    }
+
+    /// Number of peers for which rejection records are held.
+    pub(super) fn len(&self) -> usize {
+        self.rejection_counts.len()
+    }
+
+    /// Returns an estimate of the heap memory used by the rejection records.
+    pub(super) fn estimate_heap_size(&self) -> usize {
+        super::estimate_hash_table_heap_size::<(NodeId, u32)>(self.rejection_counts.capacity())
+            + self.order.capacity() * mem::size_of::<NodeId>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_evict_least_recently_rejected_peer() {
+        let mut rng = crate::new_rng();
+        let mut rejected_peers = RejectedPeers::with_capacity(2);
+        let peer_1 = NodeId::random(&mut rng);
+        let peer_2 = NodeId::random(&mut rng);
+        let peer_3 = NodeId::random(&mut rng);
+
+        rejected_peers.record(peer_1);
+        rejected_peers.record(peer_2);
+        rejected_peers.record(peer_1);
+        assert_eq!(rejected_peers.rejection_count(&peer_1), 2);
+        assert_eq!(rejected_peers.rejection_count(&peer_2), 1);
+
+        // `peer_2` is now the least recently rejected, so it is evicted to make room.
+        rejected_peers.record(peer_3);
+        assert_eq!(rejected_peers.len(), 2);
+        assert_eq!(rejected_peers.rejection_count(&peer_2), 0);
+        assert_eq!(rejected_peers.rejection_count(&peer_1), 2);
+        assert_eq!(rejected_peers.rejection_count(&peer_3), 1);
+
+        // An evicted peer is re-admitted as though never rejected.
+        rejected_peers.record(peer_2);
+        assert_eq!(rejected_peers.rejection_count(&peer_2), 1);
+        assert_eq!(rejected_peers.rejection_count(&peer_1), 0);
+    }
+}
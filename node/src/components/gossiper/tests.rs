@@ -4,20 +4,25 @@
 use std::{
     collections::{BTreeSet, HashMap},
     iter,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
 
 use derive_more::{Display, From};
+use futures::{future::BoxFuture, FutureExt};
 use prometheus::Registry;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
 use reactor::ReactorEvent;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tempfile::TempDir;
 use thiserror::Error;
 use tokio::time;
 use tracing::debug;
 
-use casper_types::{testing::TestRng, EraId, ProtocolVersion, TimeDiff};
+use casper_types::{testing::TestRng, EraId, ProtocolVersion, PublicKey, SecretKey, TimeDiff};
 
 use super::*;
 use crate::{
@@ -40,14 +45,16 @@ use crate::{
         requests::AcceptDeployRequest,
     },
     protocol::Message as NodeMessage,
-    reactor::{self, EventQueueHandle, QueueKind, Runner, TryCrankOutcome},
+    reactor::{self, EventQueueHandle, QueueKind, Runner, Scheduler, TryCrankOutcome},
     testing::{
         self,
         network::{NetworkedReactor, TestingNetwork},
         ConditionCheckReactor, FakeDeployAcceptor,
     },
-    types::{Block, Chainspec, ChainspecRawBytes, Deploy, FinalitySignature, NodeId},
-    utils::WithDir,
+    types::{
+        Block, BlockHash, Chainspec, ChainspecRawBytes, Deploy, DeployId, FinalitySignature, NodeId,
+    },
+    utils::{self, WithDir},
     NodeRng,
 };
 
@@ -118,6 +125,8 @@ impl Unhandled for TrieRequestIncoming {}
 impl Unhandled for TrieDemand {}
 impl Unhandled for TrieResponseIncoming {}
 impl Unhandled for FinalitySignatureIncoming {}
+impl Unhandled for NetworkRequest<Message<VetoableItem>> {}
+impl Unhandled for GossiperAnnouncement<VetoableItem> {}
 
 /// Error type returned by the test reactor.
 #[derive(Debug, Error)]
@@ -324,6 +333,17 @@ impl NetworkedReactor for Reactor {
     }
 }
 
+/// Creates an effect builder for tests which only inspect the effects returned by the gossiper.
+fn new_effect_builder() -> EffectBuilder<Event> {
+    let scheduler = utils::leak(Scheduler::new(QueueKind::weights(), None));
+    EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler))
+}
+
+/// Creates a standalone deploy gossiper, not attached to any reactor.
+fn new_deploy_gossiper(config: Config) -> Gossiper<{ Deploy::ID_IS_COMPLETE_ITEM }, Deploy> {
+    Gossiper::new("test_deploy_gossiper", config, &Registry::new()).unwrap()
+}
+
 fn announce_deploy_received(
     deploy: Arc<Deploy>,
 ) -> impl FnOnce(EffectBuilder<Event>) -> Effects<Event> {
@@ -749,3 +769,2997 @@ async fn should_ignore_unexpected_get_item_message() {
 async fn should_ignore_unexpected_item_message() {
     should_ignore_unexpected_message(Unexpected::Item).await
 }
+
+#[test]
+fn should_queue_puts_beyond_limit() {
+    const MAX_CONCURRENT_PUTS: usize = 2;
+    const ITEM_COUNT: usize = 5;
+
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config {
+        max_concurrent_puts: MAX_CONCURRENT_PUTS as u32,
+        ..Default::default()
+    });
+    let sender = NodeId::random(&mut rng);
+
+    let deploys: Vec<_> =
+        iter::repeat_with(|| Box::new(Deploy::random_valid_native_transfer(&mut rng)))
+            .take(ITEM_COUNT)
+            .collect();
+    for deploy in &deploys {
+        let _ = gossiper.table.new_data_id(&deploy.gossip_id(), sender);
+        let _ = gossiper.handle_item_received_from_peer(effect_builder, deploy.clone(), sender);
+    }
+    assert_eq!(gossiper.in_flight_puts.len(), MAX_CONCURRENT_PUTS);
+    assert_eq!(gossiper.queued_puts.len(), ITEM_COUNT - MAX_CONCURRENT_PUTS);
+
+    // Complete the puts one at a time: each should free a slot for the next queued item in order.
+    for (index, deploy) in deploys.iter().enumerate() {
+        assert!(gossiper.in_flight_puts.contains_key(&deploy.gossip_id()));
+        let _ = gossiper.handle_item_received(
+            effect_builder,
+            deploy.gossip_id(),
+            Source::Peer(sender),
+            deploy.gossip_target(),
+        );
+        assert!(!gossiper.in_flight_puts.contains_key(&deploy.gossip_id()));
+        if let Some(next) = deploys.get(index + MAX_CONCURRENT_PUTS) {
+            assert!(gossiper.in_flight_puts.contains_key(&next.gossip_id()));
+        }
+    }
+    assert!(gossiper.in_flight_puts.is_empty());
+    assert!(gossiper.queued_puts.is_empty());
+}
+
+#[test]
+fn should_report_healthy_gossiper() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+
+    let health = gossiper.health();
+    assert!(health.is_healthy());
+    assert_eq!(health.active_item_count, 0);
+    assert!(health.last_successful_gossip.is_none());
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let _ = gossiper
+        .table
+        .new_complete_data(&item_id, None, deploy.gossip_target());
+    let peers: HashSet<NodeId> = iter::once(NodeId::random(&mut rng)).collect();
+    let _ = gossiper.gossiped_to(effect_builder, item_id, 1, peers);
+
+    let health = gossiper.health();
+    assert!(health.is_healthy());
+    assert!(health.is_accepting_items);
+    assert_eq!(health.active_item_count, 1);
+    assert_eq!(health.stalled_item_count, 0);
+    assert!(health.last_successful_gossip.is_some());
+}
+
+#[test]
+fn should_report_fully_stalled_gossiper() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        max_concurrent_puts: 1,
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let sender = NodeId::random(&mut rng);
+
+    // Fill the only put slot with an item which is never stored, and start gossiping another.
+    let received = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let _ = gossiper.table.new_data_id(&received.gossip_id(), sender);
+    let _ = gossiper.handle_item_received_from_peer(effect_builder, received, sender);
+    let held = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper
+        .table
+        .new_complete_data(&held.gossip_id(), None, held.gossip_target());
+
+    // Let both items go without progress for longer than the stall threshold.
+    let stall_threshold = Duration::from(config.gossip_request_timeout())
+        + Duration::from(config.get_remainder_timeout())
+        + Duration::from(config.validate_and_store_timeout());
+    fake_instant::FakeClock::advance_time(stall_threshold.as_millis() as u64);
+
+    let health = gossiper.health();
+    assert!(!health.is_healthy());
+    assert!(!health.is_accepting_items);
+    assert_eq!(health.active_item_count, 2);
+    assert_eq!(health.stalled_item_count, 2);
+    assert!(health.last_successful_gossip.is_none());
+}
+
+#[tokio::test]
+async fn should_report_items_stuck_awaiting_remainder() {
+    const THRESHOLD: Duration = Duration::from_secs(10);
+
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        awaiting_remainder_stall_threshold: TimeDiff::from(THRESHOLD),
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+
+    // Start awaiting the remainder of one item while already holding another.
+    let awaited = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper
+        .table
+        .new_data_id(&awaited.gossip_id(), NodeId::random(&mut rng));
+    let held = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper
+        .table
+        .new_complete_data(&held.gossip_id(), None, held.gossip_target());
+
+    // Each round of housekeeping yields one effect to schedule the next round.
+    fake_instant::FakeClock::advance_time(THRESHOLD.as_millis() as u64 - 1);
+    let effects = gossiper.handle_event(effect_builder, &mut rng, super::Event::Housekeeping);
+    assert_eq!(effects.len(), 1);
+
+    // Only the awaited item should be reported once the threshold has passed, and only once.
+    fake_instant::FakeClock::advance_time(1);
+    let effects = gossiper.handle_event(effect_builder, &mut rng, super::Event::Housekeeping);
+    assert_eq!(effects.len(), 2);
+    let events = effects.into_iter().next().unwrap().await;
+    assert!(matches!(
+        events.as_slice(),
+        [super::Event::AwaitingRemainderStalled { item_id, elapsed }]
+            if *item_id == awaited.gossip_id() && *elapsed >= THRESHOLD
+    ));
+    let effects = gossiper.handle_event(effect_builder, &mut rng, super::Event::Housekeeping);
+    assert_eq!(effects.len(), 1);
+}
+
+#[test]
+fn should_apply_size_limit_only_to_items_from_untrusted_peers() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        max_untrusted_item_size: 1,
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let trusted = NodeId::random(&mut rng);
+    let untrusted = NodeId::random(&mut rng);
+    let rejected = NodeId::random(&mut rng);
+    gossiper.set_peer_trust(Box::new(move |peer| {
+        if *peer == trusted {
+            TrustLevel::Trusted
+        } else if *peer == rejected {
+            TrustLevel::Rejected
+        } else {
+            TrustLevel::Untrusted
+        }
+    }));
+
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let item_id = deploy.gossip_id();
+    let _ = gossiper.table.new_data_id(&item_id, untrusted);
+    let _ = gossiper.table.new_data_id(&item_id, trusted);
+
+    // Gossip from the rejected peer should be dropped outright.
+    assert!(!gossiper.is_acceptable_gossip(&Message::Gossip(item_id), rejected));
+    assert!(gossiper.is_acceptable_gossip(&Message::Gossip(item_id), untrusted));
+
+    // The oversized item should be dropped when received from the untrusted peer...
+    let effects =
+        gossiper.handle_item_received_from_peer(effect_builder, deploy.clone(), untrusted);
+    assert!(effects.is_empty());
+    assert!(!gossiper.in_flight_puts.contains_key(&item_id));
+
+    // ...but put when received from the trusted one.
+    let effects = gossiper.handle_item_received_from_peer(effect_builder, deploy, trusted);
+    assert!(!effects.is_empty());
+    assert!(gossiper.in_flight_puts.contains_key(&item_id));
+}
+
+#[tokio::test]
+async fn should_batch_storage_reads_for_get_requests_within_window() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        get_request_batch_window: TimeDiff::from_millis(10),
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let requester = NodeId::random(&mut rng);
+    let item_ids: Vec<_> = iter::repeat_with(|| DeployId::random(&mut rng))
+        .take(3)
+        .collect();
+
+    // Only the first get request should start the window, and none should read from storage yet.
+    for (index, item_id) in item_ids.iter().enumerate() {
+        let effects = gossiper.get_from_storage_for(effect_builder, *item_id, requester);
+        assert_eq!(effects.len(), usize::from(index == 0));
+    }
+    assert!(gossiper.get_from_storage_started.is_empty());
+
+    // Once the window elapses, all the requested items should be read in a single batch.
+    let effects =
+        gossiper.handle_event(effect_builder, &mut rng, super::Event::ServeGetRequestBatch);
+    for effect in effects {
+        tokio::spawn(effect);
+    }
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    assert_eq!(scheduler.item_count(), 0);
+    match event {
+        Event::StorageRequest(StorageRequest::GetDeploysById { deploy_ids, .. }) => {
+            assert_eq!(
+                deploy_ids.into_iter().collect::<HashSet<_>>(),
+                item_ids.into_iter().collect::<HashSet<_>>()
+            );
+        }
+        other => panic!("unexpected event: {}", other),
+    }
+    assert!(gossiper.pending_get_requests.is_empty());
+    assert_eq!(gossiper.get_from_storage_started.len(), 3);
+}
+
+#[tokio::test]
+async fn should_serve_get_requests_from_cache_until_ttl_elapses() {
+    const TTL_MILLIS: u64 = 1_000;
+
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        get_cache_capacity: 10,
+        get_cache_ttl: TimeDiff::from_millis(TTL_MILLIS),
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let item_id = deploy.gossip_id();
+    let _ = gossiper
+        .table
+        .new_complete_data(&item_id, None, deploy.gossip_target());
+    let requester = NodeId::random(&mut rng);
+
+    async fn single_deploy_read(
+        scheduler: &Scheduler<Event>,
+        effects: Effects<super::Event<Deploy>>,
+    ) -> DeployId {
+        for effect in effects {
+            tokio::spawn(effect);
+        }
+        let ((_ancestor, event), _) = scheduler.pop().await;
+        assert_eq!(scheduler.item_count(), 0);
+        match event {
+            Event::StorageRequest(StorageRequest::GetDeploy { deploy_id, .. }) => deploy_id,
+            other => panic!("unexpected event: {}", other),
+        }
+    }
+
+    // The first request should be served by reading the item from storage, caching the result.
+    let effects = gossiper.handle_get_item_request(effect_builder, item_id.clone(), requester, 0);
+    assert_eq!(single_deploy_read(scheduler, effects).await, item_id);
+    let effects = gossiper.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::GetFromStorageResult {
+            item_id: item_id.clone(),
+            requester,
+            maybe_item: Some(deploy.clone()),
+        },
+    );
+    let (dest, message) = single_sent_message(scheduler, effects).await;
+    assert_eq!(dest, requester);
+    assert!(matches!(message, Message::Item(ref item) if *item == deploy));
+
+    // Further requests within the TTL should be served from the cache.
+    fake_instant::FakeClock::advance_time(TTL_MILLIS - 1);
+    let effects = gossiper.handle_get_item_request(effect_builder, item_id.clone(), requester, 0);
+    let (dest, message) = single_sent_message(scheduler, effects).await;
+    assert_eq!(dest, requester);
+    assert!(matches!(message, Message::Item(ref item) if *item == deploy));
+
+    // Once the TTL has elapsed, the cached item should be evicted, and requests served by reading
+    // the item from storage again.
+    fake_instant::FakeClock::advance_time(1);
+    let effects = gossiper.handle_get_item_request(effect_builder, item_id.clone(), requester, 0);
+    assert_eq!(single_deploy_read(scheduler, effects).await, item_id);
+    assert_eq!(gossiper.get_cache.len(), 0);
+}
+
+#[tokio::test]
+async fn should_read_through_to_holder_on_storage_miss_and_relay_item() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        max_read_through_hops: 1,
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let item_id = deploy.gossip_id();
+    let holder = NodeId::random(&mut rng);
+    let requester = NodeId::random(&mut rng);
+    let _ = gossiper.table.new_data_id(&item_id, holder);
+    let _ = gossiper
+        .table
+        .new_complete_data(&item_id, Some(holder), deploy.gossip_target());
+
+    async fn sent_messages(
+        scheduler: &Scheduler<Event>,
+        effects: Effects<super::Event<Deploy>>,
+    ) -> Vec<(NodeId, Message<Deploy>)> {
+        let effect_count = effects.len();
+        for effect in effects {
+            tokio::spawn(effect);
+        }
+        let mut messages = vec![];
+        for _ in 0..effect_count {
+            let ((_ancestor, event), _) = scheduler.pop().await;
+            if let Event::NetworkRequest(NetworkRequest::SendMessage { dest, payload, .. }) = event
+            {
+                if let NodeMessage::DeployGossiper(message) = *payload {
+                    messages.push((*dest, message));
+                }
+            }
+        }
+        messages
+    }
+
+    // A request which has already been relayed the maximum number of times isn't relayed again.
+    let effects = gossiper.read_through(effect_builder, item_id.clone(), requester, 1);
+    assert!(effects.is_empty());
+    assert!(gossiper.read_throughs.is_empty());
+
+    // A request missing from storage should be relayed to the holder.
+    let effects = gossiper.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::GetFromStorageResult {
+            item_id: item_id.clone(),
+            requester,
+            maybe_item: None,
+        },
+    );
+    let messages = sent_messages(scheduler, effects).await;
+    assert!(matches!(
+        messages.as_slice(),
+        [(dest, Message::RelayedGetItem { item_id: relayed_item_id, hops: 1 })]
+            if *dest == holder && *relayed_item_id == item_id
+    ));
+
+    // The item should be relayed to the requester once it arrives from the holder.
+    let effects = gossiper.handle_item_received_from_peer(effect_builder, deploy.clone(), holder);
+    let messages = sent_messages(scheduler, effects).await;
+    assert!(matches!(
+        messages.as_slice(),
+        [(dest, Message::Item(item))] if *dest == requester && *item == deploy
+    ));
+    assert!(gossiper.read_throughs.is_empty());
+}
+
+#[test]
+fn should_record_put_latency_on_completed_put() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let sender = NodeId::random(&mut rng);
+
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let item_id = deploy.gossip_id();
+    let target = deploy.gossip_target();
+    let _ = gossiper.table.new_data_id(&item_id, sender);
+    let _ = gossiper.handle_item_received_from_peer(effect_builder, deploy, sender);
+    assert_eq!(
+        gossiper.metrics.put_to_storage_seconds.get_sample_count(),
+        0
+    );
+
+    let _ = gossiper.handle_item_received(
+        effect_builder,
+        item_id.clone(),
+        Source::Peer(sender),
+        target,
+    );
+    assert_eq!(
+        gossiper.metrics.put_to_storage_seconds.get_sample_count(),
+        1
+    );
+    assert!(!gossiper.in_flight_puts.contains_key(&item_id));
+
+    // A subsequent timeout for the same item should neither record timing nor leave an entry.
+    let _ = gossiper.check_item_received_timeout(effect_builder, item_id.clone());
+    assert_eq!(
+        gossiper.metrics.put_to_storage_seconds.get_sample_count(),
+        1
+    );
+    assert!(gossiper.in_flight_puts.is_empty());
+}
+
+#[test]
+fn should_summarize_gossip_table() {
+    let mut rng = crate::new_rng();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let holder = NodeId::random(&mut rng);
+
+    let complete = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper.table.new_complete_data(
+        &complete.gossip_id(),
+        Some(holder),
+        complete.gossip_target(),
+    );
+    let incomplete = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper.table.new_data_id(&incomplete.gossip_id(), holder);
+    let finished = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper.table.new_data_id(&finished.gossip_id(), holder);
+    assert!(gossiper.table.force_finish(&finished.gossip_id()));
+
+    let summaries = gossiper.table.summaries();
+    assert_eq!(summaries.len(), 3);
+
+    let find = |item_id: DeployId| {
+        summaries
+            .iter()
+            .find(|summary| summary.id == item_id.to_string())
+            .unwrap()
+    };
+    let summary = find(complete.gossip_id());
+    assert_eq!(summary.holders, vec![holder]);
+    assert!(summary.is_complete);
+    assert!(summary.age_millis.is_some());
+    assert!(!summary.is_finished);
+
+    let summary = find(incomplete.gossip_id());
+    assert_eq!(summary.holders, vec![holder]);
+    assert!(!summary.is_complete);
+    assert!(!summary.is_finished);
+
+    let summary = find(finished.gossip_id());
+    assert!(summary.holders.is_empty());
+    assert!(summary.age_millis.is_none());
+    assert!(summary.is_finished);
+}
+
+#[test]
+fn should_retry_gossip_when_network_unavailable() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let target = deploy.gossip_target();
+    let count = match gossiper.table.new_complete_data(&item_id, None, target) {
+        GossipAction::ShouldGossip(should_gossip) => should_gossip.count,
+        action => panic!("expected ShouldGossip, got {}", action),
+    };
+
+    // The network being unavailable should schedule a retry without finishing the item.
+    let effects = gossiper.handle_gossip_network_unavailable(
+        effect_builder,
+        item_id.clone(),
+        target,
+        count,
+        HashSet::new(),
+    );
+    assert_eq!(effects.len(), 1);
+    assert_eq!(gossiper.table.items_current(), 1);
+    assert_eq!(gossiper.table.items_finished(), 0);
+
+    // Once the network recovers, the retried gossip reaches peers and the item keeps gossiping.
+    let peers: HashSet<NodeId> = iter::repeat_with(|| NodeId::random(&mut rng))
+        .take(count)
+        .collect();
+    let _ = gossiper.gossiped_to(effect_builder, item_id.clone(), count, peers);
+    assert_eq!(gossiper.table.items_current(), 1);
+    assert!(gossiper.health().last_successful_gossip.is_some());
+
+    // In contrast, genuinely having no peers finishes the item.
+    let other = Deploy::random_valid_native_transfer(&mut rng);
+    let other_id = other.gossip_id();
+    let _ = gossiper
+        .table
+        .new_complete_data(&other_id, None, other.gossip_target());
+    let _ = gossiper.gossiped_to(effect_builder, other_id.clone(), count, HashSet::new());
+    assert_eq!(gossiper.table.items_finished(), 1);
+
+    // A retry for an item no longer in the table is dropped.
+    let effects = gossiper.handle_gossip_network_unavailable(
+        effect_builder,
+        Deploy::random_valid_native_transfer(&mut rng).gossip_id(),
+        target,
+        count,
+        HashSet::new(),
+    );
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn should_ignore_quarantined_item_during_cooldown() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        quarantine_duration: TimeDiff::from_seconds(300),
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let sender = NodeId::random(&mut rng);
+
+    // Receive an item which is never validated and stored, so that it is quarantined.
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let item_id = deploy.gossip_id();
+    let _ = gossiper.table.new_data_id(&item_id, sender);
+    let _ = gossiper.handle_item_received_from_peer(effect_builder, deploy, sender);
+    let _ = gossiper.check_item_received_timeout(effect_builder, item_id.clone());
+
+    let gossip = |item_id: DeployId| {
+        super::Event::Incoming(GossiperIncoming {
+            sender,
+            message: Box::new(Message::Gossip(item_id)),
+        })
+    };
+
+    // During the cooldown, gossip about the item is dropped.
+    let effects = gossiper.handle_event(effect_builder, &mut rng, gossip(item_id.clone()));
+    assert!(effects.is_empty());
+
+    // Once the cooldown has elapsed, gossip about the item is handled as normal.
+    let cooldown = Duration::from(config.quarantine_duration());
+    fake_instant::FakeClock::advance_time(cooldown.as_millis() as u64);
+    let effects = gossiper.handle_event(effect_builder, &mut rng, gossip(item_id));
+    assert_eq!(effects.len(), 1);
+}
+
+#[test]
+fn should_not_quarantine_item_by_default() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let sender = NodeId::random(&mut rng);
+
+    // An item which merely isn't validated and stored in time shouldn't be quarantined.
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let item_id = deploy.gossip_id();
+    let _ = gossiper.table.new_data_id(&item_id, sender);
+    let _ = gossiper.handle_item_received_from_peer(effect_builder, deploy, sender);
+    let _ = gossiper.check_item_received_timeout(effect_builder, item_id.clone());
+    assert!(!gossiper.is_quarantined(&item_id, sender));
+    assert_eq!(gossiper.quarantine.len(), 0);
+}
+
+#[test]
+fn should_clamp_gossip_count_to_peer_count() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config::default();
+    let infection_target = config.infection_target() as usize;
+    let mut gossiper = new_deploy_gossiper(config);
+    let peer_count = Arc::new(AtomicUsize::new(infection_target - 1));
+    gossiper.set_peer_count_hint(Arc::clone(&peer_count));
+
+    // With fewer peers than the infection target, the gossip count is clamped to the peer count.
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let count = match gossiper
+        .table
+        .new_complete_data(&item_id, None, deploy.gossip_target())
+    {
+        GossipAction::ShouldGossip(should_gossip) => should_gossip.count,
+        action => panic!("expected ShouldGossip, got {}", action),
+    };
+    assert_eq!(count, infection_target - 1);
+
+    // Every connected peer is infected, and any further gossip finds no more peers, after which
+    // the item finishes.
+    let peers: HashSet<NodeId> = iter::repeat_with(|| NodeId::random(&mut rng))
+        .take(count)
+        .collect();
+    let _ = gossiper.gossiped_to(effect_builder, item_id.clone(), count, peers.clone());
+    for peer in peers {
+        if let GossipAction::ShouldGossip(should_gossip) =
+            gossiper.table.we_infected(&item_id, peer)
+        {
+            let _ = gossiper.gossiped_to(
+                effect_builder,
+                item_id.clone(),
+                should_gossip.count,
+                HashSet::new(),
+            );
+        }
+    }
+    assert_eq!(gossiper.table.items_current(), 0);
+    assert_eq!(gossiper.table.items_finished(), 1);
+
+    // The reactor updating the peer count is reflected in subsequent gossip.
+    peer_count.store(infection_target + 1, Ordering::Relaxed);
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    match gossiper
+        .table
+        .new_complete_data(&deploy.gossip_id(), None, deploy.gossip_target())
+    {
+        GossipAction::ShouldGossip(should_gossip) => {
+            assert_eq!(should_gossip.count, infection_target)
+        }
+        action => panic!("expected ShouldGossip, got {}", action),
+    }
+}
+
+#[test]
+fn should_abandon_item_stored_under_wrong_id() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        verify_after_put: true,
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let sender = NodeId::random(&mut rng);
+
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let item_id = deploy.gossip_id();
+    let target = deploy.gossip_target();
+    let _ = gossiper.table.new_data_id(&item_id, sender);
+    let _ = gossiper.handle_item_received_from_peer(effect_builder, deploy.clone(), sender);
+
+    // Once stored, verification is requested rather than gossiping immediately.
+    let effects = gossiper.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::ItemReceived {
+            item_id: item_id.clone(),
+            source: Source::Peer(sender),
+            target,
+        },
+    );
+    assert_eq!(effects.len(), 1);
+    assert!(gossiper.in_flight_puts.contains_key(&item_id));
+
+    // A faulty storage returns a different item for the ID, so verification fails and the item is
+    // finished with only the finished-gossiping announcement.
+    let wrong_deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let effects = gossiper.handle_put_verification_result(
+        effect_builder,
+        item_id.clone(),
+        Source::Peer(sender),
+        target,
+        Some(wrong_deploy),
+    );
+    assert_eq!(effects.len(), 1);
+    assert!(!gossiper.in_flight_puts.contains_key(&item_id));
+
+    // The item is not gossiped onwards even if a later verification would succeed.
+    let effects = gossiper.handle_put_verification_result(
+        effect_builder,
+        item_id,
+        Source::Peer(sender),
+        target,
+        Some(deploy),
+    );
+    assert!(effects.is_empty());
+}
+
+#[tokio::test]
+async fn should_treat_read_only_storage_as_fatal_when_put_item_is_missing() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        verify_after_put: true,
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let item_id = deploy.gossip_id();
+    let sender = NodeId::random(&mut rng);
+    let _ = gossiper.put_item(effect_builder, deploy, sender);
+    assert!(gossiper.in_flight_puts.contains_key(&item_id));
+
+    // Storage turns out to be read-only, which should be reported as fatal rather than the item
+    // just being dropped.
+    let mut effects =
+        gossiper.handle_storage_writability_checked(effect_builder, item_id.clone(), false);
+    assert_eq!(effects.len(), 1);
+    assert!(!gossiper.in_flight_puts.contains_key(&item_id));
+    let events = effects.pop().unwrap().await;
+    assert!(matches!(
+        events.as_slice(),
+        [super::Event::FatalInconsistency { detail }] if detail.contains("storage is read-only")
+    ));
+}
+
+#[test]
+fn should_only_accept_validly_signed_gossip_when_required() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        require_signed_gossip: true,
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let sender = NodeId::random(&mut rng);
+    let secret_key = SecretKey::random(&mut rng);
+    let public_key = PublicKey::from(&secret_key);
+    let sender_key = public_key.clone();
+    gossiper.set_peer_signing_key(Box::new(move |peer| {
+        (*peer == sender).then(|| sender_key.clone())
+    }));
+
+    let incoming = |message: Message<Deploy>| {
+        super::Event::Incoming(GossiperIncoming {
+            sender,
+            message: Box::new(message),
+        })
+    };
+
+    // A validly-signed gossip message is handled by checking whether we hold the item.
+    let item_id = Deploy::random_valid_native_transfer(&mut rng).gossip_id();
+    let signed_gossip = Message::SignedGossip {
+        item_id: item_id.clone(),
+        signature: GossipSignature::create(&item_id, &secret_key, &public_key),
+    };
+    let effects = gossiper.handle_event(effect_builder, &mut rng, incoming(signed_gossip));
+    assert_eq!(effects.len(), 1);
+
+    // A signature over a different item ID is invalid, so the message is dropped.
+    let item_id = Deploy::random_valid_native_transfer(&mut rng).gossip_id();
+    let other_item_id = Deploy::random_valid_native_transfer(&mut rng).gossip_id();
+    let invalidly_signed_gossip = Message::SignedGossip {
+        item_id,
+        signature: GossipSignature::create(&other_item_id, &secret_key, &public_key),
+    };
+    let effects =
+        gossiper.handle_event(effect_builder, &mut rng, incoming(invalidly_signed_gossip));
+    assert!(effects.is_empty());
+    assert_eq!(gossiper.rejected_peers.rejection_count(&sender), 1);
+
+    // An unsigned gossip message is dropped.
+    let item_id = Deploy::random_valid_native_transfer(&mut rng).gossip_id();
+    let effects =
+        gossiper.handle_event(effect_builder, &mut rng, incoming(Message::Gossip(item_id)));
+    assert!(effects.is_empty());
+    assert!(gossiper.table.is_empty());
+}
+
+#[test]
+fn should_reject_signed_gossip_not_signed_by_sender() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let sender = NodeId::random(&mut rng);
+    let sender_key = PublicKey::from(&SecretKey::random(&mut rng));
+    gossiper.set_peer_signing_key(Box::new(move |peer| {
+        (*peer == sender).then(|| sender_key.clone())
+    }));
+
+    // The gossip is validly signed, but by a key other than the one the sender connected with.
+    let other_secret_key = SecretKey::random(&mut rng);
+    let other_public_key = PublicKey::from(&other_secret_key);
+    let item_id = Deploy::random_valid_native_transfer(&mut rng).gossip_id();
+    let signature = GossipSignature::create(&item_id, &other_secret_key, &other_public_key);
+    assert!(signature.is_valid_for(&item_id));
+    let event = super::Event::Incoming(GossiperIncoming {
+        sender,
+        message: Box::new(Message::SignedGossip { item_id, signature }),
+    });
+
+    let effects = gossiper.handle_event(effect_builder, &mut rng, event);
+    assert!(effects.is_empty());
+    assert!(gossiper.table.is_empty());
+    assert_eq!(gossiper.rejected_peers.rejection_count(&sender), 1);
+}
+
+#[test]
+fn should_keep_wire_encoding_of_original_messages() {
+    let mut rng = crate::new_rng();
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+
+    // Bincode encodes the variant index first, so the messages understood by every peer must keep
+    // their original indices.
+    let variant_index = |message: Message<Deploy>| {
+        let serialized = bincode::serialize(&message).unwrap();
+        u32::from_le_bytes(serialized[..4].try_into().unwrap())
+    };
+    assert_eq!(variant_index(Message::Gossip(item_id.clone())), 0);
+    assert_eq!(
+        variant_index(Message::GossipResponse {
+            item_id: item_id.clone(),
+            is_already_held: true,
+        }),
+        1
+    );
+    assert_eq!(variant_index(Message::GetItem(item_id)), 2);
+    assert_eq!(variant_index(Message::Item(Box::new(deploy))), 3);
+}
+
+#[test]
+fn should_suppress_cascade_for_well_propagated_item() {
+    const CASCADE_THRESHOLD: u32 = 3;
+
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        cascade_threshold: CASCADE_THRESHOLD,
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+
+    // An item with few holders still gossips onwards on a response from an already-infected peer.
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let low_count_id = deploy.gossip_id();
+    let _ = gossiper
+        .table
+        .new_complete_data(&low_count_id, None, deploy.gossip_target());
+    let peer = NodeId::random(&mut rng);
+    let effects = gossiper.handle_gossip_response(effect_builder, low_count_id.clone(), true, peer);
+    assert_eq!(effects.len(), 1);
+    assert_eq!(gossiper.table.holder_count(&low_count_id), Some(1));
+
+    // An item reaching the threshold records the responding peer as a holder but isn't gossiped
+    // onwards, and remains current.
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let high_count_id = deploy.gossip_id();
+    for _ in 1..CASCADE_THRESHOLD {
+        let holder = NodeId::random(&mut rng);
+        let _ =
+            gossiper
+                .table
+                .new_complete_data(&high_count_id, Some(holder), deploy.gossip_target());
+    }
+    let peer = NodeId::random(&mut rng);
+    let effects =
+        gossiper.handle_gossip_response(effect_builder, high_count_id.clone(), true, peer);
+    assert!(effects.is_empty());
+    assert!(gossiper.table.is_current(&high_count_id));
+    assert!(gossiper.table.is_holder(&high_count_id, &peer));
+    assert_eq!(
+        gossiper.table.holder_count(&high_count_id),
+        Some(CASCADE_THRESHOLD as usize)
+    );
+}
+
+#[test]
+fn should_tag_events_for_one_item_with_same_op_id() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+
+    let sender = NodeId::random(&mut rng);
+    let peer = NodeId::random(&mut rng);
+    let incoming = |sender: NodeId, message: Message<Deploy>| {
+        super::Event::Incoming(GossiperIncoming {
+            sender,
+            message: Box::new(message),
+        })
+    };
+    let mut op_ids = vec![];
+    for _ in 0..2 {
+        let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+        let item_id = deploy.gossip_id();
+        // The entry is created, with only the ID held, on handling the is-stored result.
+        let events = vec![
+            incoming(sender, Message::Gossip(item_id.clone())),
+            super::Event::IsStoredResult {
+                item_id: item_id.clone(),
+                sender,
+                result: false,
+            },
+            incoming(sender, Message::Item(deploy.clone())),
+            super::Event::ItemReceived {
+                item_id: item_id.clone(),
+                source: Source::Peer(sender),
+                target: deploy.gossip_target(),
+            },
+            super::Event::GossipedTo {
+                item_id: item_id.clone(),
+                requested_count: 1,
+                peers: iter::once(peer).collect(),
+            },
+            incoming(
+                peer,
+                Message::GossipResponse {
+                    item_id: item_id.clone(),
+                    is_already_held: false,
+                },
+            ),
+        ];
+        let mut item_op_ids = vec![];
+        for event in events {
+            item_op_ids.push(gossiper.op_id_for(&event));
+            let _ = gossiper.handle_event(effect_builder, &mut rng, event);
+        }
+
+        // Every event after the entry's creation carries the same op ID, including across the item
+        // becoming complete.
+        assert_eq!(item_op_ids[..2], [None, None]);
+        let op_id = gossiper.table.op_id(&item_id).unwrap();
+        assert!(item_op_ids[2..].iter().all(|tagged| *tagged == Some(op_id)));
+        op_ids.push(op_id);
+    }
+    // Each item has its own op ID.
+    assert_ne!(op_ids[0], op_ids[1]);
+}
+
+/// A secondary transport which records the messages it is asked to announce.
+#[derive(Debug, Default)]
+struct MockTransport {
+    announced: Mutex<Vec<Message<Deploy>>>,
+}
+
+impl GossipTransport<Deploy> for MockTransport {
+    fn name(&self) -> &str {
+        "mock"
+    }
+
+    fn announce(
+        &self,
+        message: Message<Deploy>,
+        _gossip_target: GossipTarget,
+        _count: usize,
+        _exclude_peers: HashSet<NodeId>,
+    ) -> BoxFuture<'static, Option<HashSet<NodeId>>> {
+        self.announced.lock().unwrap().push(message);
+        async { Some(HashSet::new()) }.boxed()
+    }
+}
+
+#[test]
+fn should_announce_gossip_on_all_transports() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let transports = vec![
+        Arc::new(MockTransport::default()),
+        Arc::new(MockTransport::default()),
+    ];
+    let mut gossiper = Gossiper::<{ Deploy::ID_IS_COMPLETE_ITEM }, Deploy>::new_with_transports(
+        "test_deploy_gossiper",
+        Config::default(),
+        &Registry::new(),
+        transports
+            .iter()
+            .map(|transport| Arc::clone(transport) as Arc<dyn GossipTransport<Deploy>>)
+            .collect(),
+    )
+    .unwrap();
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let effects = gossiper.handle_item_received(
+        effect_builder,
+        item_id.clone(),
+        Source::Client,
+        deploy.gossip_target(),
+    );
+    // One effect per secondary transport, plus one for the primary gossip network.
+    assert_eq!(effects.len(), transports.len() + 1);
+
+    for transport in &transports {
+        let announced = transport.announced.lock().unwrap();
+        assert_eq!(announced.len(), 1);
+        assert!(matches!(&announced[0], Message::Gossip(id) if *id == item_id));
+    }
+}
+
+#[test]
+fn should_defer_non_urgent_gossip_during_quiet_hours() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let is_quiet = Arc::new(AtomicBool::new(true));
+    let quiet_hours = |is_quiet: &Arc<AtomicBool>| -> QuietHours {
+        let is_quiet = Arc::clone(is_quiet);
+        Box::new(move || {
+            is_quiet
+                .load(Ordering::SeqCst)
+                .then(|| Duration::from_secs(60))
+        })
+    };
+
+    // Gossip of a normal priority item should be queued during the quiet window.
+    let mut deploy_gossiper = new_deploy_gossiper(Config::default());
+    deploy_gossiper.set_quiet_hours(quiet_hours(&is_quiet));
+    assert_eq!(Deploy::PRIORITY, Priority::Normal);
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = deploy_gossiper.handle_item_received(
+        effect_builder,
+        deploy.gossip_id(),
+        Source::Client,
+        deploy.gossip_target(),
+    );
+    assert_eq!(deploy_gossiper.quiet_queue.len(), 1);
+
+    // Further gossip of the same item should be merged into the queued entry.
+    let peer = NodeId::random(&mut rng);
+    let queued_count = deploy_gossiper.quiet_queue[0].2;
+    let effects = deploy_gossiper.gossip(
+        effect_builder,
+        deploy.gossip_id(),
+        deploy.gossip_target(),
+        1,
+        iter::once(peer).collect(),
+    );
+    assert!(effects.is_empty());
+    assert_eq!(deploy_gossiper.quiet_queue.len(), 1);
+    assert_eq!(deploy_gossiper.quiet_queue[0].2, queued_count + 1);
+    assert!(deploy_gossiper.quiet_queue[0].3.contains(&peer));
+
+    // Gossip of a high priority item should not be.
+    let mut signature_gossiper =
+        Gossiper::<{ FinalitySignature::ID_IS_COMPLETE_ITEM }, FinalitySignature>::new(
+            "test_finality_signature_gossiper",
+            Config::default(),
+            &Registry::new(),
+        )
+        .unwrap();
+    signature_gossiper.set_quiet_hours(quiet_hours(&is_quiet));
+    assert_eq!(FinalitySignature::PRIORITY, Priority::High);
+    let signature = FinalitySignature::random_for_block(BlockHash::random(&mut rng), 1);
+    let effects = signature_gossiper.handle_item_received(
+        effect_builder,
+        signature.gossip_id(),
+        Source::Ourself,
+        signature.gossip_target(),
+    );
+    assert!(signature_gossiper.quiet_queue.is_empty());
+    assert!(!effects.is_empty());
+
+    // The queue should be held if the window is still under way when the timer fires.
+    let effects =
+        deploy_gossiper.handle_event(effect_builder, &mut rng, super::Event::QuietHoursEnded);
+    assert_eq!(effects.len(), 1);
+    assert_eq!(deploy_gossiper.quiet_queue.len(), 1);
+
+    // Once the window ends, the queued gossip should be sent.
+    is_quiet.store(false, Ordering::SeqCst);
+    let effects =
+        deploy_gossiper.handle_event(effect_builder, &mut rng, super::Event::QuietHoursEnded);
+    assert!(!effects.is_empty());
+    assert!(deploy_gossiper.quiet_queue.is_empty());
+}
+
+#[test]
+fn should_defer_rapid_regossip_of_same_item() {
+    const INTERVAL: Duration = Duration::from_secs(5);
+
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        min_regossip_interval: INTERVAL.into(),
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+
+    // Trigger gossip of the same item three times in quick succession.
+    let mut scheduled = vec![];
+    for _ in 0..3 {
+        let effects = gossiper.gossip(
+            effect_builder,
+            item_id.clone(),
+            deploy.gossip_target(),
+            1,
+            HashSet::new(),
+        );
+        assert_eq!(effects.len(), 1);
+        scheduled.push(gossiper.last_gossiped[&item_id]);
+    }
+
+    // Each gossip should be scheduled at least the minimum interval after the previous one.
+    for pair in scheduled.windows(2) {
+        assert!(pair[1] >= pair[0] + INTERVAL);
+    }
+
+    // Other items are unaffected.
+    let other_deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper.gossip(
+        effect_builder,
+        other_deploy.gossip_id(),
+        other_deploy.gossip_target(),
+        1,
+        HashSet::new(),
+    );
+    assert!(gossiper.last_gossiped[&other_deploy.gossip_id()] < scheduled[1]);
+}
+
+#[tokio::test(start_paused = true)]
+async fn should_delay_sends_to_selected_peers_until_regossip_interval_elapses() {
+    const INTERVAL: Duration = Duration::from_secs(5);
+
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        min_regossip_interval: INTERVAL.into(),
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let peer = NodeId::random(&mut rng);
+    gossiper.set_peer_selector(Box::new(move |_count, _exclude_peers| {
+        iter::once(peer).collect()
+    }));
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+
+    // The first gossip is sent straight away.
+    for effect in gossiper.gossip(
+        effect_builder,
+        item_id.clone(),
+        deploy.gossip_target(),
+        1,
+        HashSet::new(),
+    ) {
+        tokio::spawn(effect);
+    }
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    assert!(matches!(
+        event,
+        Event::NetworkRequest(NetworkRequest::SendMessage { dest, .. }) if *dest == peer
+    ));
+
+    // An immediate regossip isn't sent until the interval has elapsed.
+    for effect in gossiper.gossip(
+        effect_builder,
+        item_id,
+        deploy.gossip_target(),
+        1,
+        HashSet::new(),
+    ) {
+        tokio::spawn(effect);
+    }
+    tokio::time::sleep(INTERVAL / 2).await;
+    assert_eq!(scheduler.item_count(), 0);
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    assert!(matches!(
+        event,
+        Event::NetworkRequest(NetworkRequest::SendMessage { dest, .. }) if *dest == peer
+    ));
+}
+
+#[test]
+fn should_publish_health_to_registry() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let gossiper_registry = GossiperRegistry::default();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    gossiper.register_with(&gossiper_registry);
+    assert_eq!(
+        gossiper_registry.active_types(),
+        vec!["test_deploy_gossiper"]
+    );
+    assert_eq!(
+        gossiper_registry
+            .health_of("test_deploy_gossiper")
+            .unwrap()
+            .active_item_count,
+        0
+    );
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::ItemReceived {
+            item_id: deploy.gossip_id(),
+            source: Source::Client,
+            target: deploy.gossip_target(),
+        },
+    );
+    assert_eq!(
+        gossiper_registry
+            .health_of("test_deploy_gossiper")
+            .unwrap()
+            .active_item_count,
+        1
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn should_time_out_gossip_to_peers_chosen_by_selector() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+
+    let chosen_peers: HashSet<NodeId> = iter::repeat_with(|| NodeId::random(&mut rng))
+        .take(2)
+        .collect();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let selected_peers = chosen_peers.clone();
+    gossiper.set_peer_selector(Box::new(move |_count, _exclude_peers| {
+        selected_peers.clone()
+    }));
+
+    // Runs the given effects in the background, forwarding any resulting gossiper events.
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let run_effects = |effects: Effects<super::Event<Deploy>>| {
+        for effect in effects {
+            let event_sender = event_sender.clone();
+            tokio::spawn(async move {
+                for event in effect.await {
+                    let _ = event_sender.send(event);
+                }
+            });
+        }
+    };
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    run_effects(gossiper.handle_item_received(
+        effect_builder,
+        item_id.clone(),
+        Source::Client,
+        deploy.gossip_target(),
+    ));
+
+    // The gossip message should be sent to exactly the chosen peers.
+    let mut sent_to = HashSet::new();
+    while sent_to.len() < chosen_peers.len() {
+        let ((_ancestor, event), _) = scheduler.pop().await;
+        match event {
+            Event::NetworkRequest(NetworkRequest::SendMessage { dest, .. }) => {
+                assert!(sent_to.insert(*dest));
+            }
+            other => panic!("unexpected event: {}", other),
+        }
+    }
+    assert_eq!(sent_to, chosen_peers);
+
+    // Each chosen peer should then be checked for a response once the gossip timeout elapses.
+    let mut timed_out_peers = HashSet::new();
+    while timed_out_peers.len() < chosen_peers.len() {
+        match event_receiver.recv().await.unwrap() {
+            event @ super::Event::GossipedTo { .. } => {
+                run_effects(gossiper.handle_event(effect_builder, &mut rng, event));
+            }
+            super::Event::CheckGossipTimeout {
+                item_id: timed_out_item_id,
+                peer,
+            } => {
+                assert_eq!(timed_out_item_id, item_id);
+                assert!(timed_out_peers.insert(peer));
+            }
+            other => panic!("unexpected event: {}", other),
+        }
+    }
+    assert_eq!(timed_out_peers, chosen_peers);
+}
+
+#[tokio::test]
+async fn should_not_send_inconsistent_item_from_storage() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let mut gossiper = new_deploy_gossiper(Config::default());
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let _ = gossiper.handle_item_received(
+        effect_builder,
+        item_id.clone(),
+        Source::Client,
+        deploy.gossip_target(),
+    );
+
+    // Storage returns an item which isn't the one requested.
+    let other_deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let effects = gossiper.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::GetFromStorageResult {
+            item_id: item_id.clone(),
+            requester: NodeId::random(&mut rng),
+            maybe_item: Some(Box::new(other_deploy)),
+        },
+    );
+    for effect in effects {
+        let _ = effect.await;
+    }
+
+    // The item should be finished with rather than sent to the requester.
+    assert_eq!(scheduler.item_count(), 1);
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    match event {
+        Event::DeployGossiperAnnouncement(GossiperAnnouncement::FinishedGossiping(
+            finished_item_id,
+        )) => assert_eq!(finished_item_id, item_id),
+        other => panic!("unexpected event: {}", other),
+    }
+
+    // The requested item itself is consistent.
+    assert!(check_item_consistency(&item_id, &deploy).is_ok());
+}
+
+#[tokio::test]
+async fn should_report_convergence_time_of_saturated_item() {
+    const CONVERGENCE_TIME: Duration = Duration::from_secs(7);
+
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+
+    // Runs the given effects in the background, forwarding any resulting gossiper events.
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let run_effects = |effects: Effects<super::Event<Deploy>>| {
+        for effect in effects {
+            let event_sender = event_sender.clone();
+            tokio::spawn(async move {
+                for event in effect.await {
+                    let _ = event_sender.send(event);
+                }
+            });
+        }
+    };
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let _ = gossiper.handle_item_received(
+        effect_builder,
+        item_id.clone(),
+        Source::Client,
+        deploy.gossip_target(),
+    );
+    fake_instant::FakeClock::advance_time(CONVERGENCE_TIME.as_millis() as u64);
+
+    // Newly infect enough peers to saturate the item.
+    let infection_target = usize::from(Config::default().infection_target());
+    for _ in 0..infection_target {
+        assert!(gossiper.table.take_convergence_time(&item_id).is_none());
+        run_effects(gossiper.handle_gossip_response(
+            effect_builder,
+            item_id.clone(),
+            false,
+            NodeId::random(&mut rng),
+        ));
+    }
+
+    loop {
+        if let super::Event::ItemConverged {
+            item_id: converged_item_id,
+            elapsed,
+        } = event_receiver.recv().await.unwrap()
+        {
+            assert_eq!(converged_item_id, item_id);
+            assert!(elapsed >= CONVERGENCE_TIME);
+            break;
+        }
+    }
+
+    // The convergence time is only reported once.
+    assert!(gossiper.table.take_convergence_time(&item_id).is_none());
+}
+
+#[tokio::test(start_paused = true)]
+async fn should_share_storage_permits_fairly_between_gossipers() {
+    const BUDGET: usize = 2;
+    const ACCESSES_PER_GOSSIPER: usize = 100;
+    const WINDOW: usize = 10;
+
+    let storage_permits = Arc::new(Semaphore::new(BUDGET));
+    let mut deploy_gossiper = new_deploy_gossiper(Config::default());
+    deploy_gossiper.set_storage_permits(Arc::clone(&storage_permits));
+    let mut finality_signature_gossiper =
+        Gossiper::<{ FinalitySignature::ID_IS_COMPLETE_ITEM }, FinalitySignature>::new(
+            "test_finality_signature_gossiper",
+            Config::default(),
+            &Registry::new(),
+        )
+        .unwrap();
+    finality_signature_gossiper.set_storage_permits(Arc::clone(&storage_permits));
+
+    // Simulates a storage access, recording which gossiper made it and checking the budget holds.
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(Mutex::new(vec![]));
+    let storage_access = |gossiper_name: &'static str| {
+        let in_flight = Arc::clone(&in_flight);
+        let completed = Arc::clone(&completed);
+        async move {
+            assert!(in_flight.fetch_add(1, Ordering::SeqCst) < BUDGET);
+            time::sleep(Duration::from_millis(10)).await;
+            let _ = in_flight.fetch_sub(1, Ordering::SeqCst);
+            completed.lock().unwrap().push(gossiper_name);
+        }
+    };
+
+    // Both gossipers contend for storage throughout.
+    let mut tasks = vec![];
+    for _ in 0..ACCESSES_PER_GOSSIPER {
+        tasks.push(tokio::spawn(
+            deploy_gossiper.with_storage_permit(storage_access(deploy_gossiper.name)),
+        ));
+        tasks.push(tokio::spawn(
+            finality_signature_gossiper
+                .with_storage_permit(storage_access(finality_signature_gossiper.name)),
+        ));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    // Every access should complete, with neither gossiper going without storage access for long.
+    let completed = completed.lock().unwrap();
+    assert_eq!(completed.len(), 2 * ACCESSES_PER_GOSSIPER);
+    for window in completed.chunks(WINDOW) {
+        assert!(window.contains(&deploy_gossiper.name));
+        assert!(window.contains(&finality_signature_gossiper.name));
+    }
+}
+
+/// A large item which hints at a serialized size deliberately different from its actual size.
+#[derive(Clone, Debug, Display, Serialize, Deserialize, PartialEq, Eq)]
+#[display(fmt = "size-hinted item {}", _0)]
+struct SizeHintedItem(u64);
+
+impl SizeHintedItem {
+    const SIZE_HINT: usize = 1_000;
+}
+
+impl GossipItem for SizeHintedItem {
+    type Id = u64;
+    type StorageKey = u64;
+
+    const ID_IS_COMPLETE_ITEM: bool = false;
+    const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
+
+    fn gossip_id(&self) -> Self::Id {
+        self.0
+    }
+
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        *item_id
+    }
+
+    fn gossip_target(&self) -> GossipTarget {
+        GossipTarget::All
+    }
+
+    fn serialized_size_hint(&self) -> Option<usize> {
+        Some(Self::SIZE_HINT)
+    }
+}
+
+#[test]
+fn should_use_serialized_size_hint_or_cache_computed_size() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+
+    // The hint is used in place of serializing the item, and nothing is cached.
+    let mut hinted_gossiper = Gossiper::<false, SizeHintedItem>::new(
+        "test_hinted_gossiper",
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
+    let item = SizeHintedItem(rng.gen());
+    assert_ne!(
+        bincode::serialized_size(&item).unwrap() as usize,
+        SizeHintedItem::SIZE_HINT
+    );
+    assert_eq!(
+        hinted_gossiper.item_serialized_size(&item),
+        SizeHintedItem::SIZE_HINT
+    );
+    assert!(hinted_gossiper.serialized_sizes.is_empty());
+
+    // Without a hint, the item is serialized once and the size cached while it's being gossiped.
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let deploy_size = bincode::serialized_size(&deploy).unwrap() as usize;
+    let _ = gossiper.handle_item_received(
+        effect_builder,
+        deploy.gossip_id(),
+        Source::Client,
+        deploy.gossip_target(),
+    );
+    assert_eq!(gossiper.item_serialized_size(&deploy), deploy_size);
+    assert_eq!(
+        gossiper.serialized_sizes.get(&deploy.gossip_id()),
+        Some(&deploy_size)
+    );
+    assert_eq!(gossiper.item_serialized_size(&deploy), deploy_size);
+
+    // Once the item is no longer being gossiped, its cached size is dropped.
+    assert!(gossiper.table.force_finish(&deploy.gossip_id()));
+    let other_deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper.handle_item_received(
+        effect_builder,
+        other_deploy.gossip_id(),
+        Source::Client,
+        other_deploy.gossip_target(),
+    );
+    let _ = gossiper.item_serialized_size(&other_deploy);
+    assert_eq!(gossiper.serialized_sizes.len(), 1);
+    assert!(gossiper
+        .serialized_sizes
+        .contains_key(&other_deploy.gossip_id()));
+}
+
+#[test]
+fn should_refresh_gauges_on_demand_without_side_effects() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper.handle_item_received(
+        effect_builder,
+        deploy.gossip_id(),
+        Source::Client,
+        deploy.gossip_target(),
+    );
+
+    // The gauges are stale as the item was added without going through `handle_event`.
+    assert_eq!(gossiper.metrics.table_items_current.get(), 0);
+    assert_eq!(gossiper.metrics.table_items_tracked.get(), 0);
+    let summaries = gossiper.table.summaries();
+
+    let effects = gossiper.handle_event(effect_builder, &mut rng, super::Event::RefreshMetrics);
+    assert!(effects.is_empty());
+    assert_eq!(gossiper.metrics.table_items_current.get(), 1);
+    assert_eq!(gossiper.metrics.table_items_finished.get(), 0);
+    assert_eq!(gossiper.metrics.table_items_tracked.get(), 1);
+    assert_eq!(gossiper.table.summaries(), summaries);
+}
+
+#[test]
+fn should_include_tracked_peers_and_requests_in_heap_size_estimate() {
+    let mut rng = crate::new_rng();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let initial_heap_size = gossiper.estimate_heap_size();
+
+    let peer = NodeId::random(&mut rng);
+    gossiper.rejected_peers.record(peer);
+    let with_rejected_peer = gossiper.estimate_heap_size();
+    assert!(with_rejected_peer > initial_heap_size);
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let _ = gossiper
+        .outstanding_gets
+        .entry(peer)
+        .or_default()
+        .insert(deploy.gossip_id());
+    assert!(gossiper.estimate_heap_size() > with_rejected_peer);
+}
+
+#[test]
+fn should_only_purge_expired_state_during_housekeeping() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        quarantine_duration: TimeDiff::from_seconds(300),
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    gossiper.quarantine.insert(deploy.gossip_id());
+
+    let cooldown = Duration::from(config.quarantine_duration());
+    fake_instant::FakeClock::advance_time(cooldown.as_millis() as u64);
+    let effects = gossiper.handle_event(effect_builder, &mut rng, super::Event::RefreshMetrics);
+    assert!(effects.is_empty());
+    assert_eq!(gossiper.quarantine.len(), 1);
+
+    let effects = gossiper.handle_event(effect_builder, &mut rng, super::Event::Housekeeping);
+    assert_eq!(effects.len(), 1);
+    assert_eq!(gossiper.quarantine.len(), 0);
+}
+
+/// Has the gossiper holding `deploy` handle it being retrieved from storage for the given range
+/// request, returning the bytes sent in response.
+async fn get_range_response(
+    deploy: &Deploy,
+    offset: u64,
+    len: u32,
+    rng: &mut TestRng,
+) -> Option<Vec<u8>> {
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let mut gossiper = new_deploy_gossiper(Config::default());
+
+    let item_id = deploy.gossip_id();
+    let _ = gossiper.handle_item_received(
+        effect_builder,
+        item_id.clone(),
+        Source::Client,
+        deploy.gossip_target(),
+    );
+
+    let requester = NodeId::random(rng);
+    let effects = gossiper.handle_event(
+        effect_builder,
+        rng,
+        super::Event::GetRangeFromStorageResult {
+            item_id: item_id.clone(),
+            requester,
+            offset,
+            len,
+            maybe_item: Some(Box::new(deploy.clone())),
+        },
+    );
+    for effect in effects {
+        tokio::spawn(effect);
+    }
+
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    match event {
+        Event::NetworkRequest(NetworkRequest::SendMessage { dest, payload, .. }) => {
+            assert_eq!(*dest, requester);
+            match *payload {
+                NodeMessage::DeployGossiper(Message::GetRangeResponse {
+                    item_id: response_item_id,
+                    offset: response_offset,
+                    maybe_bytes,
+                }) => {
+                    assert_eq!(response_item_id, item_id);
+                    assert_eq!(response_offset, offset);
+                    maybe_bytes
+                }
+                other => panic!("unexpected message: {}", other),
+            }
+        }
+        other => panic!("unexpected event: {}", other),
+    }
+}
+
+#[tokio::test]
+async fn should_send_in_bounds_range_of_item() {
+    let mut rng = crate::new_rng();
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let serialized_deploy = bincode::serialize(&deploy).unwrap();
+    let item_length = serialized_deploy.len();
+
+    let bytes = get_range_response(&deploy, 10, 20, &mut rng).await;
+    assert_eq!(bytes.as_deref(), Some(&serialized_deploy[10..30]));
+
+    // The final bytes, and the whole item, are also in bounds.
+    let bytes = get_range_response(&deploy, item_length as u64 - 5, 5, &mut rng).await;
+    assert_eq!(
+        bytes.as_deref(),
+        Some(&serialized_deploy[item_length - 5..])
+    );
+    let bytes = get_range_response(&deploy, 0, item_length as u32, &mut rng).await;
+    assert_eq!(bytes, Some(serialized_deploy));
+}
+
+#[tokio::test]
+async fn should_reject_out_of_bounds_range_of_item() {
+    let mut rng = crate::new_rng();
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_length = bincode::serialized_size(&deploy).unwrap();
+
+    // Ranges extending past the end of the item get an empty response.
+    assert!(get_range_response(&deploy, item_length - 5, 10, &mut rng)
+        .await
+        .is_none());
+    assert!(get_range_response(&deploy, item_length + 1, 0, &mut rng)
+        .await
+        .is_none());
+    assert!(get_range_response(&deploy, u64::MAX, 1, &mut rng)
+        .await
+        .is_none());
+
+    let serialized_item = [0_u8; 8];
+    assert_eq!(
+        item_range(&serialized_item, u64::MAX, 1),
+        Err(RangeError::OutOfBounds {
+            offset: u64::MAX,
+            len: 1,
+            item_length: 8
+        })
+    );
+    assert_eq!(
+        item_range(&serialized_item, 0, MAX_GET_RANGE_LENGTH + 1),
+        Err(RangeError::TooLong {
+            len: MAX_GET_RANGE_LENGTH + 1
+        })
+    );
+}
+
+#[tokio::test]
+async fn should_queue_get_responses_beyond_inflight_byte_limit() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let size = bincode::serialized_size(&deploy).unwrap() as usize;
+    let config = Config {
+        max_inflight_get_response_bytes: 2 * size as u32,
+        ..Default::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+
+    // Many peers request the item at once, but only two responses fit within the limit.
+    let requesters: Vec<NodeId> = iter::repeat_with(|| NodeId::random(&mut rng))
+        .take(5)
+        .collect();
+    let mut in_flight = HashMap::new();
+    for requester in &requesters {
+        let effects = gossiper.got_from_storage(
+            effect_builder,
+            item_id.clone(),
+            Box::new(deploy.clone()),
+            *requester,
+        );
+        if !effects.is_empty() {
+            let handles: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
+            let _ = in_flight.insert(*requester, handles);
+        }
+    }
+    assert_eq!(in_flight.len(), 2);
+    assert_eq!(gossiper.inflight_get_response_bytes, 2 * size);
+    assert_eq!(gossiper.queued_get_responses.len(), 3);
+
+    // As each send completes, the next queued response is released in turn.
+    let mut next_released = 2;
+    let mut sent_to = vec![];
+    while sent_to.len() < requesters.len() {
+        let ((_ancestor, event), _) = scheduler.pop().await;
+        let dest = match event {
+            Event::NetworkRequest(NetworkRequest::SendMessage {
+                dest,
+                payload,
+                auto_closing_responder,
+                ..
+            }) => {
+                assert!(matches!(
+                    *payload,
+                    NodeMessage::DeployGossiper(Message::Item(_))
+                ));
+                auto_closing_responder.respond(()).await;
+                *dest
+            }
+            other => panic!("unexpected event: {}", other),
+        };
+        sent_to.push(dest);
+
+        for handle in in_flight.remove(&dest).unwrap() {
+            for event in handle.await.unwrap() {
+                assert!(matches!(event, super::Event::GetResponseSent { .. }));
+                let effects = gossiper.handle_event(effect_builder, &mut rng, event);
+                if !effects.is_empty() {
+                    let handles: Vec<_> = effects.into_iter().map(tokio::spawn).collect();
+                    let _ = in_flight.insert(requesters[next_released], handles);
+                    next_released += 1;
+                }
+            }
+        }
+        assert!(gossiper.inflight_get_response_bytes <= 2 * size);
+    }
+
+    // Every response was eventually sent, and nothing remains in flight or queued.
+    assert_eq!(next_released, requesters.len());
+    assert_eq!(
+        sent_to.into_iter().collect::<HashSet<_>>(),
+        requesters.into_iter().collect::<HashSet<_>>()
+    );
+    assert_eq!(gossiper.inflight_get_response_bytes, 0);
+    assert!(gossiper.queued_get_responses.is_empty());
+}
+
+#[tokio::test]
+async fn should_respond_not_available_yet_for_item_still_being_acquired() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        acquiring_item_get_policy: AcquiringItemGetPolicy::Defer,
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+
+    // We know of the item via gossip, but are still getting the remainder from the holder.
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let holder = NodeId::random(&mut rng);
+    let _ = gossiper.table.new_data_id(&item_id, holder);
+
+    let requester = NodeId::random(&mut rng);
+    let get_item = |item_id: <Deploy as GossipItem>::Id| {
+        super::Event::Incoming(GossiperIncoming {
+            sender: requester,
+            message: Box::new(Message::GetItem(item_id)),
+        })
+    };
+    let effects = gossiper.handle_event(effect_builder, &mut rng, get_item(item_id.clone()));
+    for effect in effects {
+        let _ = effect.await;
+    }
+
+    // The requester should be told to retry later, and the entry left untouched.
+    assert_eq!(scheduler.item_count(), 1);
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    match event {
+        Event::NetworkRequest(NetworkRequest::SendMessage { dest, payload, .. }) => {
+            assert_eq!(*dest, requester);
+            assert!(matches!(
+                *payload,
+                NodeMessage::DeployGossiper(Message::NotAvailableYet { item_id: ref id })
+                    if *id == item_id
+            ));
+        }
+        other => panic!("unexpected event: {}", other),
+    }
+    assert!(gossiper.table.is_acquiring(&item_id));
+
+    // Under the default `ignore` policy, the request should be dropped, as older peers can't
+    // decode a `NotAvailableYet` message.
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let _ = gossiper.table.new_data_id(&item_id, holder);
+    let effects = gossiper.handle_event(effect_builder, &mut rng, get_item(item_id.clone()));
+    assert!(effects.is_empty());
+    assert!(gossiper.table.is_acquiring(&item_id));
+}
+
+/// A large item which can veto being gossiped.
+#[derive(Clone, Debug, Display, Serialize, Deserialize, PartialEq, Eq)]
+#[display(fmt = "vetoable item {}", id)]
+struct VetoableItem {
+    id: u64,
+    should_gossip: bool,
+}
+
+impl GossipItem for VetoableItem {
+    type Id = u64;
+    type StorageKey = u64;
+
+    const ID_IS_COMPLETE_ITEM: bool = false;
+    const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
+
+    fn gossip_id(&self) -> Self::Id {
+        self.id
+    }
+
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        *item_id
+    }
+
+    fn gossip_target(&self) -> GossipTarget {
+        GossipTarget::All
+    }
+
+    fn should_gossip(&self) -> bool {
+        self.should_gossip
+    }
+}
+
+#[test]
+fn should_not_gossip_item_which_vetoes_gossip() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = Gossiper::<false, VetoableItem>::new(
+        "test_vetoable_gossiper",
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
+
+    // The stored item vetoes being gossiped, so no gossip table entry should be created for it.
+    let local_item = VetoableItem {
+        id: rng.gen(),
+        should_gossip: false,
+    };
+    let effects = gossiper.handle_put_verification_result(
+        effect_builder,
+        local_item.id,
+        Source::Client,
+        local_item.gossip_target(),
+        Some(Box::new(local_item.clone())),
+    );
+    assert!(effects.is_empty());
+    assert!(!gossiper.table.has_entry(&local_item.id));
+
+    // An otherwise identical item which doesn't veto being gossiped should be gossiped.
+    let item = VetoableItem {
+        id: rng.gen(),
+        should_gossip: true,
+    };
+    let effects = gossiper.handle_put_verification_result(
+        effect_builder,
+        item.id,
+        Source::Client,
+        item.gossip_target(),
+        Some(Box::new(item.clone())),
+    );
+    assert!(!effects.is_empty());
+    assert!(gossiper.table.is_current(&item.id));
+}
+
+/// Has a gossiper with the given collision policy handle an item received from `sender` which
+/// collides with the one it already holds, returning the resulting effects and the gossiper.
+fn resolve_collision(
+    policy: IdCollisionPolicy,
+    stored_item: &VetoableItem,
+    received_item: &VetoableItem,
+    sender: NodeId,
+) -> (
+    Effects<super::Event<VetoableItem>>,
+    Gossiper<false, VetoableItem>,
+) {
+    let config = Config {
+        id_collision_policy: policy,
+        ..Config::default()
+    };
+    let mut gossiper =
+        Gossiper::<false, VetoableItem>::new("test_collision_gossiper", config, &Registry::new())
+            .unwrap();
+    let _ = gossiper
+        .table
+        .new_complete_data(&stored_item.id, None, stored_item.gossip_target());
+    let effects = gossiper.handle_collision_check_result(
+        new_effect_builder(),
+        Box::new(received_item.clone()),
+        sender,
+        Some(Box::new(stored_item.clone())),
+    );
+    (effects, gossiper)
+}
+
+#[test]
+fn should_resolve_id_collision_according_to_policy() {
+    let mut rng = crate::new_rng();
+    let sender = NodeId::random(&mut rng);
+    let stored_item = VetoableItem {
+        id: rng.gen(),
+        should_gossip: true,
+    };
+    let colliding_item = VetoableItem {
+        id: stored_item.id,
+        should_gossip: false,
+    };
+
+    // Keeping the existing item drops the received one without penalizing the sender.
+    let (effects, gossiper) = resolve_collision(
+        IdCollisionPolicy::KeepExisting,
+        &stored_item,
+        &colliding_item,
+        sender,
+    );
+    assert!(effects.is_empty());
+    assert!(gossiper.in_flight_puts.is_empty());
+    assert_eq!(gossiper.rejected_peers.rejection_count(&sender), 0);
+    assert!(gossiper.held_content_hashes.contains_key(&stored_item.id));
+
+    // Rejecting the new item also records the sender as rejected.
+    let (effects, gossiper) = resolve_collision(
+        IdCollisionPolicy::RejectNew,
+        &stored_item,
+        &colliding_item,
+        sender,
+    );
+    assert!(effects.is_empty());
+    assert!(gossiper.in_flight_puts.is_empty());
+    assert_eq!(gossiper.rejected_peers.rejection_count(&sender), 1);
+
+    // Replacing the existing item puts the received one to storage.
+    let (effects, gossiper) = resolve_collision(
+        IdCollisionPolicy::ReplaceWithNew,
+        &stored_item,
+        &colliding_item,
+        sender,
+    );
+    assert!(!effects.is_empty());
+    assert!(gossiper.in_flight_puts.contains_key(&stored_item.id));
+    assert_eq!(gossiper.rejected_peers.rejection_count(&sender), 0);
+    assert!(!gossiper.held_content_hashes.contains_key(&stored_item.id));
+
+    // An identical item isn't a collision, so is ignored under any policy.
+    for policy in [
+        IdCollisionPolicy::KeepExisting,
+        IdCollisionPolicy::RejectNew,
+        IdCollisionPolicy::ReplaceWithNew,
+    ] {
+        let (effects, gossiper) = resolve_collision(policy, &stored_item, &stored_item, sender);
+        assert!(effects.is_empty());
+        assert!(gossiper.in_flight_puts.is_empty());
+        assert_eq!(gossiper.rejected_peers.rejection_count(&sender), 0);
+    }
+}
+
+#[test]
+fn should_check_duplicates_of_held_item_against_storage_only_once() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let sender = NodeId::random(&mut rng);
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let _ = gossiper
+        .table
+        .new_complete_data(&deploy.gossip_id(), None, deploy.gossip_target());
+
+    // The first duplicate is compared against the item read from storage.
+    let effects = gossiper.handle_item_received_from_peer(effect_builder, deploy.clone(), sender);
+    assert_eq!(effects.len(), 1);
+    let effects = gossiper.handle_collision_check_result(
+        effect_builder,
+        deploy.clone(),
+        sender,
+        Some(deploy.clone()),
+    );
+    assert!(effects.is_empty());
+
+    // Later duplicates are compared against the cached content hash without reading storage.
+    let effects = gossiper.handle_item_received_from_peer(effect_builder, deploy.clone(), sender);
+    assert!(effects.is_empty());
+    assert_eq!(gossiper.held_content_hashes.len(), 1);
+    assert_eq!(gossiper.rejected_peers.rejection_count(&sender), 0);
+}
+
+#[tokio::test]
+async fn should_sync_storage_only_for_locally_submitted_items() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        sync_after_put: true,
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+
+    // Has the gossiper handle a newly-stored deploy received from the given source.
+    let mut item_received = |gossiper: &mut Gossiper<false, Deploy>, source: Source| {
+        let deploy = Deploy::random_valid_native_transfer(&mut rng);
+        let effects = gossiper.handle_event(
+            effect_builder,
+            &mut rng,
+            super::Event::ItemReceived {
+                item_id: deploy.gossip_id(),
+                source,
+                target: deploy.gossip_target(),
+            },
+        );
+        for effect in effects {
+            tokio::spawn(effect);
+        }
+    };
+
+    // A locally-submitted deploy should only be gossiped once storage has been synced.
+    item_received(&mut gossiper, Source::Client);
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    assert!(
+        matches!(
+            event,
+            Event::StorageRequest(StorageRequest::SyncToDisk { .. })
+        ),
+        "unexpected event: {}",
+        event
+    );
+    assert!(gossiper.table.is_empty());
+
+    // A deploy received from a peer should be gossiped without syncing.
+    let peer = NodeId::random(&mut crate::new_rng());
+    item_received(&mut gossiper, Source::PeerGossiped(peer));
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    assert!(
+        matches!(event, Event::NetworkRequest(NetworkRequest::Gossip { .. })),
+        "unexpected event: {}",
+        event
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn should_jitter_get_from_peer_timeouts() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        get_from_peer_jitter_percent: 50,
+        ..Config::default()
+    };
+    let get_from_peer_timeout: Duration = config.get_remainder_timeout().into();
+    let mut gossiper = new_deploy_gossiper(config);
+
+    // Get two different items from a holder, forwarding the resulting timeout events along with
+    // the time they fired.
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::unbounded_channel();
+    let holder = NodeId::random(&mut rng);
+    let start = time::Instant::now();
+    for _ in 0..2 {
+        let item_id = Deploy::random_valid_native_transfer(&mut rng).gossip_id();
+        for effect in gossiper.get_from_peer(effect_builder, item_id, holder) {
+            let event_sender = event_sender.clone();
+            tokio::spawn(async move {
+                for event in effect.await {
+                    let _ = event_sender.send((event, time::Instant::now()));
+                }
+            });
+        }
+    }
+
+    // Both timeouts should fire within the jittered range, but at different times.
+    let mut deadlines = vec![];
+    while deadlines.len() < 2 {
+        let (event, fired_at) = event_receiver.recv().await.unwrap();
+        assert!(matches!(
+            event,
+            super::Event::CheckGetFromPeerTimeout { .. }
+        ));
+        let elapsed = fired_at - start;
+        assert!(elapsed >= get_from_peer_timeout);
+        assert!(elapsed <= get_from_peer_timeout * 3 / 2);
+        deadlines.push(elapsed);
+    }
+    assert_ne!(deadlines[0], deadlines[1]);
+}
+
+#[test]
+fn should_replay_random_decisions_after_restoring_rng() {
+    const SEED: u64 = 0x5eed;
+
+    let config = Config {
+        get_from_peer_jitter_percent: 50,
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    gossiper.set_rng(ChaCha20Rng::seed_from_u64(SEED));
+    let snapshot = gossiper.rng_snapshot();
+    let jittered_timeouts = |gossiper: &mut Gossiper<false, Deploy>| {
+        iter::repeat_with(|| gossiper.jittered_get_from_peer_timeout())
+            .take(10)
+            .collect::<Vec<_>>()
+    };
+    let timeouts = jittered_timeouts(&mut gossiper);
+    assert!(timeouts.iter().any(|timeout| *timeout != timeouts[0]));
+
+    // Having moved on, the gossiper should make different decisions, until its rng is restored.
+    assert_ne!(jittered_timeouts(&mut gossiper), timeouts);
+    gossiper.set_rng(snapshot);
+    assert_eq!(jittered_timeouts(&mut gossiper), timeouts);
+
+    // A different gossiper seeded identically should make the same decisions.
+    let mut other_gossiper = new_deploy_gossiper(config);
+    other_gossiper.set_rng(ChaCha20Rng::seed_from_u64(SEED));
+    assert_eq!(jittered_timeouts(&mut other_gossiper), timeouts);
+}
+
+#[tokio::test]
+async fn should_get_from_preferred_holder_over_untested_peers() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        max_preferred_holders: 8,
+        ..Config::default()
+    };
+    let half_life: Duration = config.preferred_holder_half_life().into();
+    let mut gossiper = new_deploy_gossiper(config);
+
+    // A peer delivers an item we were getting from it, and so becomes preferred.
+    let reliable_peer = NodeId::random(&mut rng);
+    let deploy = Box::new(Deploy::random_valid_native_transfer(&mut rng));
+    let _ = gossiper
+        .table
+        .new_data_id(&deploy.gossip_id(), reliable_peer);
+    let _ = gossiper.handle_item_received_from_peer(effect_builder, deploy, reliable_peer);
+    assert_eq!(gossiper.preferred_holders.ranked(), vec![reliable_peer]);
+
+    // Whenever a holder fails to provide a further item, the preferred peer should be chosen to
+    // get it from over the item's other, untested holders.
+    for _ in 0..5 {
+        let item_id = Deploy::random_valid_native_transfer(&mut rng).gossip_id();
+        let failed_peer = NodeId::random(&mut rng);
+        let _ = gossiper.table.new_data_id(&item_id, failed_peer);
+        for _ in 0..5 {
+            let _ = gossiper
+                .table
+                .new_data_id(&item_id, NodeId::random(&mut rng));
+        }
+        let _ = gossiper.table.new_data_id(&item_id, reliable_peer);
+        let effects =
+            gossiper.check_get_from_peer_timeout(effect_builder, item_id.clone(), failed_peer);
+        let (dest, message) = single_sent_message(scheduler, effects).await;
+        assert_eq!(dest, reliable_peer);
+        assert!(matches!(message, Message::GetItem(ref requested) if *requested == item_id));
+    }
+
+    // The preference should decay away if the peer doesn't deliver further items.
+    fake_instant::FakeClock::advance_time(half_life.as_millis() as u64 + 1);
+    assert!(gossiper.preferred_holders.ranked().is_empty());
+}
+
+#[tokio::test]
+async fn should_ack_stored_get_response() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        ack_get_responses: true,
+        ..Config::default()
+    };
+
+    // The requester stores the item it was acquiring from the holder, and acknowledges it.
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let holder = NodeId::random(&mut rng);
+    let requester = NodeId::random(&mut rng);
+    let mut requesting_gossiper = new_deploy_gossiper(config);
+    let _ = requesting_gossiper.table.new_data_id(&item_id, holder);
+    let effects = requesting_gossiper.handle_item_received(
+        effect_builder,
+        item_id.clone(),
+        Source::PeerGossiped(holder),
+        deploy.gossip_target(),
+    );
+    for effect in effects {
+        tokio::spawn(effect);
+    }
+    let mut acked = false;
+    while !acked {
+        let ((_ancestor, event), _) = scheduler.pop().await;
+        if let Event::NetworkRequest(NetworkRequest::SendMessage { dest, payload, .. }) = event {
+            assert_eq!(*dest, holder);
+            match *payload {
+                NodeMessage::DeployGossiper(Message::GetResponseAck {
+                    item_id: acked_item_id,
+                }) => assert_eq!(acked_item_id, item_id),
+                other => panic!("unexpected message: {}", other),
+            }
+            acked = true;
+        }
+    }
+
+    // The holder tracks the response until it receives the ack, after which the timeout is a noop.
+    let mut holding_gossiper = new_deploy_gossiper(config);
+    let _ = holding_gossiper.got_from_storage(
+        effect_builder,
+        item_id.clone(),
+        Box::new(deploy.clone()),
+        requester,
+    );
+    assert!(holding_gossiper
+        .unacked_get_responses
+        .contains_key(&(item_id.clone(), requester)));
+    let ack = super::Event::Incoming(GossiperIncoming {
+        sender: requester,
+        message: Box::new(Message::GetResponseAck {
+            item_id: item_id.clone(),
+        }),
+    });
+    let effects = holding_gossiper.handle_event(effect_builder, &mut rng, ack);
+    assert!(effects.is_empty());
+    assert!(holding_gossiper.unacked_get_responses.is_empty());
+    let effects = holding_gossiper.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::CheckGetResponseAckTimeout { item_id, requester },
+    );
+    assert!(effects.is_empty());
+}
+
+#[tokio::test]
+async fn should_resend_unacked_get_response_after_timeout() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        ack_get_responses: true,
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let requester = NodeId::random(&mut rng);
+    let _ = gossiper.got_from_storage(
+        effect_builder,
+        item_id.clone(),
+        Box::new(deploy.clone()),
+        requester,
+    );
+
+    // Each timeout without an ack causes the item to be retrieved from storage and re-sent.
+    let key = (item_id.clone(), requester);
+    for resend in 1..=MAX_GET_RESPONSE_RESENDS {
+        let effects = gossiper.handle_event(
+            effect_builder,
+            &mut rng,
+            super::Event::CheckGetResponseAckTimeout {
+                item_id: item_id.clone(),
+                requester,
+            },
+        );
+        assert!(!effects.is_empty());
+        assert_eq!(gossiper.unacked_get_responses.get(&key), Some(&resend));
+
+        let effects = gossiper.handle_event(
+            effect_builder,
+            &mut rng,
+            super::Event::GetFromStorageResult {
+                item_id: item_id.clone(),
+                requester,
+                maybe_item: Some(Box::new(deploy.clone())),
+            },
+        );
+        assert!(!effects.is_empty());
+        assert_eq!(gossiper.unacked_get_responses.get(&key), Some(&resend));
+    }
+
+    // Once the re-sends are exhausted, the response is no longer tracked.
+    let effects = gossiper.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::CheckGetResponseAckTimeout { item_id, requester },
+    );
+    assert!(effects.is_empty());
+    assert!(gossiper.unacked_get_responses.is_empty());
+}
+
+#[tokio::test(start_paused = true)]
+async fn should_pause_gossip_of_item_exceeding_amplification_limit() {
+    const MAX_AMPLIFICATION: u32 = 4;
+
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        max_amplification: MAX_AMPLIFICATION,
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let _ = gossiper
+        .table
+        .new_complete_data(&item_id, None, deploy.gossip_target());
+
+    // Re-gossip the item to the same peer until the limit is exceeded.
+    let peer = NodeId::random(&mut rng);
+    let mut limit_hits = vec![];
+    for _ in 0..=MAX_AMPLIFICATION {
+        assert!(!gossiper.table.is_paused(&item_id));
+        let effects = gossiper.gossiped_to(
+            effect_builder,
+            item_id.clone(),
+            1,
+            iter::once(peer).collect(),
+        );
+        for effect in effects {
+            limit_hits.extend(
+                effect
+                    .await
+                    .into_iter()
+                    .filter(|event| matches!(event, super::Event::AmplificationLimitHit { .. })),
+            );
+        }
+    }
+    assert!(gossiper.table.is_paused(&item_id));
+    assert_eq!(limit_hits.len(), 1);
+    match limit_hits.pop().unwrap() {
+        super::Event::AmplificationLimitHit {
+            item_id: paused_item_id,
+            messages_sent,
+        } => {
+            assert_eq!(paused_item_id, item_id);
+            assert_eq!(messages_sent, MAX_AMPLIFICATION as usize + 1);
+        }
+        other => panic!("unexpected event: {}", other),
+    }
+
+    // Further gossip of the paused item reaches no peers.
+    let effects = gossiper.gossip(
+        effect_builder,
+        item_id.clone(),
+        deploy.gossip_target(),
+        1,
+        HashSet::new(),
+    );
+    assert_eq!(effects.len(), 1);
+    let events = effects.into_iter().next().unwrap().await;
+    assert!(matches!(
+        events.as_slice(),
+        [super::Event::GossipedTo { peers, .. }] if peers.is_empty()
+    ));
+    assert_eq!(
+        gossiper.table.messages_sent(&item_id),
+        Some(MAX_AMPLIFICATION as usize + 1)
+    );
+}
+
+#[tokio::test]
+async fn should_not_gossip_expired_item() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let deploy = Deploy::random_with_timestamp_and_ttl(
+        &mut rng,
+        Timestamp::zero(),
+        TimeDiff::from_seconds(1),
+    );
+    assert!(deploy.is_expired(Timestamp::now()));
+    let item_id = deploy.gossip_id();
+    let sender = NodeId::random(&mut rng);
+    let _ = gossiper.table.new_data_id(&item_id, sender);
+    assert!(gossiper.table.is_acquiring(&item_id));
+
+    // The expired deploy received from the peer is neither stored nor gossiped.
+    let effects = gossiper.handle_item_received_from_peer(effect_builder, Box::new(deploy), sender);
+    assert_eq!(effects.len(), 1);
+    let events = effects.into_iter().next().unwrap().await;
+    assert!(gossiper.in_flight_puts.is_empty());
+    let expired_item_id = match events.as_slice() {
+        [super::Event::ItemExpired { item_id }] => item_id.clone(),
+        other => panic!("unexpected events: {:?}", other),
+    };
+    assert_eq!(expired_item_id, item_id);
+
+    let effects = gossiper.handle_item_expired(effect_builder, expired_item_id);
+    assert_eq!(effects.len(), 1);
+    assert!(!gossiper.table.is_acquiring(&item_id));
+    assert!(gossiper.table.has_entry(&item_id));
+
+    // Even if the deploy is subsequently stored, it isn't gossiped onwards.
+    let effects = gossiper.handle_item_received(
+        effect_builder,
+        item_id.clone(),
+        Source::PeerGossiped(sender),
+        GossipTarget::All,
+    );
+    assert!(effects.is_empty());
+}
+
+#[test]
+fn should_observe_achieved_degree_of_distinct_acknowledging_peers() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let item_id = DeployId::random(&mut rng);
+    let sender = NodeId::random(&mut rng);
+    let peers: Vec<_> = iter::repeat_with(|| NodeId::random(&mut rng))
+        .take(3)
+        .collect();
+    assert_eq!(gossiper.table.achieved_degree(&item_id), None);
+
+    // The peer we received the item from is a holder, but hasn't acknowledged our gossip.
+    let _ = gossiper.table.new_data_id(&item_id, sender);
+    let _ = gossiper
+        .table
+        .new_complete_data(&item_id, Some(sender), GossipTarget::All);
+    assert_eq!(gossiper.table.achieved_degree(&item_id), Some(0));
+
+    // Acks count each peer once, whether or not it already held the item.
+    let _ = gossiper.table.we_infected(&item_id, peers[0]);
+    let _ = gossiper.table.already_infected(&item_id, peers[1]);
+    let _ = gossiper.table.already_infected(&item_id, peers[2]);
+    let _ = gossiper.table.already_infected(&item_id, peers[1]);
+    let _ = gossiper.table.we_infected(&item_id, peers[0]);
+    assert_eq!(gossiper.table.achieved_degree(&item_id), Some(3));
+
+    // Once finished, the achieved degree is retained and observed in the metrics.
+    let _ = gossiper.handle_item_expired(effect_builder, item_id);
+    assert!(!gossiper.table.is_current(&item_id));
+    assert_eq!(gossiper.table.achieved_degree(&item_id), Some(3));
+    assert_eq!(gossiper.metrics.achieved_degree.get_sample_count(), 1);
+    assert_eq!(gossiper.metrics.achieved_degree.get_sample_sum(), 3.0);
+}
+
+#[test]
+fn should_spread_gets_across_holders_when_peer_exceeds_budget() {
+    const BUDGET: usize = 20;
+    const ITEM_COUNT: u64 = 10;
+
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        max_outstanding_gets_per_peer: BUDGET as u32,
+        ..Config::default()
+    };
+    let mut gossiper =
+        Gossiper::<false, VetoableItem>::new("test_budget_gossiper", config, &Registry::new())
+            .unwrap();
+    let busy_peer = NodeId::random(&mut rng);
+    let idle_peer = NodeId::random(&mut rng);
+    let unresponsive_peer = NodeId::random(&mut rng);
+
+    // Use up the busy peer's budget with requests for other items.
+    for item_id in 0..BUDGET as u64 {
+        gossiper.record_outstanding_get(busy_peer, item_id);
+    }
+    assert!(gossiper.exceeds_get_budget(&busy_peer));
+
+    for item_id in BUDGET as u64..BUDGET as u64 + ITEM_COUNT {
+        let _ = gossiper.table.new_data_id(&item_id, unresponsive_peer);
+        let _ = gossiper.table.new_data_id(&item_id, busy_peer);
+        let _ = gossiper.table.new_data_id(&item_id, idle_peer);
+
+        // Whichever remaining holder the table chooses, the item should be requested from the
+        // peer with spare budget.
+        let effects =
+            gossiper.check_get_from_peer_timeout(effect_builder, item_id, unresponsive_peer);
+        assert_eq!(effects.len(), 2);
+        assert!(gossiper.outstanding_gets[&idle_peer].contains(&item_id));
+    }
+    assert_eq!(gossiper.outstanding_gets[&busy_peer].len(), BUDGET);
+    assert_eq!(
+        gossiper.outstanding_gets[&idle_peer].len(),
+        ITEM_COUNT as usize
+    );
+
+    // Without a budget, the busy peer can still be chosen.
+    let mut gossiper = Gossiper::<false, VetoableItem>::new(
+        "test_unlimited_gossiper",
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
+    for item_id in 0..BUDGET as u64 {
+        gossiper.record_outstanding_get(busy_peer, item_id);
+    }
+    assert!(!gossiper.exceeds_get_budget(&busy_peer));
+}
+
+/// Runs the given effects, which should send exactly one message, returning its destination and
+/// the message.
+async fn single_sent_message(
+    scheduler: &Scheduler<Event>,
+    effects: Effects<super::Event<Deploy>>,
+) -> (NodeId, Message<Deploy>) {
+    for effect in effects {
+        tokio::spawn(effect);
+    }
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    assert_eq!(scheduler.item_count(), 0);
+    match event {
+        Event::NetworkRequest(NetworkRequest::SendMessage { dest, payload, .. }) => {
+            match *payload {
+                NodeMessage::DeployGossiper(message) => (*dest, message),
+                other => panic!("unexpected message: {}", other),
+            }
+        }
+        other => panic!("unexpected event: {}", other),
+    }
+}
+
+#[tokio::test]
+async fn should_gossip_id_prefix_and_resolve_matching_id() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        gossip_id_prefix_len: 4,
+        ..Config::default()
+    };
+    let mut sending_gossiper = new_deploy_gossiper(config);
+    let mut receiving_gossiper = new_deploy_gossiper(config);
+    let sender = NodeId::random(&mut rng);
+    let receiver = NodeId::random(&mut rng);
+    let item_id = DeployId::random(&mut rng);
+    let _ = sending_gossiper
+        .table
+        .new_complete_data(&item_id, None, GossipTarget::All);
+
+    // Only the prefix of the ID's hash should be gossiped.
+    let prefix = match sending_gossiper.new_gossip_message(&item_id) {
+        Message::GossipIdPrefix(prefix) => prefix,
+        other => panic!("unexpected message: {}", other),
+    };
+    assert_eq!(prefix, id_hash_prefix(&item_id, 4));
+
+    // A receiver not knowing the item should request its full ID, which should then be gossiped.
+    let effects =
+        receiving_gossiper.handle_gossip_id_prefix(effect_builder, prefix.clone(), sender);
+    let (dest, message) = single_sent_message(scheduler, effects).await;
+    assert_eq!(dest, sender);
+    assert!(matches!(message, Message::GetFullId(ref requested) if *requested == prefix));
+    let effects = sending_gossiper.handle_get_full_id(effect_builder, prefix.clone(), receiver);
+    let (dest, message) = single_sent_message(scheduler, effects).await;
+    assert_eq!(dest, receiver);
+    assert!(matches!(message, Message::Gossip(gossiped_id) if gossiped_id == item_id));
+
+    // Once the receiver knows the item, the prefix should be handled as gossip of its full ID.
+    let _ = receiving_gossiper
+        .table
+        .new_complete_data(&item_id, None, GossipTarget::All);
+    let effects = receiving_gossiper.handle_gossip_id_prefix(effect_builder, prefix, sender);
+    for effect in effects {
+        tokio::spawn(effect);
+    }
+    let ((_ancestor, event), _) = scheduler.pop().await;
+    match event {
+        Event::DeployGossiper(super::Event::Incoming(incoming)) => {
+            assert_eq!(incoming.sender, sender);
+            assert!(
+                matches!(*incoming.message, Message::Gossip(gossiped_id) if gossiped_id == item_id)
+            );
+        }
+        other => panic!("unexpected event: {}", other),
+    }
+}
+
+#[tokio::test]
+async fn should_fall_back_to_full_ids_on_id_prefix_collision() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let config = Config {
+        gossip_id_prefix_len: 1,
+        ..Config::default()
+    };
+    let mut sending_gossiper = new_deploy_gossiper(config);
+    let mut receiving_gossiper = new_deploy_gossiper(config);
+    let sender = NodeId::random(&mut rng);
+    let receiver = NodeId::random(&mut rng);
+
+    // Find two IDs whose hashes share the one-byte prefix.
+    let item_id = DeployId::random(&mut rng);
+    let prefix = id_hash_prefix(&item_id, 1);
+    let colliding_id = iter::repeat_with(|| DeployId::random(&mut rng))
+        .find(|other_id| id_hash_prefix(other_id, 1) == prefix)
+        .unwrap();
+    for gossiper in [&mut sending_gossiper, &mut receiving_gossiper] {
+        for id in [&item_id, &colliding_id] {
+            let _ = gossiper
+                .table
+                .new_complete_data(id, None, GossipTarget::All);
+        }
+    }
+
+    // The sender should gossip the full ID, as the prefix is ambiguous.
+    assert!(matches!(
+        sending_gossiper.new_gossip_message(&item_id),
+        Message::Gossip(gossiped_id) if gossiped_id == item_id
+    ));
+
+    // A receiver knowing several IDs with the prefix should request the full IDs, and be sent all
+    // matching ones.
+    let effects =
+        receiving_gossiper.handle_gossip_id_prefix(effect_builder, prefix.clone(), sender);
+    let (dest, message) = single_sent_message(scheduler, effects).await;
+    assert_eq!(dest, sender);
+    assert!(matches!(message, Message::GetFullId(ref requested) if *requested == prefix));
+    let effects = sending_gossiper.handle_get_full_id(effect_builder, prefix, receiver);
+    let (dest, message) = single_sent_message(scheduler, effects).await;
+    assert_eq!(dest, receiver);
+    match message {
+        Message::GossipBatch(gossiped_ids) => {
+            let gossiped_ids: HashSet<_> = gossiped_ids.into_iter().collect();
+            assert_eq!(gossiped_ids, [item_id, colliding_id].into_iter().collect());
+        }
+        other => panic!("unexpected message: {}", other),
+    }
+}
+
+#[tokio::test]
+async fn should_emit_fatal_inconsistency_instead_of_panicking() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let item_id = DeployId::random(&mut rng);
+    let holder = NodeId::random(&mut rng);
+
+    // Put the table into the impossible state of never having asked a holder for the remainder of
+    // an item we don't hold, then check a gossip timeout for it.
+    let _ = gossiper.table.new_data_id(&item_id, holder);
+    gossiper.table.reset_get_remainder_attempts(&item_id);
+    let mut effects = gossiper.check_gossip_timeout(effect_builder, item_id, holder);
+    assert_eq!(effects.len(), 1);
+    let events = effects.pop().unwrap().await;
+    assert_eq!(events.len(), 1);
+    let detail = match events.into_iter().next().unwrap() {
+        super::Event::FatalInconsistency { detail } => detail,
+        other => panic!("unexpected event: {}", other),
+    };
+    assert!(detail.contains(&item_id.to_string()));
+
+    // Handling the event should report it to the reactor.
+    let effects = gossiper.handle_event(
+        effect_builder,
+        &mut rng,
+        super::Event::FatalInconsistency { detail },
+    );
+    assert_eq!(effects.len(), 1);
+}
+
+#[test]
+fn should_limit_rate_of_storage_error_logs() {
+    const LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        storage_error_log_interval: TimeDiff::from(LOG_INTERVAL),
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+
+    // Simulate storage failing for many items in quick succession.  Only the first failure should
+    // be logged, with the rest counted.
+    for _ in 0..100 {
+        let item_id = Deploy::random_valid_native_transfer(&mut rng).gossip_id();
+        let _ = gossiper.failed_to_get_from_storage(effect_builder, item_id, None);
+    }
+    let sampler = &gossiper.storage_error_sampler;
+    assert_eq!(sampler.suppressed(&StorageErrorKind::NotFound), 99);
+
+    // Errors of a different kind are counted separately, so the first should still be logged.
+    let corrupt = StorageErrorKind::Corrupt(ItemCorruption::IdMismatch);
+    assert!(gossiper.should_log_storage_error(corrupt));
+    assert!(!gossiper.should_log_storage_error(corrupt));
+    assert!(!gossiper.should_log_storage_error(StorageErrorKind::NotFound));
+    assert_eq!(gossiper.storage_error_sampler.take_elapsed(), vec![]);
+
+    // Once the interval elapses, the next error should be logged, summarizing those suppressed.
+    fake_instant::FakeClock::advance_time(LOG_INTERVAL.as_millis() as u64);
+    assert_eq!(
+        gossiper
+            .storage_error_sampler
+            .sample(StorageErrorKind::NotFound),
+        Some((100, LOG_INTERVAL))
+    );
+    assert_eq!(
+        gossiper
+            .storage_error_sampler
+            .suppressed(&StorageErrorKind::NotFound),
+        0
+    );
+
+    // Suppressed errors of a kind which doesn't recur are summarized when the interval elapses.
+    assert_eq!(
+        gossiper.storage_error_sampler.take_elapsed(),
+        vec![(corrupt, 1, LOG_INTERVAL)]
+    );
+    assert!(gossiper.should_log_storage_error(corrupt));
+
+    // With no interval, every error should be logged.
+    let config = Config {
+        storage_error_log_interval: TimeDiff::from_millis(0),
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    assert!((0..10).all(|_| gossiper.should_log_storage_error(StorageErrorKind::NotFound)));
+}
+
+#[test]
+fn should_count_per_peer_metrics_for_most_active_peers() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let config = Config {
+        infection_target: 2,
+        max_peers_in_metrics: 2,
+        ..Config::default()
+    };
+    let mut gossiper = new_deploy_gossiper(config);
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let peers: Vec<_> = iter::repeat_with(|| NodeId::random(&mut rng))
+        .take(3)
+        .collect();
+    let labels: Vec<_> = peers.iter().map(NodeId::to_string).collect();
+    let _ = gossiper
+        .table
+        .new_complete_data(&item_id, None, deploy.gossip_target());
+
+    // Gossip to two peers, only one of which responds.  Only the other should have a timeout
+    // counted against it.
+    let _ = gossiper.gossiped_to(
+        effect_builder,
+        item_id,
+        2,
+        peers[..2].iter().copied().collect(),
+    );
+    let _ = gossiper.handle_gossip_response(effect_builder, item_id, false, peers[0]);
+    let _ = gossiper.check_gossip_timeout(effect_builder, item_id, peers[1]);
+    let peer_metrics = gossiper.metrics.peers.as_ref().unwrap();
+    assert_eq!(peer_metrics.count(PeerMetric::GossipTimeout, &labels[0]), 0);
+    assert_eq!(peer_metrics.count(PeerMetric::GossipTimeout, &labels[1]), 1);
+
+    // Fill the remaining label, then have a third peer do no more than the least active labeled
+    // peer.  It should be counted under `other`.
+    gossiper
+        .metrics
+        .record_peer(peers[1], PeerMetric::GossipTimeout);
+    gossiper
+        .metrics
+        .record_peer(peers[0], PeerMetric::FailedGet);
+    gossiper
+        .metrics
+        .record_peer(peers[2], PeerMetric::ItemLearned);
+    let peer_metrics = gossiper.metrics.peers.as_ref().unwrap();
+    assert_eq!(peer_metrics.count(PeerMetric::GossipTimeout, &labels[1]), 2);
+    assert_eq!(peer_metrics.count(PeerMetric::FailedGet, &labels[0]), 1);
+    assert_eq!(peer_metrics.count(PeerMetric::ItemLearned, &labels[2]), 0);
+    assert_eq!(peer_metrics.count(PeerMetric::ItemLearned, "other"), 1);
+
+    // Once the third peer is more active than the least active labeled peer, it should take that
+    // peer's label, with the counts of the displaced peer folded into `other`.
+    gossiper
+        .metrics
+        .record_peer(peers[2], PeerMetric::ItemLearned);
+    let peer_metrics = gossiper.metrics.peers.as_ref().unwrap();
+    assert_eq!(peer_metrics.count(PeerMetric::ItemLearned, &labels[2]), 1);
+    assert_eq!(peer_metrics.count(PeerMetric::ItemLearned, "other"), 1);
+    assert_eq!(peer_metrics.count(PeerMetric::FailedGet, &labels[0]), 0);
+    assert_eq!(peer_metrics.count(PeerMetric::FailedGet, "other"), 1);
+    assert_eq!(peer_metrics.count(PeerMetric::GossipTimeout, &labels[1]), 2);
+}
+
+#[tokio::test]
+async fn should_reply_already_held_to_echo_of_own_gossip() {
+    let mut rng = crate::new_rng();
+    let scheduler = utils::leak(Scheduler::<Event>::new(QueueKind::weights(), None));
+    let effect_builder = EffectBuilder::new(EventQueueHandle::without_shutdown(scheduler));
+    let mut gossiper = new_deploy_gossiper(Config::default());
+    let our_id = NodeId::random(&mut rng);
+    let relay = NodeId::random(&mut rng);
+    gossiper.set_our_id(our_id);
+
+    // Originate an item, which we then gossip.
+    let deploy = Deploy::random_valid_native_transfer(&mut rng);
+    let item_id = deploy.gossip_id();
+    let _ = gossiper.handle_item_received(
+        effect_builder,
+        item_id,
+        Source::Client,
+        deploy.gossip_target(),
+    );
+
+    let gossip = |sender: NodeId, item_id: DeployId| {
+        super::Event::Incoming(GossiperIncoming {
+            sender,
+            message: Box::new(Message::Gossip(item_id)),
+        })
+    };
+
+    // Our gossip reflected back via a relay should be answered without checking storage, while
+    // recording the relay as a holder.
+    let effects = gossiper.handle_event(effect_builder, &mut rng, gossip(relay, item_id));
+    let (dest, message) = single_sent_message(scheduler, effects).await;
+    assert_eq!(dest, relay);
+    assert!(matches!(
+        message,
+        Message::GossipResponse {
+            item_id: responded_id,
+            is_already_held: true,
+        } if responded_id == item_id
+    ));
+    assert!(gossiper.table.is_holder(&item_id, &relay));
+
+    // Gossip appearing to come from ourselves should be dropped, even for an item we didn't
+    // originate.
+    let other_item_id = DeployId::random(&mut rng);
+    let effects = gossiper.handle_event(effect_builder, &mut rng, gossip(our_id, other_item_id));
+    assert!(effects.is_empty());
+    assert!(!gossiper.table.has_entry(&other_item_id));
+}
+
+#[test]
+fn should_not_double_gossip_resubmitted_item() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    for policy in [ResubmissionPolicy::TopUp, ResubmissionPolicy::Ignore] {
+        let config = Config {
+            infection_target: 2,
+            resubmission_policy: policy,
+            ..Config::default()
+        };
+        let mut gossiper = new_deploy_gossiper(config);
+        let requested_counts = Arc::new(Mutex::new(vec![]));
+        let recorded_counts = Arc::clone(&requested_counts);
+        gossiper.set_peer_selector(Box::new(move |count, _exclude_peers| {
+            recorded_counts.lock().unwrap().push(count);
+            HashSet::new()
+        }));
+        let deploy = Deploy::random_valid_native_transfer(&mut rng);
+        let item_id = deploy.gossip_id();
+        let target = deploy.gossip_target();
+
+        // Submitting the item should gossip it to the infection target.
+        let _ = gossiper.handle_item_received(effect_builder, item_id, Source::Client, target);
+        assert_eq!(*requested_counts.lock().unwrap(), vec![2]);
+
+        // Re-submitting it while that gossip is in flight shouldn't gossip it again.
+        let _ = gossiper.handle_item_received(effect_builder, item_id, Source::Client, target);
+        assert_eq!(*requested_counts.lock().unwrap(), vec![2]);
+
+        // Once only one of the requested peers could be gossiped to, a re-submission should only
+        // top up the gossip by one peer, or not gossip at all if ignored.
+        let peer = NodeId::random(&mut rng);
+        let _ = gossiper.gossiped_to(effect_builder, item_id, 2, iter::once(peer).collect());
+        let _ = gossiper.handle_item_received(effect_builder, item_id, Source::Client, target);
+        let expected = match policy {
+            ResubmissionPolicy::TopUp => vec![2, 1],
+            ResubmissionPolicy::Ignore => vec![2],
+        };
+        assert_eq!(*requested_counts.lock().unwrap(), expected);
+    }
+}
+
+/// An item stored under a key other than its gossip ID.
+#[derive(Clone, Debug, Display, Serialize, Deserialize, PartialEq, Eq)]
+#[display(fmt = "keyed item {}", id)]
+struct KeyedItem {
+    id: u64,
+}
+
+impl GossipItem for KeyedItem {
+    type Id = u64;
+    type StorageKey = String;
+
+    const ID_IS_COMPLETE_ITEM: bool = false;
+    const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
+
+    fn gossip_id(&self) -> Self::Id {
+        self.id
+    }
+
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        format!("keyed-item-{}", item_id)
+    }
+
+    fn gossip_target(&self) -> GossipTarget {
+        GossipTarget::All
+    }
+}
+
+thread_local! {
+    /// The keyed items held in storage, by storage key.
+    static KEYED_ITEM_STORAGE: std::cell::RefCell<HashMap<String, KeyedItem>> =
+        std::cell::RefCell::new(HashMap::new());
+}
+
+#[async_trait::async_trait]
+impl ItemProvider<KeyedItem> for Gossiper<false, KeyedItem> {
+    async fn is_stored<REv: From<StorageRequest> + Send>(
+        _effect_builder: EffectBuilder<REv>,
+        storage_key: String,
+    ) -> bool {
+        KEYED_ITEM_STORAGE.with(|storage| storage.borrow().contains_key(&storage_key))
+    }
+
+    async fn get_from_storage<REv: From<StorageRequest> + Send>(
+        _effect_builder: EffectBuilder<REv>,
+        storage_key: String,
+    ) -> Option<Box<KeyedItem>> {
+        KEYED_ITEM_STORAGE.with(|storage| storage.borrow().get(&storage_key).cloned().map(Box::new))
+    }
+}
+
+#[tokio::test]
+async fn should_use_storage_key_for_storage_operations() {
+    let mut rng = crate::new_rng();
+    let effect_builder = new_effect_builder();
+    let mut gossiper = Gossiper::<false, KeyedItem>::new(
+        "test_keyed_gossiper",
+        Config::default(),
+        &Registry::new(),
+    )
+    .unwrap();
+    let item = KeyedItem { id: rng.gen() };
+    let item_id = item.gossip_id();
+    let unstored_item_id = item_id.wrapping_add(1);
+    let storage_key = KeyedItem::storage_key(&item_id);
+    assert_ne!(storage_key, item_id.to_string());
+    KEYED_ITEM_STORAGE.with(|storage| storage.borrow_mut().insert(storage_key, item.clone()));
+
+    // Checking whether items are stored should use their storage keys, while the resulting events
+    // refer to their gossip IDs.
+    let source = NodeId::random(&mut rng);
+    let mut effects = Effects::new();
+    for gossiped_id in [item_id, unstored_item_id] {
+        let event = super::Event::Incoming(GossiperIncoming {
+            sender: source,
+            message: Box::new(Message::Gossip(gossiped_id)),
+        });
+        effects.extend(gossiper.handle_event(effect_builder, &mut rng, event));
+    }
+    let mut results = vec![];
+    for effect in effects {
+        for event in effect.await {
+            match event {
+                super::Event::IsStoredResult {
+                    item_id, result, ..
+                } => results.push((item_id, result)),
+                other => panic!("unexpected event: {}", other),
+            }
+        }
+    }
+    results.sort();
+    assert_eq!(results, vec![(item_id, true), (unstored_item_id, false)]);
+
+    // A peer's request for the item should be served from storage via its storage key.
+    let requester = NodeId::random(&mut rng);
+    let effects = gossiper.get_from_storage_for(effect_builder, item_id, requester);
+    assert_eq!(effects.len(), 1);
+    let events = effects.into_iter().next().unwrap().await;
+    assert!(matches!(
+        events.as_slice(),
+        [super::Event::GetFromStorageResult {
+            item_id: result_item_id,
+            maybe_item: Some(stored_item),
+            ..
+        }] if *result_item_id == item_id && **stored_item == item
+    ));
+}
@@ -0,0 +1,29 @@
+use std::{collections::HashSet, fmt::Debug};
+
+use futures::future::BoxFuture;
+
+use super::{GossipItem, Message};
+use crate::{effect::GossipTarget, types::NodeId};
+
+/// A secondary channel, such as a separate relay, over which gossip messages are announced in
+/// addition to the primary gossip network.
+///
+/// Secondary transports are used for redundancy only: their results do not affect the gossip
+/// table, and a failure or timeout on one doesn't affect gossiping via the others.
+pub(crate) trait GossipTransport<T: GossipItem>: Debug + Send + Sync {
+    /// A short name identifying the transport in logs.
+    fn name(&self) -> &str;
+
+    /// Announces the given message to up to `count` peers matching `gossip_target`, excluding
+    /// `exclude_peers`.
+    ///
+    /// The returned future resolves to the set of peers announced to, or `None` if the transport
+    /// is unavailable.
+    fn announce(
+        &self,
+        message: Message<T>,
+        gossip_target: GossipTarget,
+        count: usize,
+        exclude_peers: HashSet<NodeId>,
+    ) -> BoxFuture<'static, Option<HashSet<NodeId>>>;
+}
@@ -49,7 +49,7 @@ use std::{
     fmt::{self, Debug, Display, Formatter},
     io,
     net::{SocketAddr, TcpListener},
-    sync::{Arc, Weak},
+    sync::{Arc, RwLock, Weak},
     time::{Duration, Instant},
 };
 
@@ -148,6 +148,43 @@ const PING_TIMEOUT: Duration = Duration::from_secs(6);
 /// How many pings to send before giving up and dropping the connection.
 const PING_RETRIES: u16 = 5;
 
+/// The consensus keys with which peers connected to us authenticated during the handshake, kept up
+/// to date by the network component and shared with other components.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct PeerConsensusKeys(Arc<RwLock<HashMap<NodeId, PublicKey>>>);
+
+impl PeerConsensusKeys {
+    /// Returns the consensus key the given peer authenticated with, if it is connected to us and
+    /// presented one.
+    pub(crate) fn get(&self, peer_id: &NodeId) -> Option<PublicKey> {
+        match self.0.read() {
+            Ok(keys) => keys.get(peer_id).cloned(),
+            Err(_) => {
+                error!("could not read peer consensus keys, lock poisoned");
+                None
+            }
+        }
+    }
+
+    fn insert(&self, peer_id: NodeId, public_key: PublicKey) {
+        match self.0.write() {
+            Ok(mut keys) => {
+                let _ = keys.insert(peer_id, public_key);
+            }
+            Err(_) => error!("could not update peer consensus keys, lock poisoned"),
+        }
+    }
+
+    fn remove(&self, peer_id: &NodeId) {
+        match self.0.write() {
+            Ok(mut keys) => {
+                let _ = keys.remove(peer_id);
+            }
+            Err(_) => error!("could not remove from peer consensus keys, lock poisoned"),
+        }
+    }
+}
+
 #[derive(Clone, DataSize, Debug)]
 pub(crate) struct OutgoingHandle<P> {
     #[data_size(skip)] // Unfortunately, there is no way to inspect an `UnboundedSender`.
@@ -196,6 +233,10 @@ where
     #[data_size(skip)]
     incoming_limiter: Limiter,
 
+    /// The consensus keys of the peers with incoming connections to us.
+    #[data_size(skip)]
+    peer_consensus_keys: PeerConsensusKeys,
+
     /// The era that is considered the active era by the network component.
     active_era: EraId,
 
@@ -295,6 +336,7 @@ where
             net_metrics,
             outgoing_limiter,
             incoming_limiter,
+            peer_consensus_keys: PeerConsensusKeys::default(),
             // We start with an empty set of validators for era 0 and expect to be updated.
             active_era: EraId::new(0),
             state: ComponentState::Uninitialized,
@@ -599,6 +641,10 @@ where
                     // connection after a peer has closed the corresponding incoming connection.
                 }
 
+                if let Some(public_key) = &peer_consensus_public_key {
+                    self.peer_consensus_keys.insert(peer_id, public_key.clone());
+                }
+
                 // Now we can start the message reader.
                 let boxed_span = Box::new(span.clone());
                 effects.extend(
@@ -648,6 +694,14 @@ where
                 .entry(peer_id)
                 .or_default()
                 .remove_incoming(peer_addr, Instant::now());
+            let has_incoming = self
+                .connection_symmetries
+                .get(&peer_id)
+                .and_then(ConnectionSymmetry::incoming_addrs)
+                .map_or(false, |addrs| !addrs.is_empty());
+            if !has_incoming {
+                self.peer_consensus_keys.remove(&peer_id);
+            }
 
             Effects::new()
         })
@@ -1004,6 +1058,12 @@ where
         ret
     }
 
+    /// Returns the consensus keys of the peers with incoming connections to us, as kept up to date
+    /// by this component.
+    pub(crate) fn peer_consensus_keys(&self) -> PeerConsensusKeys {
+        self.peer_consensus_keys.clone()
+    }
+
     pub(crate) fn fully_connected_peers_random(
         &self,
         rng: &mut NodeRng,
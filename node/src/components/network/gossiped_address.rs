@@ -34,11 +34,16 @@ impl GossipItem for GossipedAddress {
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
 
     type Id = GossipedAddress;
+    type StorageKey = GossipedAddress;
 
     fn gossip_id(&self) -> Self::Id {
         *self
     }
 
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        *item_id
+    }
+
     fn gossip_target(&self) -> GossipTarget {
         GossipTarget::All
     }
@@ -30,7 +30,9 @@ use crate::{
         Component, InitializedComponent,
     },
     effect::{
-        announcements::{ControlAnnouncement, GossiperAnnouncement, PeerBehaviorAnnouncement},
+        announcements::{
+            ControlAnnouncement, FatalAnnouncement, GossiperAnnouncement, PeerBehaviorAnnouncement,
+        },
         incoming::GossiperIncoming,
         requests::{
             BeginGossipRequest, ChainspecRawBytesRequest, ContractRuntimeRequest, NetworkRequest,
@@ -122,6 +124,12 @@ impl From<ContractRuntimeRequest> for Event {
     }
 }
 
+impl From<FatalAnnouncement> for Event {
+    fn from(_announcement: FatalAnnouncement) -> Self {
+        unreachable!()
+    }
+}
+
 impl FromIncoming<Message> for Event {
     fn from_incoming(sender: NodeId, payload: Message) -> Self {
         match payload {
@@ -132,6 +132,12 @@ const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 const MAX_DB_COUNT: u32 = 9;
 /// Key under which completed blocks are to be stored.
 const COMPLETED_BLOCKS_STORAGE_KEY: &[u8] = b"completed_blocks_disjoint_sequences";
+/// Key under which the schema version of the stored data is to be stored.
+const SCHEMA_VERSION_STORAGE_KEY: &[u8] = b"schema_version";
+/// The schema version of the data written by this version of the storage component.
+///
+/// Must be incremented whenever the on-disk format changes in a way which requires a migration.
+const STORAGE_SCHEMA_VERSION: u32 = 1;
 /// Name of the file created when initializing a force resync.
 const FORCE_RESYNC_FILE_NAME: &str = "force_resync";
 
@@ -404,7 +410,8 @@ impl Storage {
             .set_max_readers(MAX_TRANSACTIONS)
             .set_max_dbs(MAX_DB_COUNT)
             .set_map_size(total_size)
-            .open(&root.join(STORAGE_DB_FILENAME))?;
+            .open(&root.join(STORAGE_DB_FILENAME))
+            .map_err(FatalStorageError::from_open_error)?;
 
         let block_header_db = env.create_db(Some("block_header"), DatabaseFlags::empty())?;
         let block_metadata_db = env.create_db(Some("block_metadata"), DatabaseFlags::empty())?;
@@ -423,7 +430,7 @@ impl Storage {
         let mut block_height_index = BTreeMap::new();
         let mut switch_block_era_id_index = BTreeMap::new();
         let mut deploy_hash_index = BTreeMap::new();
-        let mut block_txn = env.begin_rw_txn()?;
+        let mut block_txn = begin_rw_txn_for(&env, "index block store")?;
         let mut cursor = block_txn.open_rw_cursor(block_header_db)?;
 
         let mut deleted_block_hashes = HashSet::new();
@@ -521,6 +528,8 @@ impl Storage {
             metrics,
         };
 
+        component.check_schema_version()?;
+
         if force_resync {
             let force_resync_file_path = component.root_path().join(FORCE_RESYNC_FILE_NAME);
             // Check if resync is already in progress. Force resync will kick
@@ -601,6 +610,32 @@ impl Storage {
         Ok(component)
     }
 
+    /// Checks the schema version recorded in the state store against `STORAGE_SCHEMA_VERSION`.
+    ///
+    /// Databases created before a schema version was recorded are assumed to be current, and are
+    /// stamped with the current version.
+    fn check_schema_version(&self) -> Result<(), FatalStorageError> {
+        match self.read_state_store(&Cow::Borrowed(SCHEMA_VERSION_STORAGE_KEY))? {
+            Some(raw) => {
+                let (found, _) = u32::from_bytes(&raw)
+                    .map_err(FatalStorageError::UnexpectedDeserializationFailure)?;
+                if found != STORAGE_SCHEMA_VERSION {
+                    return Err(FatalStorageError::VersionMismatch {
+                        found,
+                        expected: STORAGE_SCHEMA_VERSION,
+                    });
+                }
+                Ok(())
+            }
+            None => {
+                let serialized = STORAGE_SCHEMA_VERSION
+                    .to_bytes()
+                    .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+                self.write_state_store(Cow::Borrowed(SCHEMA_VERSION_STORAGE_KEY), &serialized)
+            }
+        }
+    }
+
     /// Reads from the state storage database.
     ///
     /// If key is non-empty, returns bytes from under the key. Otherwise returns `Ok(None)`.
@@ -625,7 +660,7 @@ impl Storage {
         key: Cow<'static, [u8]>,
         data: &Vec<u8>,
     ) -> Result<(), FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "write state store")?;
 
         // Note: The interface of `lmdb` seems suboptimal: `&K` and `&V` could simply be `&[u8]` for
         //       simplicity. At the very least it seems to be missing a `?Sized` trait bound. For
@@ -800,7 +835,7 @@ impl Storage {
                 responder,
             } => {
                 let env = Rc::clone(&self.env);
-                let mut txn = env.begin_rw_txn()?;
+                let mut txn = begin_rw_txn_for(&env, "put approvals hashes")?;
                 let result = self.write_approvals_hashes(&mut txn, &approvals_hashes)?;
                 txn.commit()?;
                 responder.respond(result).ignore()
@@ -889,17 +924,20 @@ impl Storage {
                 responder,
             } => {
                 let mut txn = self.env.begin_ro_txn()?;
-                let maybe_deploy = match self
-                    .get_deploy_with_finalized_approvals(&mut txn, deploy_id.deploy_hash())?
-                {
-                    None => None,
-                    Some(deploy_with_finalized_approvals) => {
-                        let deploy = deploy_with_finalized_approvals.into_naive();
-                        (deploy.fetch_id() == deploy_id).then_some(deploy)
-                    }
-                };
+                let maybe_deploy = self.get_deploy_with_id(&mut txn, deploy_id)?;
                 responder.respond(maybe_deploy).ignore()
             }
+            StorageRequest::GetDeploysById {
+                deploy_ids,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let maybe_deploys = deploy_ids
+                    .into_iter()
+                    .map(|deploy_id| self.get_deploy_with_id(&mut txn, deploy_id))
+                    .collect::<Result<Vec<_>, _>>()?;
+                responder.respond(maybe_deploys).ignore()
+            }
             StorageRequest::IsDeployStored {
                 deploy_id,
                 responder,
@@ -923,7 +961,7 @@ impl Storage {
                 responder,
             } => {
                 let env = Rc::clone(&self.env);
-                let mut txn = env.begin_rw_txn()?;
+                let mut txn = begin_rw_txn_for(&env, "put execution results")?;
                 self.write_execution_results(&mut txn, &block_hash, execution_results)?;
                 txn.commit()?;
                 responder.respond(()).ignore()
@@ -1091,7 +1129,7 @@ impl Storage {
                     );
                     return Ok(responder.respond(false).ignore());
                 }
-                let mut txn = self.env.begin_rw_txn()?;
+                let mut txn = begin_rw_txn_for(&self.env, "put block signatures")?;
                 let old_data: Option<BlockSignatures> =
                     txn.get_value(self.block_metadata_db, &signatures.block_hash)?;
                 let new_data = match old_data {
@@ -1168,6 +1206,21 @@ impl Storage {
             } => responder
                 .respond(self.put_executed_block(&block, &approvals_hashes, execution_results)?)
                 .ignore(),
+            StorageRequest::SyncToDisk { responder } => {
+                self.env.sync(true)?;
+                responder.respond(()).ignore()
+            }
+            StorageRequest::IsWritable { responder } => {
+                let is_writable = match begin_rw_txn_for(&self.env, "check storage is writable") {
+                    Ok(txn) => {
+                        txn.abort();
+                        true
+                    }
+                    Err(FatalStorageError::Readonly { .. }) => false,
+                    Err(err) => return Err(err),
+                };
+                responder.respond(is_writable).ignore()
+            }
             StorageRequest::GetKeyBlockHeightForActivationPoint { responder } => {
                 // If we haven't already cached the height, try to retrieve the key block header.
                 if self.key_block_height_for_activation_point.is_none() {
@@ -1191,7 +1244,7 @@ impl Storage {
         &mut self,
         signature: Box<FinalitySignature>,
     ) -> Result<bool, FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "put finality signature")?;
         let mut block_signatures = txn
             .get_value(self.block_metadata_db, &signature.block_hash)?
             .unwrap_or_else(|| BlockSignatures::new(signature.block_hash, signature.era_id));
@@ -1250,7 +1303,7 @@ impl Storage {
 
     /// Put a single deploy into storage.
     pub fn put_deploy(&self, deploy: &Deploy) -> Result<bool, FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "put deploy")?;
         let deploy_hash = deploy.hash();
         let outcome = txn.put_value(self.deploy_db, deploy_hash, deploy, false)?;
         if outcome {
@@ -1269,7 +1322,7 @@ impl Storage {
         execution_results: HashMap<DeployHash, ExecutionResult>,
     ) -> Result<bool, FatalStorageError> {
         let env = Rc::clone(&self.env);
-        let mut txn = env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&env, "put executed block")?;
         let wrote = self.write_validated_block(&mut txn, block)?;
         if !wrote {
             return Err(FatalStorageError::FailedToOverwriteBlock);
@@ -1436,7 +1489,7 @@ impl Storage {
         // Validate the block prior to inserting it into the database
         block.verify()?;
         let env = Rc::clone(&self.env);
-        let mut txn = env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&env, "write block")?;
         let wrote = self.write_validated_block(&mut txn, block)?;
         if wrote {
             txn.commit()?;
@@ -1454,7 +1507,7 @@ impl Storage {
         // Validate the block prior to inserting it into the database
         block.verify()?;
         let env = Rc::clone(&self.env);
-        let mut txn = env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&env, "write complete block")?;
         let wrote = self.write_validated_block(&mut txn, block)?;
         if wrote {
             // Update the `completed_blocks` index only if the block was actually stored.
@@ -1531,7 +1584,7 @@ impl Storage {
         &mut self,
         signatures: &BlockSignatures,
     ) -> Result<(), FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "write finality signatures")?;
         let block_hash = signatures.block_hash;
         if txn
             .put_value(self.block_metadata_db, &block_hash, signatures, true)
@@ -2070,7 +2123,7 @@ impl Storage {
         &mut self,
         block_headers: Vec<BlockHeader>,
     ) -> Result<bool, FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "put block headers")?;
         let mut result = false;
 
         for block_header in &block_headers {
@@ -2170,6 +2223,24 @@ impl Storage {
             .collect()
     }
 
+    /// Retrieves the deploy with the given ID, i.e. with the finalized approvals if any, and only
+    /// if its approvals match those of the ID.
+    fn get_deploy_with_id<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        deploy_id: DeployId,
+    ) -> Result<Option<Deploy>, FatalStorageError> {
+        Ok(
+            match self.get_deploy_with_finalized_approvals(txn, deploy_id.deploy_hash())? {
+                None => None,
+                Some(deploy_with_finalized_approvals) => {
+                    let deploy = deploy_with_finalized_approvals.into_naive();
+                    (deploy.fetch_id() == deploy_id).then_some(deploy)
+                }
+            },
+        )
+    }
+
     /// Retrieves a single deploy along with its finalized approvals from storage
     fn get_deploy_with_finalized_approvals<Tx: Transaction>(
         &self,
@@ -2212,7 +2283,7 @@ impl Storage {
         &self,
         block_hash: &BlockHash,
     ) -> Result<Option<Vec<Transfer>>, FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "get transfers")?;
         if let Some(transfers) = txn.get_value::<_, Vec<Transfer>>(self.transfer_db, block_hash)? {
             if !transfers.is_empty() {
                 return Ok(Some(transfers));
@@ -2296,7 +2367,7 @@ impl Storage {
         deploy_hash: &DeployHash,
         finalized_approvals: &FinalizedApprovals,
     ) -> Result<bool, FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "store finalized approvals")?;
         let maybe_original_deploy: Option<Deploy> = txn.get_value(self.deploy_db, &deploy_hash)?;
         let original_deploy =
             maybe_original_deploy.ok_or(FatalStorageError::UnexpectedFinalizedApprovals {
@@ -2581,7 +2652,7 @@ impl Storage {
         &self,
         block_hash: &BlockHash,
     ) -> Result<Option<Vec<(DeployHash, DeployHeader, ExecutionResult)>>, FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "read execution results")?;
         let execution_results = match self.get_execution_results(&mut txn, block_hash)? {
             Some(execution_results) => execution_results,
             None => return Ok(None),
@@ -2608,7 +2679,7 @@ impl Storage {
         &self,
         request: &BlockExecutionResultsOrChunkId,
     ) -> Result<Option<BlockExecutionResultsOrChunk>, FatalStorageError> {
-        let mut txn = self.env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&self.env, "read block execution results or chunk")?;
         let execution_results = match self.get_execution_results(&mut txn, request.block_hash())? {
             Some(execution_results) => execution_results
                 .into_iter()
@@ -2646,6 +2717,16 @@ impl Storage {
     }
 }
 
+/// Begins a read-write transaction, returning `FatalStorageError::Readonly` naming `operation` if
+/// the environment was opened read-only.
+fn begin_rw_txn_for<'a>(
+    env: &'a Environment,
+    operation: &str,
+) -> Result<RwTransaction<'a>, FatalStorageError> {
+    env.begin_rw_txn()
+        .map_err(|err| FatalStorageError::from_write_error(err, operation))
+}
+
 /// Decodes an item's ID, typically from an incoming request.
 fn decode_item_id<T>(raw: &[u8]) -> Result<T::Id, GetRequestError>
 where
@@ -2964,7 +3045,7 @@ fn initialize_block_body_db(
     deleted_block_body_hashes_raw: &HashSet<&[u8]>,
 ) -> Result<(), FatalStorageError> {
     info!("initializing block body database");
-    let mut txn = env.begin_rw_txn()?;
+    let mut txn = begin_rw_txn_for(&env, "initialize block body database")?;
 
     let block_body_hash_to_header_map =
         construct_block_body_to_block_header_reverse_lookup(&txn, block_header_db)?;
@@ -3015,7 +3096,7 @@ fn initialize_block_metadata_db(
     );
 
     if !deleted_block_hashes.is_empty() {
-        let mut txn = env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&env, "initialize block metadata database")?;
         let mut cursor = txn.open_rw_cursor(*block_metadata_db)?;
 
         for row in cursor.iter() {
@@ -3043,7 +3124,7 @@ fn initialize_deploy_metadata_db(
     env: &Environment,
     deploy_metadata_db: &Database,
     deleted_deploy_hashes: &HashSet<DeployHash>,
-) -> Result<(), LmdbExtError> {
+) -> Result<(), FatalStorageError> {
     let deploy_count_to_be_deleted = deleted_deploy_hashes.len();
     info!(
         deploy_count_to_be_deleted,
@@ -3051,7 +3132,7 @@ fn initialize_deploy_metadata_db(
     );
 
     if !deleted_deploy_hashes.is_empty() {
-        let mut txn = env.begin_rw_txn()?;
+        let mut txn = begin_rw_txn_for(&env, "initialize deploy metadata database")?;
         deleted_deploy_hashes.iter().for_each(|deleted_deploy_hash| {
         if txn.del(*deploy_metadata_db, deleted_deploy_hash, None).is_err() {
             debug!(%deleted_deploy_hash, "not purging from 'deploy_metadata_db' because not existing");
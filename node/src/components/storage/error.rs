@@ -53,7 +53,18 @@ pub enum FatalStorageError {
     },
     /// LMDB error while operating.
     #[error("internal database error: {0}")]
-    InternalStorage(#[from] LmdbExtError),
+    InternalStorage(LmdbExtError),
+    /// The filesystem holding storage has run out of space.
+    #[error("disk full, free space or increase storage: {0}")]
+    DiskFull(lmdb::Error),
+    /// The LMDB environment was created by an incompatible version of the LMDB library, e.g. by a
+    /// newer node binary before the node was downgraded.  Unrelated to the node's own storage
+    /// schema version, which is checked separately (see `VersionMismatch`).
+    #[error(
+        "LMDB environment was created by an incompatible LMDB library version, likely by a newer \
+        node binary; run a node binary built against the same LMDB version: {0}"
+    )]
+    LmdbVersionMismatch(lmdb::Error),
     /// An internal DB error - blocks should be overwritten.
     #[error("failed overwriting block")]
     FailedToOverwriteBlock,
@@ -132,6 +143,12 @@ pub enum FatalStorageError {
         /// The hash of the superfluous body part.
         part_hash: Digest,
     },
+    /// An attempt was made to write to storage which was opened read-only.
+    #[error("storage is read-only: cannot {operation}")]
+    Readonly {
+        /// The write operation which was attempted.
+        operation: String,
+    },
     /// Failed to serialize an item that was found in local storage.
     #[error("failed to serialized stored item")]
     StoredItemSerializationFailure(#[source] bincode::Error),
@@ -162,6 +179,17 @@ pub enum FatalStorageError {
         /// The number of approvals hashes.
         actual: usize,
     },
+    /// The stored data was written using a different schema version and needs to be migrated.
+    #[error(
+        "storage schema version mismatch: found {found}, expected {expected}; \
+        a storage migration needs to be run"
+    )]
+    VersionMismatch {
+        /// The schema version recorded in storage.
+        found: u32,
+        /// The schema version expected by this version of the node.
+        expected: u32,
+    },
     /// Error initializing metrics.
     #[error("failed to initialize metrics for storage: {0}")]
     Prometheus(#[from] prometheus::Error),
@@ -174,6 +202,87 @@ impl From<lmdb::Error> for FatalStorageError {
     }
 }
 
+impl From<LmdbExtError> for FatalStorageError {
+    fn from(err: LmdbExtError) -> Self {
+        match err {
+            // Running out of disk space is actionable by the operator, so is reported distinctly.
+            LmdbExtError::DiskFull(lmdb_error) => FatalStorageError::DiskFull(lmdb_error),
+            err => FatalStorageError::InternalStorage(err),
+        }
+    }
+}
+
+impl FatalStorageError {
+    /// Converts an `lmdb::Error` arising from the given write operation, classifying a write
+    /// against a read-only environment as `Readonly`.
+    pub(super) fn from_write_error(err: lmdb::Error, operation: &str) -> Self {
+        match err {
+            // LMDB returns `EACCES` when a write transaction is requested on a read-only
+            // environment.
+            lmdb::Error::Other(libc::EACCES) => FatalStorageError::Readonly {
+                operation: operation.to_string(),
+            },
+            err => err.into(),
+        }
+    }
+
+    /// Converts an `lmdb::Error` arising from opening the LMDB environment, classifying an
+    /// environment created by an incompatible LMDB library version as `LmdbVersionMismatch`.
+    pub(super) fn from_open_error(err: lmdb::Error) -> Self {
+        match err {
+            lmdb::Error::VersionMismatch => FatalStorageError::LmdbVersionMismatch(err),
+            err => err.into(),
+        }
+    }
+
+    /// Returns `true` if the error arises from a transient condition, such that retrying the
+    /// operation may succeed, or `false` if storage is corrupted or otherwise unusable until the
+    /// node is reconfigured or restarted.
+    pub(crate) fn is_retryable(&self) -> bool {
+        // Variants are matched exhaustively so that new ones must be explicitly classified.
+        match self {
+            FatalStorageError::InternalStorage(LmdbExtError::ResourceExhausted(lmdb_error)) => {
+                // Readers are released as other reads complete, and a map resized by another
+                // process only requires the transaction to be restarted.
+                matches!(
+                    lmdb_error,
+                    lmdb::Error::ReadersFull | lmdb::Error::MapResized
+                )
+            }
+            FatalStorageError::InternalStorage(
+                LmdbExtError::LmdbCorrupted(_)
+                | LmdbExtError::DataCorrupted(_)
+                | LmdbExtError::DiskFull(_)
+                | LmdbExtError::Other(_),
+            )
+            | FatalStorageError::CreateDatabaseDirectory(..)
+            | FatalStorageError::DuplicateBlockIndex { .. }
+            | FatalStorageError::DuplicateEraIdIndex { .. }
+            | FatalStorageError::DuplicateDeployIndex { .. }
+            | FatalStorageError::DiskFull(_)
+            | FatalStorageError::LmdbVersionMismatch(_)
+            | FatalStorageError::FailedToOverwriteBlock
+            | FatalStorageError::UnableToMoveFile { .. }
+            | FatalStorageError::MissingStorageFiles { .. }
+            | FatalStorageError::BlockValidation(_)
+            | FatalStorageError::BlockHeaderNotStoredUnderItsHash { .. }
+            | FatalStorageError::NoBlockHeaderForBlockBody { .. }
+            | FatalStorageError::SignatureVerification(_)
+            | FatalStorageError::CorruptedBlockSignatureIndex { .. }
+            | FatalStorageError::InvalidSwitchBlock(_)
+            | FatalStorageError::UnexpectedBlockBodyPart { .. }
+            | FatalStorageError::Readonly { .. }
+            | FatalStorageError::StoredItemSerializationFailure(_)
+            | FatalStorageError::UnexpectedFinalizedApprovals { .. }
+            | FatalStorageError::UnexpectedSerializationFailure(_)
+            | FatalStorageError::UnexpectedDeserializationFailure(_)
+            | FatalStorageError::ApprovalsHashesLengthMismatch { .. }
+            | FatalStorageError::VersionMismatch { .. }
+            | FatalStorageError::Prometheus(_) => false,
+        }
+    }
+}
+
 impl From<Box<BlockValidationError>> for FatalStorageError {
     fn from(err: Box<BlockValidationError>) -> Self {
         Self::BlockValidation(*err)
@@ -42,6 +42,10 @@ pub enum LmdbExtError {
     /// might fix the problem. Storage integrity is still intact.
     #[error("storage exhausted resource (but still intact): {0}")]
     ResourceExhausted(lmdb::Error),
+    /// The filesystem holding the database has run out of space.  Unlike `MapFull`, this cannot be
+    /// fixed by changing settings; space must be freed or storage increased.
+    #[error("disk full: {0}")]
+    DiskFull(lmdb::Error),
     /// Error neither corruption nor resource exhaustion occurred, likely a programming error.
     #[error("unknown LMDB or serialization error, likely from a bug: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -72,6 +76,8 @@ impl From<lmdb::Error> for LmdbExtError {
             | lmdb::Error::PageFull
             | lmdb::Error::MapResized => LmdbExtError::ResourceExhausted(lmdb_error),
 
+            lmdb::Error::Other(libc::ENOSPC) => LmdbExtError::DiskFull(lmdb_error),
+
             lmdb::Error::NotFound
             | lmdb::Error::BadRslot
             | lmdb::Error::BadTxn
@@ -1,6 +1,7 @@
 //! Unit tests for the storage component.
 
 use std::{
+    borrow::Cow,
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs::{self, File},
     iter::{self, FromIterator},
@@ -8,25 +9,35 @@ use std::{
     sync::Arc,
 };
 
-use lmdb::Transaction;
+use lmdb::{Environment, EnvironmentFlags, Transaction};
 use rand::{prelude::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
 use casper_types::{
-    generate_ed25519_keypair, system::auction::UnbondingPurse, testing::TestRng, AccessRights,
-    EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey, TimeDiff,
-    Transfer, Transform, TransformEntry, URef, U512,
+    bytesrepr::{self, ToBytes},
+    generate_ed25519_keypair,
+    system::auction::UnbondingPurse,
+    testing::TestRng,
+    AccessRights, EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey,
+    SecretKey, TimeDiff, Transfer, Transform, TransformEntry, URef, U512,
 };
 
 use super::{
-    initialize_block_metadata_db,
-    lmdb_ext::{deserialize_internal, serialize_internal, TransactionExt, WriteTransactionExt},
+    begin_rw_txn_for, initialize_block_metadata_db,
+    lmdb_ext::{
+        deserialize_internal, serialize_internal, BytesreprError, LmdbExtError, TransactionExt,
+        WriteTransactionExt,
+    },
     move_storage_files_to_network_subdir, should_move_storage_files_to_network_subdir, Config,
-    Storage, FORCE_RESYNC_FILE_NAME,
+    FatalStorageError, Storage, FORCE_RESYNC_FILE_NAME, SCHEMA_VERSION_STORAGE_KEY,
+    STORAGE_SCHEMA_VERSION,
 };
 use crate::{
-    components::fetcher::{FetchItem, FetchResponse},
+    components::{
+        fetcher::{FetchItem, FetchResponse},
+        gossiper::GossipItem,
+    },
     effect::{
         requests::{MarkBlockCompletedRequest, StorageRequest},
         Multiple,
@@ -35,9 +46,9 @@ use crate::{
     types::{
         sync_leap_validation_metadata::SyncLeapValidationMetaData, AvailableBlockRange, Block,
         BlockHash, BlockHashAndHeight, BlockHashHeightAndEra, BlockHeader, BlockHeaderWithMetadata,
-        BlockSignatures, Chainspec, ChainspecRawBytes, Deploy, DeployHash, DeployMetadata,
-        DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature, LegacyDeploy,
-        SyncLeapIdentifier, TestBlockBuilder,
+        BlockSignatures, Chainspec, ChainspecRawBytes, Deploy, DeployExecutionResult, DeployHash,
+        DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
+        LegacyDeploy, SyncLeapIdentifier, TestBlockBuilder,
     },
     utils::{Loadable, WithDir},
 };
@@ -1422,6 +1433,40 @@ fn persist_blocks_deploys_and_deploy_metadata_across_instantiations() {
     );
 }
 
+#[test]
+fn should_fail_to_open_storage_with_different_schema_version() {
+    let harness = ComponentHarness::default();
+    let storage = storage_fixture(&harness);
+
+    // Tag the stored data with an older schema version.
+    let older_version = STORAGE_SCHEMA_VERSION - 1;
+    storage
+        .write_state_store(
+            Cow::Borrowed(SCHEMA_VERSION_STORAGE_KEY),
+            &older_version.to_bytes().unwrap(),
+        )
+        .unwrap();
+    drop(storage);
+
+    let cfg = new_config(&harness);
+    let result = Storage::new(
+        &WithDir::new(harness.tmp.path(), cfg),
+        None,
+        ProtocolVersion::from_parts(1, 0, 0),
+        EraId::default(),
+        "test",
+        MAX_TTL.into(),
+        RECENT_ERA_COUNT,
+        None,
+        false,
+    );
+    assert!(matches!(
+        result,
+        Err(FatalStorageError::VersionMismatch { found, expected })
+            if found == older_version && expected == STORAGE_SCHEMA_VERSION
+    ));
+}
+
 #[test]
 fn should_hard_reset() {
     let blocks_count = 8_usize;
@@ -2235,3 +2280,210 @@ fn should_initialize_block_metadata_db() {
     assert_signatures(&storage, *block_3.hash(), vec![]);
     assert_signatures(&storage, *block_4.hash(), vec![]);
 }
+
+#[test]
+fn should_fail_to_write_to_readonly_storage() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("readonly.lmdb");
+
+    // Create the environment, then reopen it read-only.
+    drop(
+        Environment::new()
+            .set_flags(EnvironmentFlags::NO_SUB_DIR)
+            .open(&path)
+            .unwrap(),
+    );
+    let env = Environment::new()
+        .set_flags(EnvironmentFlags::NO_SUB_DIR | EnvironmentFlags::READ_ONLY)
+        .open(&path)
+        .unwrap();
+
+    // Reading is fine, but writing should be reported as a read-only error.
+    assert!(env.begin_ro_txn().is_ok());
+    match begin_rw_txn_for(&env, "put deploy") {
+        Err(FatalStorageError::Readonly { operation }) => assert_eq!(operation, "put deploy"),
+        Err(error) => panic!("unexpected error: {}", error),
+        Ok(_) => panic!("should not be able to write to read-only storage"),
+    }
+}
+
+#[test]
+fn should_round_trip_gossiped_block_header() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let block = Block::random(&mut harness.rng);
+    let block_header = block.header().clone();
+    let item_id = block_header.gossip_id();
+    assert_eq!(item_id, *block.hash());
+
+    let get_block_header = |harness: &mut ComponentHarness<UnitTestEvent>,
+                            storage: &mut Storage| {
+        harness.send_request(storage, move |responder| {
+            StorageRequest::GetBlockHeader {
+                block_hash: item_id,
+                only_from_available_block_range: false,
+                responder,
+            }
+            .into()
+        })
+    };
+
+    // The header isn't available before it is stored.
+    assert!(get_block_header(&mut harness, &mut storage).is_none());
+
+    // Store the header alone, as a header gossiper would on receiving it.
+    let was_stored = harness.send_request(&mut storage, |responder| {
+        StorageRequest::PutBlockHeader {
+            block_header: Box::new(block_header.clone()),
+            responder,
+        }
+        .into()
+    });
+    assert!(was_stored);
+
+    // The header retrieved under the gossiped ID should be the one stored.
+    let stored_header = get_block_header(&mut harness, &mut storage).expect("should get header");
+    assert_eq!(stored_header, block_header);
+    assert_eq!(stored_header.gossip_id(), item_id);
+    assert!(harness.is_idle());
+}
+
+#[test]
+fn should_map_out_of_disk_space_to_disk_full() {
+    let lmdb_error = lmdb::Error::Other(libc::ENOSPC);
+    let error = FatalStorageError::from(lmdb_error);
+    assert!(matches!(error, FatalStorageError::DiskFull(_)));
+    assert!(error
+        .to_string()
+        .starts_with("disk full, free space or increase storage"));
+
+    // The mapping also applies to errors which have already been classified by `lmdb_ext`.
+    let error = FatalStorageError::from(LmdbExtError::from(lmdb_error));
+    assert!(matches!(error, FatalStorageError::DiskFull(_)));
+
+    // Exhausting the LMDB map size is a distinct, non-disk-full error.
+    let error = FatalStorageError::from(lmdb::Error::MapFull);
+    assert!(matches!(
+        error,
+        FatalStorageError::InternalStorage(LmdbExtError::ResourceExhausted(_))
+    ));
+}
+
+#[test]
+fn should_map_lmdb_version_mismatch_on_open_to_lmdb_version_mismatch() {
+    // `MDB_VERSION_MISMATCH`, returned when opening an environment created by an incompatible LMDB
+    // library version.
+    const MDB_VERSION_MISMATCH: libc::c_int = -30794;
+    let lmdb_error = lmdb::Error::from_err_code(MDB_VERSION_MISMATCH);
+    assert_eq!(lmdb_error, lmdb::Error::VersionMismatch);
+
+    let error = FatalStorageError::from_open_error(lmdb_error);
+    assert!(matches!(
+        error,
+        FatalStorageError::LmdbVersionMismatch(lmdb::Error::VersionMismatch)
+    ));
+    assert!(error
+        .to_string()
+        .starts_with("LMDB environment was created by an incompatible LMDB library version"));
+    assert!(!error.is_retryable());
+
+    // Other errors on opening are classified as usual.
+    let error = FatalStorageError::from_open_error(lmdb::Error::Other(libc::ENOSPC));
+    assert!(matches!(error, FatalStorageError::DiskFull(_)));
+    let error = FatalStorageError::from_open_error(lmdb::Error::Corrupted);
+    assert!(matches!(
+        error,
+        FatalStorageError::InternalStorage(LmdbExtError::LmdbCorrupted(_))
+    ));
+}
+
+#[test]
+fn should_classify_retryable_errors() {
+    // Transient resource exhaustion is retryable.
+    for lmdb_error in [lmdb::Error::ReadersFull, lmdb::Error::MapResized] {
+        assert!(FatalStorageError::from(lmdb_error).is_retryable());
+    }
+
+    // Exhaustion requiring reconfiguration, corruption and other LMDB errors are not.
+    for lmdb_error in [
+        lmdb::Error::MapFull,
+        lmdb::Error::TxnFull,
+        lmdb::Error::Corrupted,
+        lmdb::Error::VersionMismatch,
+        lmdb::Error::Panic,
+        lmdb::Error::Other(libc::ENOSPC),
+        lmdb::Error::Other(libc::EACCES),
+    ] {
+        assert!(!FatalStorageError::from(lmdb_error).is_retryable());
+    }
+    let data_corrupted =
+        LmdbExtError::DataCorrupted(Box::new(BytesreprError(bytesrepr::Error::EarlyEndOfStream)));
+    assert!(!FatalStorageError::from(data_corrupted).is_retryable());
+
+    // Neither are errors not arising from LMDB itself.
+    let mut rng = crate::new_rng();
+    let block_hash = BlockHash::random(&mut rng);
+    let fatal_errors = [
+        FatalStorageError::VersionMismatch {
+            found: 1,
+            expected: 2,
+        },
+        FatalStorageError::Readonly {
+            operation: "put deploy".to_string(),
+        },
+        FatalStorageError::from_write_error(lmdb::Error::Other(libc::EACCES), "put deploy"),
+        FatalStorageError::FailedToOverwriteBlock,
+        FatalStorageError::DuplicateBlockIndex {
+            height: 1,
+            first: block_hash,
+            second: BlockHash::random(&mut rng),
+        },
+        FatalStorageError::CorruptedBlockSignatureIndex {
+            raw_key: vec![1],
+            block_hash_bytes: vec![2],
+        },
+        FatalStorageError::UnexpectedDeserializationFailure(bytesrepr::Error::Formatting),
+        FatalStorageError::MissingStorageFiles {
+            missing_files: vec![],
+        },
+    ];
+    for error in fatal_errors {
+        assert!(!error.is_retryable(), "{} should not be retryable", error);
+    }
+}
+
+#[test]
+fn should_round_trip_gossiped_execution_result() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploy = Arc::new(Deploy::random(&mut harness.rng));
+    let deploy_hash = *deploy.hash();
+    let block_hash = BlockHash::random(&mut harness.rng);
+    let raw_execution_result: ExecutionResult = harness.rng.gen();
+    let execution_result =
+        DeployExecutionResult::new(deploy_hash, block_hash, raw_execution_result.clone());
+
+    // The ID is the deploy hash, and is unaffected by serialization.
+    assert_eq!(execution_result.gossip_id(), deploy_hash);
+    let serialized = bincode::serialize(&execution_result).unwrap();
+    let deserialized: DeployExecutionResult = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(deserialized.gossip_id(), execution_result.gossip_id());
+
+    // Nothing can be retrieved before the execution result is stored.
+    assert!(put_deploy(&mut harness, &mut storage, Arc::clone(&deploy)));
+    let (_, metadata) =
+        get_naive_deploy_and_metadata(&mut harness, &mut storage, deploy_hash).unwrap();
+    assert!(DeployExecutionResult::from_metadata(deploy_hash, metadata).is_none());
+
+    let execution_results = iter::once((deploy_hash, raw_execution_result)).collect();
+    put_execution_results(&mut harness, &mut storage, block_hash, execution_results);
+
+    let (_, metadata) =
+        get_naive_deploy_and_metadata(&mut harness, &mut storage, deploy_hash).unwrap();
+    let retrieved = DeployExecutionResult::from_metadata(deploy_hash, metadata)
+        .expect("should have execution result");
+    assert_eq!(retrieved, execution_result);
+    assert_eq!(retrieved.gossip_id(), deploy_hash);
+}
@@ -742,6 +742,26 @@ impl<REv> EffectBuilder<REv> {
         count: usize,
         exclude: HashSet<NodeId>,
     ) -> HashSet<NodeId>
+    where
+        REv: From<NetworkRequest<P>>,
+        P: Send,
+    {
+        self.try_gossip_message(payload, gossip_target, count, exclude)
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Gossips a network message, as per `gossip_message`.
+    ///
+    /// Returns `None` if the networking component did not respond, e.g. because it is shutting
+    /// down, as opposed to `Some` empty set if there were no suitable peers.
+    pub(crate) async fn try_gossip_message<P>(
+        self,
+        payload: P,
+        gossip_target: GossipTarget,
+        count: usize,
+        exclude: HashSet<NodeId>,
+    ) -> Option<HashSet<NodeId>>
     where
         REv: From<NetworkRequest<P>>,
         P: Send,
@@ -757,7 +777,6 @@ impl<REv> EffectBuilder<REv> {
             QueueKind::Network,
         )
         .await
-        .unwrap_or_default()
     }
 
     /// Gets a structure describing the current network status.
@@ -1454,6 +1473,30 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Flushes all writes committed to storage to disk.
+    pub(crate) async fn sync_storage_to_disk(self)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::SyncToDisk { responder },
+            QueueKind::ToStorage,
+        )
+        .await
+    }
+
+    /// Checks whether storage accepts writes, returning `false` if it was opened read-only.
+    pub(crate) async fn is_storage_writable(self) -> bool
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::IsWritable { responder },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     /// Puts the given deploy into the deploy store.
     pub(crate) async fn put_deploy_to_storage(self, deploy: Arc<Deploy>) -> bool
     where
@@ -1526,6 +1569,21 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the requested deploys from storage by ID, in a single storage transaction.
+    pub(crate) async fn get_stored_deploys(self, deploy_ids: Vec<DeployId>) -> Vec<Option<Deploy>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetDeploysById {
+                deploy_ids,
+                responder,
+            },
+            QueueKind::FromStorage,
+        )
+        .await
+    }
+
     pub(crate) async fn is_deploy_stored(self, deploy_id: DeployId) -> bool
     where
         REv: From<StorageRequest>,
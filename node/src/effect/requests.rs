@@ -377,6 +377,12 @@ pub(crate) enum StorageRequest {
         deploy_id: DeployId,
         responder: Responder<Option<Deploy>>,
     },
+    /// Retrieve deploys with given IDs in a single storage transaction.
+    GetDeploysById {
+        deploy_ids: Vec<DeployId>,
+        /// Responder to call with the results, in the same order as `deploy_ids`.
+        responder: Responder<Vec<Option<Deploy>>>,
+    },
     IsDeployStored {
         deploy_id: DeployId,
         responder: Responder<bool>,
@@ -498,6 +504,16 @@ pub(crate) enum StorageRequest {
     },
     /// Retrieve the height of the final block of the previous protocol version, if known.
     GetKeyBlockHeightForActivationPoint { responder: Responder<Option<u64>> },
+    /// Flush all committed writes to disk.
+    SyncToDisk {
+        /// Responder, responded to once the flush completes.
+        responder: Responder<()>,
+    },
+    /// Check whether storage accepts writes, i.e. wasn't opened read-only.
+    IsWritable {
+        /// Responder, responded to with `false` if storage is read-only.
+        responder: Responder<bool>,
+    },
 }
 
 impl Display for StorageRequest {
@@ -546,6 +562,9 @@ impl Display for StorageRequest {
             StorageRequest::GetDeploy { deploy_id, .. } => {
                 write!(formatter, "get deploy {}", deploy_id)
             }
+            StorageRequest::GetDeploysById { deploy_ids, .. } => {
+                write!(formatter, "get {} deploys by id", deploy_ids.len())
+            }
             StorageRequest::IsDeployStored { deploy_id, .. } => {
                 write!(formatter, "is deploy {} stored", deploy_id)
             }
@@ -620,6 +639,8 @@ impl Display for StorageRequest {
                     "get key block height for current activation point"
                 )
             }
+            StorageRequest::SyncToDisk { .. } => write!(formatter, "sync storage to disk"),
+            StorageRequest::IsWritable { .. } => write!(formatter, "check storage is writable"),
         }
     }
 }
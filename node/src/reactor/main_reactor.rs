@@ -18,11 +18,19 @@ mod upgrade_shutdown;
 mod upgrading_instruction;
 mod validate;
 
-use std::{collections::BTreeMap, sync::Arc, time::Instant};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
 
 use datasize::DataSize;
 use memory_metrics::MemoryMetrics;
 use prometheus::Registry;
+use tokio::sync::Semaphore;
 use tracing::{debug, error, info, warn};
 
 use casper_types::{EraId, PublicKey, TimeDiff, Timestamp, U512};
@@ -74,7 +82,7 @@ use crate::{
     },
     types::{
         Block, BlockHash, Chainspec, ChainspecRawBytes, Deploy, FinalitySignature, MetaBlock,
-        MetaBlockState, SyncHandling, TrieOrChunk, ValidatorMatrix,
+        MetaBlockState, NodeId, SyncHandling, TrieOrChunk, ValidatorMatrix,
     },
     utils::{Source, WithDir},
     NodeRng,
@@ -159,6 +167,10 @@ pub(crate) struct MainReactor {
     block_gossiper: Gossiper<{ Block::ID_IS_COMPLETE_ITEM }, Block>,
     finality_signature_gossiper:
         Gossiper<{ FinalitySignature::ID_IS_COMPLETE_ITEM }, FinalitySignature>,
+    /// The number of connected peers, shared with the gossipers so they don't request gossip to
+    /// more peers than exist.
+    #[data_size(skip)] // Never allocates heap data.
+    gossip_peer_count: Arc<AtomicUsize>,
 
     // record retrieval
     sync_leaper: SyncLeaper,
@@ -345,10 +357,24 @@ impl reactor::Reactor for MainReactor {
             ),
 
             // NETWORK CONNECTION AND ORIENTATION
-            MainEvent::Network(event) => reactor::wrap_effects(
-                MainEvent::Network,
-                self.net.handle_event(effect_builder, rng, event),
-            ),
+            MainEvent::Network(event) => {
+                let is_connection_change = matches!(
+                    event,
+                    network::Event::IncomingConnection { .. }
+                        | network::Event::IncomingClosed { .. }
+                        | network::Event::OutgoingConnection { .. }
+                        | network::Event::OutgoingDropped { .. }
+                );
+                let effects = reactor::wrap_effects(
+                    MainEvent::Network,
+                    self.net.handle_event(effect_builder, rng, event),
+                );
+                if is_connection_change {
+                    self.gossip_peer_count
+                        .store(self.net.peers().len(), Ordering::Relaxed);
+                }
+                effects
+            }
             MainEvent::NetworkRequest(req) => {
                 let event = MainEvent::Network(network::Event::from(req));
                 self.dispatch_event(effect_builder, rng, event)
@@ -1067,6 +1093,7 @@ impl reactor::Reactor for MainReactor {
             chainspec.core_config.fee_handling,
         )?;
 
+        let our_node_id = NodeId::from(&network_identity);
         let network = Network::new(
             config.network.clone(),
             network_identity,
@@ -1076,11 +1103,47 @@ impl reactor::Reactor for MainReactor {
             validator_matrix.clone(),
         )?;
 
-        let address_gossiper = Gossiper::<{ GossipedAddress::ID_IS_COMPLETE_ITEM }, _>::new(
+        let gossip_peer_count = Arc::new(AtomicUsize::new(0));
+        let gossiper_registry = gossiper::GossiperRegistry::default();
+        let peer_trust = config.gossip.peer_trust();
+        // Storage permits are shared by all gossipers so that none can starve the others.
+        let gossip_storage_permits = match config.gossip.max_concurrent_storage_accesses() {
+            0 => None,
+            permits => Some(Arc::new(Semaphore::new(permits as usize))),
+        };
+        // Gossip is only signed where peers require it, as unsigned gossip can be batched and is
+        // understood by every peer.
+        let gossip_signing_key = config
+            .gossip
+            .require_signed_gossip()
+            .then(|| Arc::clone(&our_secret_key));
+        // Signed gossip is only accepted if signed with the key its sender connected to us with.
+        let peer_consensus_keys = network.peer_consensus_keys();
+        let peer_signing_key = || -> gossiper::PeerSigningKey {
+            let peer_consensus_keys = peer_consensus_keys.clone();
+            Box::new(move |peer| peer_consensus_keys.get(peer))
+        };
+        let mut address_gossiper = Gossiper::<{ GossipedAddress::ID_IS_COMPLETE_ITEM }, _>::new(
             "address_gossiper",
             config.gossip,
             registry,
         )?;
+        address_gossiper.set_peer_count_hint(Arc::clone(&gossip_peer_count));
+        address_gossiper.register_with(&gossiper_registry);
+        address_gossiper.set_our_id(our_node_id);
+        if let Some(quiet_hours) = config.gossip.quiet_hours() {
+            address_gossiper.set_quiet_hours(quiet_hours);
+        }
+        if peer_trust != gossiper::TrustLevel::Trusted {
+            address_gossiper.set_peer_trust(Box::new(move |_| peer_trust));
+        }
+        if let Some(storage_permits) = &gossip_storage_permits {
+            address_gossiper.set_storage_permits(Arc::clone(storage_permits));
+        }
+        if let Some(secret_key) = &gossip_signing_key {
+            address_gossiper.set_signing_key(Arc::clone(secret_key));
+        }
+        address_gossiper.set_peer_signing_key(peer_signing_key());
 
         let rpc_server = RpcServer::new(
             config.rpc_server.clone(),
@@ -1100,8 +1163,10 @@ impl reactor::Reactor for MainReactor {
             storage.root_path().to_path_buf(),
             protocol_version,
         );
-        let diagnostics_port =
-            DiagnosticsPort::new(WithDir::new(&root_dir, config.diagnostics_port));
+        let diagnostics_port = DiagnosticsPort::new(
+            WithDir::new(&root_dir, config.diagnostics_port),
+            gossiper_registry,
+        );
         let shutdown_trigger = ShutdownTrigger::new();
 
         // local / remote data management
@@ -1109,22 +1174,70 @@ impl reactor::Reactor for MainReactor {
         let fetchers = Fetchers::new(&config.fetcher, registry)?;
 
         // gossipers
-        let block_gossiper = Gossiper::<{ Block::ID_IS_COMPLETE_ITEM }, _>::new(
+        let mut block_gossiper = Gossiper::<{ Block::ID_IS_COMPLETE_ITEM }, _>::new(
             "block_gossiper",
             config.gossip,
             registry,
         )?;
-        let deploy_gossiper = Gossiper::<{ Deploy::ID_IS_COMPLETE_ITEM }, _>::new(
+        block_gossiper.set_peer_count_hint(Arc::clone(&gossip_peer_count));
+        block_gossiper.register_with(&gossiper_registry);
+        block_gossiper.set_our_id(our_node_id);
+        if let Some(quiet_hours) = config.gossip.quiet_hours() {
+            block_gossiper.set_quiet_hours(quiet_hours);
+        }
+        if peer_trust != gossiper::TrustLevel::Trusted {
+            block_gossiper.set_peer_trust(Box::new(move |_| peer_trust));
+        }
+        if let Some(storage_permits) = &gossip_storage_permits {
+            block_gossiper.set_storage_permits(Arc::clone(storage_permits));
+        }
+        if let Some(secret_key) = &gossip_signing_key {
+            block_gossiper.set_signing_key(Arc::clone(secret_key));
+        }
+        block_gossiper.set_peer_signing_key(peer_signing_key());
+        let mut deploy_gossiper = Gossiper::<{ Deploy::ID_IS_COMPLETE_ITEM }, _>::new(
             "deploy_gossiper",
             config.gossip,
             registry,
         )?;
-        let finality_signature_gossiper =
+        deploy_gossiper.set_peer_count_hint(Arc::clone(&gossip_peer_count));
+        deploy_gossiper.register_with(&gossiper_registry);
+        deploy_gossiper.set_our_id(our_node_id);
+        if let Some(quiet_hours) = config.gossip.quiet_hours() {
+            deploy_gossiper.set_quiet_hours(quiet_hours);
+        }
+        if peer_trust != gossiper::TrustLevel::Trusted {
+            deploy_gossiper.set_peer_trust(Box::new(move |_| peer_trust));
+        }
+        if let Some(storage_permits) = &gossip_storage_permits {
+            deploy_gossiper.set_storage_permits(Arc::clone(storage_permits));
+        }
+        if let Some(secret_key) = &gossip_signing_key {
+            deploy_gossiper.set_signing_key(Arc::clone(secret_key));
+        }
+        deploy_gossiper.set_peer_signing_key(peer_signing_key());
+        let mut finality_signature_gossiper =
             Gossiper::<{ FinalitySignature::ID_IS_COMPLETE_ITEM }, _>::new(
                 "finality_signature_gossiper",
                 config.gossip,
                 registry,
             )?;
+        finality_signature_gossiper.set_peer_count_hint(Arc::clone(&gossip_peer_count));
+        finality_signature_gossiper.register_with(&gossiper_registry);
+        finality_signature_gossiper.set_our_id(our_node_id);
+        if let Some(quiet_hours) = config.gossip.quiet_hours() {
+            finality_signature_gossiper.set_quiet_hours(quiet_hours);
+        }
+        if peer_trust != gossiper::TrustLevel::Trusted {
+            finality_signature_gossiper.set_peer_trust(Box::new(move |_| peer_trust));
+        }
+        if let Some(storage_permits) = &gossip_storage_permits {
+            finality_signature_gossiper.set_storage_permits(Arc::clone(storage_permits));
+        }
+        if let Some(secret_key) = &gossip_signing_key {
+            finality_signature_gossiper.set_signing_key(Arc::clone(secret_key));
+        }
+        finality_signature_gossiper.set_peer_signing_key(peer_signing_key());
 
         // consensus
         let consensus = EraSupervisor::new(
@@ -1179,6 +1292,7 @@ impl reactor::Reactor for MainReactor {
             block_gossiper,
             deploy_gossiper,
             finality_signature_gossiper,
+            gossip_peer_count,
             sync_leaper,
             deploy_buffer,
             consensus,
@@ -1213,16 +1327,37 @@ impl reactor::Reactor for MainReactor {
         // shut down immediately for upgrade.
         let should_upgrade_immediately = reactor.upgrade_watcher.next_upgrade_activation_point()
             == Some(reactor.chainspec.protocol_config.activation_point.era_id());
-        let effects = if should_upgrade_immediately {
-            info!("MainReactor: immediate shutdown for upgrade");
-            effect_builder
-                .immediately()
-                .event(|()| MainEvent::ControlAnnouncement(ControlAnnouncement::ShutdownForUpgrade))
-        } else {
-            effect_builder
-                .immediately()
-                .event(|()| MainEvent::ReactorCrank)
-        };
+        let effects =
+            if should_upgrade_immediately {
+                info!("MainReactor: immediate shutdown for upgrade");
+                effect_builder.immediately().event(|()| {
+                    MainEvent::ControlAnnouncement(ControlAnnouncement::ShutdownForUpgrade)
+                })
+            } else {
+                let mut effects = effect_builder
+                    .immediately()
+                    .event(|()| MainEvent::ReactorCrank);
+                // Each gossiper reschedules its own housekeeping once the first round has run.
+                effects.extend(
+                    effect_builder
+                        .immediately()
+                        .event(|()| MainEvent::DeployGossiper(gossiper::Event::Housekeeping)),
+                );
+                effects.extend(
+                    effect_builder
+                        .immediately()
+                        .event(|()| MainEvent::BlockGossiper(gossiper::Event::Housekeeping)),
+                );
+                effects.extend(effect_builder.immediately().event(|()| {
+                    MainEvent::FinalitySignatureGossiper(gossiper::Event::Housekeeping)
+                }));
+                effects.extend(
+                    effect_builder
+                        .immediately()
+                        .event(|()| MainEvent::AddressGossiper(gossiper::Event::Housekeeping)),
+                );
+                effects
+            };
         Ok((reactor, effects))
     }
 
@@ -69,6 +69,7 @@ impl FakeDeployAcceptor {
             .event(move |is_new| Event::PutToStorageResult {
                 event_metadata,
                 is_new,
+                put_retries: 0,
                 verification_start_timestamp,
             })
     }
@@ -47,8 +47,8 @@ pub use deploy::{
     DeployHeader, DeployOrTransferHash, ExcessiveSizeError as ExcessiveSizeDeployError,
 };
 pub(crate) use deploy::{
-    DeployFootprint, DeployHashWithApprovals, DeployId, DeployMetadata, DeployMetadataExt,
-    DeployWithFinalizedApprovals, FinalizedApprovals, LegacyDeploy,
+    DeployExecutionResult, DeployFootprint, DeployHashWithApprovals, DeployId, DeployMetadata,
+    DeployMetadataExt, DeployWithFinalizedApprovals, FinalizedApprovals, LegacyDeploy,
 };
 pub use error::BlockValidationError;
 pub use exit_code::ExitCode;
@@ -43,7 +43,7 @@ use crate::{
         block_synchronizer::ExecutionResultsChecksum,
         consensus,
         fetcher::{EmptyValidationMetadata, FetchItem, Tag},
-        gossiper::{GossipItem, LargeGossipItem},
+        gossiper::{GossipItem, LargeGossipItem, Priority},
     },
     effect::GossipTarget,
     rpcs::docs::DocExample,
@@ -1795,6 +1795,7 @@ impl FetchItem for Block {
 
 impl GossipItem for Block {
     type Id = BlockHash;
+    type StorageKey = BlockHash;
 
     const ID_IS_COMPLETE_ITEM: bool = false;
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = true;
@@ -1803,6 +1804,10 @@ impl GossipItem for Block {
         *self.hash()
     }
 
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        *item_id
+    }
+
     fn gossip_target(&self) -> GossipTarget {
         // Validators make their own blocks thus we only gossip blocks to non validators.
         GossipTarget::Mixed(self.header.era_id)
@@ -1811,6 +1816,29 @@ impl GossipItem for Block {
 
 impl LargeGossipItem for Block {}
 
+impl GossipItem for BlockHeader {
+    type Id = BlockHash;
+    type StorageKey = BlockHash;
+
+    const ID_IS_COMPLETE_ITEM: bool = false;
+    const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = true;
+
+    fn gossip_id(&self) -> Self::Id {
+        self.block_hash()
+    }
+
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        *item_id
+    }
+
+    fn gossip_target(&self) -> GossipTarget {
+        // As for blocks, validators create their own, so we only gossip headers to non validators.
+        GossipTarget::Mixed(self.era_id)
+    }
+}
+
+impl LargeGossipItem for BlockHeader {}
+
 /// A wrapper around `Block` for the purposes of fetching blocks by height in linear chain.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BlockWithMetadata {
@@ -2583,9 +2611,12 @@ impl FetchItem for FinalitySignature {
 
 impl GossipItem for FinalitySignature {
     type Id = Box<FinalitySignatureId>;
+    type StorageKey = Box<FinalitySignatureId>;
 
     const ID_IS_COMPLETE_ITEM: bool = false;
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = true;
+    // Finality signatures gate block finality, so are gossiped ahead of other items.
+    const PRIORITY: Priority = Priority::High;
 
     fn gossip_id(&self) -> Self::Id {
         // Note: Unfortunately this is somewhat of a mismatch, as finality signature IDs are fairly
@@ -2598,6 +2629,10 @@ impl GossipItem for FinalitySignature {
         })
     }
 
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        item_id.clone()
+    }
+
     fn gossip_target(&self) -> GossipTarget {
         GossipTarget::Mixed(self.era_id)
     }
@@ -9,6 +9,7 @@ mod deploy_header;
 mod deploy_or_transfer_hash;
 mod deploy_with_finalized_approvals;
 mod error;
+mod execution_result;
 mod finalized_approvals;
 mod footprint;
 mod id;
@@ -66,6 +67,7 @@ pub use deploy_header::DeployHeader;
 pub use deploy_or_transfer_hash::DeployOrTransferHash;
 pub(crate) use deploy_with_finalized_approvals::DeployWithFinalizedApprovals;
 pub use error::{DeployConfigurationFailure, Error as DeployError, ExcessiveSizeError};
+pub(crate) use execution_result::DeployExecutionResult;
 pub(crate) use finalized_approvals::FinalizedApprovals;
 pub(crate) use footprint::Footprint as DeployFootprint;
 pub use id::Id as DeployId;
@@ -550,6 +552,7 @@ impl FetchItem for Deploy {
 
 impl GossipItem for Deploy {
     type Id = DeployId;
+    type StorageKey = DeployId;
 
     const ID_IS_COMPLETE_ITEM: bool = false;
     const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
@@ -563,9 +566,17 @@ impl GossipItem for Deploy {
         DeployId::new(deploy_hash, approvals_hash)
     }
 
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        *item_id
+    }
+
     fn gossip_target(&self) -> GossipTarget {
         GossipTarget::All
     }
+
+    fn is_expired(&self, now: Timestamp) -> bool {
+        self.header().expired(now)
+    }
 }
 
 impl LargeGossipItem for Deploy {}
@@ -0,0 +1,90 @@
+use std::fmt::{self, Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+
+use casper_types::ExecutionResult;
+
+use super::{DeployHash, DeployMetadataExt};
+use crate::{
+    components::gossiper::{GossipItem, LargeGossipItem},
+    effect::GossipTarget,
+    types::BlockHash,
+};
+
+/// The result of executing a deploy in a given block, gossiped under the deploy's hash.
+///
+/// Execution results can be large, so are gossiped as large items, i.e. only by ID until a peer
+/// requests the full result.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub(crate) struct DeployExecutionResult {
+    deploy_hash: DeployHash,
+    block_hash: BlockHash,
+    execution_result: ExecutionResult,
+}
+
+impl DeployExecutionResult {
+    pub(crate) fn new(
+        deploy_hash: DeployHash,
+        block_hash: BlockHash,
+        execution_result: ExecutionResult,
+    ) -> Self {
+        DeployExecutionResult {
+            deploy_hash,
+            block_hash,
+            execution_result,
+        }
+    }
+
+    /// Constructs the execution result of the given deploy from its stored metadata.
+    ///
+    /// Returns `None` if the metadata holds no execution results.  If the deploy was executed in
+    /// more than one block, the result from the block with the lowest hash is chosen so that all
+    /// nodes agree on the result for a given deploy hash.
+    pub(crate) fn from_metadata(
+        deploy_hash: DeployHash,
+        metadata: DeployMetadataExt,
+    ) -> Option<Self> {
+        match metadata {
+            DeployMetadataExt::Metadata(metadata) => metadata
+                .execution_results
+                .into_iter()
+                .min_by_key(|(block_hash, _)| *block_hash)
+                .map(|(block_hash, execution_result)| {
+                    DeployExecutionResult::new(deploy_hash, block_hash, execution_result)
+                }),
+            DeployMetadataExt::BlockInfo(_) | DeployMetadataExt::Empty => None,
+        }
+    }
+}
+
+impl Display for DeployExecutionResult {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "execution result of deploy {} in block {}",
+            self.deploy_hash, self.block_hash
+        )
+    }
+}
+
+impl GossipItem for DeployExecutionResult {
+    type Id = DeployHash;
+    type StorageKey = DeployHash;
+
+    const ID_IS_COMPLETE_ITEM: bool = false;
+    const REQUIRES_GOSSIP_RECEIVED_ANNOUNCEMENT: bool = false;
+
+    fn gossip_id(&self) -> Self::Id {
+        self.deploy_hash
+    }
+
+    fn storage_key(item_id: &Self::Id) -> Self::StorageKey {
+        *item_id
+    }
+
+    fn gossip_target(&self) -> GossipTarget {
+        GossipTarget::All
+    }
+}
+
+impl LargeGossipItem for DeployExecutionResult {}